@@ -57,7 +57,9 @@ use syn::{parse_macro_input, DeriveInput};
 /// see [TardisActiveModel::create_table_statement](https://docs.rs/tardis/latest/tardis/db/reldb_client/trait.TardisActiveModel.html#method.create_table_statement).
 ///
 /// According to tardis_entity automatically generates `tardis_create_table_statement(db: DbBackend)`
-/// method, you can be directly called in the `TardisActiveModel::create_table_statement` method. \
+/// method, you can be directly called in the `TardisActiveModel::create_table_statement` method,
+/// and `tardis_create_compatible_table_statement(db: DbBackend, compatible_type: CompatibleType)` method
+/// for the `TardisActiveModel::create_compatible_table_statement` method. \
 /// example see [macros_examples::example_for_derive_create_tabled].
 ///
 /// ## tardis_entity attribute
@@ -150,12 +152,123 @@ pub fn tardis_create_index(input: TokenStream) -> TokenStream {
 /// The functionality of `TardisCreateEntity` is equivalent to `TardisCreateIndex` combined with `TardisCreateTable`.
 /// Additionally, it introduces a new attribute called fill_ctx, and automatically implements `ActiveModelBehavior`. \
 /// see [TardisCreateIndex] and [TardisCreateTable]
+///
+/// ## tardis_entity attribute of the struct
+///
+/// - `tenant_scoped`: The rows are isolated by the field with `#[fill_ctx(fill = "own_paths")]`, the table is registered by the derive itself,
+///   see [TardisActiveModel::own_paths_column](https://docs.rs/tardis/latest/tardis/db/reldb_client/trait.TardisActiveModel.html#method.own_paths_column).
+/// - `version = "<field>"`: The field is the version of the optimistic locking, `update_one` updates the record only if the version is unchanged and increments it,
+///   see [TardisActiveModel::version_column](https://docs.rs/tardis/latest/tardis/db/reldb_client/trait.TardisActiveModel.html#method.version_column).
+/// - `audited`: The changes are recorded in the `tardis_audit_record` table, the table is registered by the derive itself,
+///   see [TardisActiveModel::audited](https://docs.rs/tardis/latest/tardis/db/reldb_client/trait.TardisActiveModel.html#method.audited).
+/// - `dto = "<prefix>"`: Generates the `<prefix>AddReq` , `<prefix>ModifyReq` and `<prefix>DetailResp` structs deriving
+///   `poem_openapi::Object` and serde, `<prefix>DetailResp` also derives `FromQueryResult` ,
+///   and the requests can be converted into the `ActiveModel` . The `web-server` feature of tardis is required.
+///   - `<prefix>AddReq` has the fields except the auto increment primary key and the `fill_ctx` fields.
+///   - `<prefix>ModifyReq` has the fields except the primary key and the `fill_ctx` fields, all of them are optional,
+///     only the present ones are set to the `ActiveModel` . The `Option<T>` fields are `poem_openapi::types::MaybeUndefined<T>` ,
+///     so `null` clears the column while an absent field keeps it.
+///   - `<prefix>DetailResp` has all the fields.
+///
+///   The fields with `#[sea_orm(ignore)]` are excluded, and the doc comments are carried over.
+///
+/// ## dto attribute of the field
+///
+/// - `skip`: Excluded from all the DTOs, or `skip_add` , `skip_modify` , `skip_detail` for one of them.
+/// - `rename = "<name>"`: Name of the field in the JSON of the DTOs.
+/// - `validator(...)`: Validators of the field in the requests, see `poem_openapi` .
+///
+/// ```ignore
+/// #[derive(Clone, Debug, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+/// #[sea_orm(table_name = "todos")]
+/// #[tardis_entity(dto = "Todo")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     #[dto(validator(min_length = "2", max_length = "255"))]
+///     pub code: String,
+///     #[dto(rename = "desc")]
+///     pub description: String,
+///     pub done: bool,
+/// }
+///
+/// let todo_id = TardisFuns::reldb().conn().insert_one(todos::ActiveModel::from(add_req.0), &ctx.0).await?.last_insert_id;
+/// ```
+///
+/// ```ignore
+/// #[derive(Clone, Debug, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+/// #[sea_orm(table_name = "examples")]
+/// #[tardis_entity(tenant_scoped, version = "version")]
+/// pub struct Model {
+///     #[sea_orm(primary_key, auto_increment = false)]
+///     pub id: String,
+///     #[fill_ctx(fill = "own_paths")]
+///     pub own_paths: String,
+///     pub version: i32,
+/// }
+/// ```
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
-#[proc_macro_derive(TardisCreateEntity, attributes(tardis_entity, index, fill_ctx))]
+#[proc_macro_derive(TardisCreateEntity, attributes(tardis_entity, index, fill_ctx, dto))]
 pub fn tardis_create_entity(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, .. } = parse_macro_input!(input as DeriveInput);
+    let DeriveInput { ident, data, attrs, .. } = parse_macro_input!(input as DeriveInput);
 
-    match tardis_create_entity::create_entity(ident, data) {
+    match tardis_create_entity::create_entity(ident, data, attrs) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+/// # TardisCrud
+/// Generates the CRUD service of the entity on top of the DTOs of `#[tardis_entity(dto = "<prefix>")]` , see [TardisCreateEntity].
+///
+/// - `<prefix>FilterReq`: The optional fields marked with `#[tardis_crud(filter)]` .
+/// - `<prefix>Serv`: The async functions `add` , `modify` , `get_by_id` , `paginate` and `delete` ,
+///   each of them takes the `TardisRelDBlConnection` and the `TardisContext` of the request.
+///   The `fill_ctx` fields are filled by the context, and the records are limited to the `own_paths` of the context
+///   by the field with `#[fill_ctx(fill = "own_paths")]` , which is required unless `unscoped` .
+///   `modify` fails with `400` if no field is present, and with `404` if no record within the `own_paths` is updated.
+///
+/// The entity must have a single primary key.
+///
+/// ## tardis_crud attribute of the struct
+///
+/// - `delete = "soft" | "hard"`: The records are soft deleted into the `tardis_del_record` table (default) or deleted,
+///   both are recorded if the entity is `audited` .
+/// - `unscoped`: The records are shared among all the contexts, without the field with `#[fill_ctx(fill = "own_paths")]` .
+/// - `api = "<path>"`: Generates the `<prefix>Api` implementing `poem_openapi::OpenApi` with the prefix path,
+///   `POST /` , `PUT /:id` , `GET /:id` , `GET /` (paginate, with the filters as the query parameters) and `DELETE /:id` .
+///   The modifications are executed in a transaction.
+/// - `module = "<code>"`: The module of the database used by the `<prefix>Api` , the default database if not specified.
+///
+/// ## tardis_crud attribute of the field
+///
+/// - `filter` or `filter = "eq"`: The records are filtered by equality.
+/// - `filter = "like"`: The records are filtered by containing the value, the wildcards `%` and `_` of which are escaped.
+///
+/// ```ignore
+/// #[derive(Clone, Debug, DeriveEntityModel, TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation)]
+/// #[sea_orm(table_name = "todos")]
+/// #[tardis_entity(dto = "Todo")]
+/// #[tardis_crud(delete = "hard", api = "/todo")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     #[tardis_crud(filter = "like")]
+///     pub code: String,
+///     #[tardis_crud(filter)]
+///     pub done: bool,
+///     #[fill_ctx(fill = "own_paths")]
+///     pub own_paths: String,
+/// }
+///
+/// let todo_id = TodoServ::add(add_req, &conn, &ctx).await?;
+/// let todos = TodoServ::paginate(&TodoFilterReq { done: Some(false), ..Default::default() }, 1, 10, &conn, &ctx).await?;
+/// ```
+#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
+#[proc_macro_derive(TardisCrud, attributes(tardis_crud))]
+pub fn tardis_crud(input: TokenStream) -> TokenStream {
+    let DeriveInput { ident, data, attrs, .. } = parse_macro_input!(input as DeriveInput);
+
+    match tardis_crud::create_crud(ident, data, attrs) {
         Ok(stream) => stream.into(),
         Err(err) => err.to_compile_error().into(),
     }
@@ -188,10 +301,14 @@ pub fn tardis_empty_relation(input: TokenStream) -> TokenStream {
 #[allow(dead_code)]
 pub(crate) mod macro_helpers;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
+mod tardis_create_dto;
+#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
 mod tardis_create_entity;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
 mod tardis_create_index;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
 mod tardis_create_table;
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
+mod tardis_crud;
+#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
 mod tardis_empty_impl;
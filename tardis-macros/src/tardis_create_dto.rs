@@ -0,0 +1,277 @@
+use crate::macro_helpers::helpers::default_doc;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Attribute, Error, Expr, Field, Fields, GenericArgument, LitBool, LitStr, PathArguments, Result, Type};
+
+/// `dto` attribute of a field
+#[derive(Default)]
+struct DtoMeta {
+    skip_add: bool,
+    skip_modify: bool,
+    skip_detail: bool,
+    rename: Option<LitStr>,
+    validator: Option<TokenStream>,
+}
+
+fn parse_dto_meta(field: &Field) -> Result<DtoMeta> {
+    let mut dto_meta = DtoMeta::default();
+    for attr in &field.attrs {
+        if attr.path().is_ident("dto") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    dto_meta.skip_add = true;
+                    dto_meta.skip_modify = true;
+                    dto_meta.skip_detail = true;
+                } else if meta.path.is_ident("skip_add") {
+                    dto_meta.skip_add = true;
+                } else if meta.path.is_ident("skip_modify") {
+                    dto_meta.skip_modify = true;
+                } else if meta.path.is_ident("skip_detail") {
+                    dto_meta.skip_detail = true;
+                } else if meta.path.is_ident("rename") {
+                    dto_meta.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("validator") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    dto_meta.validator = Some(content.parse()?);
+                } else {
+                    return Err(meta.error("unsupported dto attribute, expected `skip` , `skip_add` , `skip_modify` , `skip_detail` , `rename` or `validator`"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(dto_meta)
+}
+
+/// The `sea_orm` attribute of a field, the other keys are ignored
+pub(crate) struct SeaOrmFlags {
+    pub(crate) primary_key: bool,
+    pub(crate) auto_increment: bool,
+    pub(crate) ignore: bool,
+}
+
+pub(crate) fn parse_sea_orm_flags(field: &Field) -> Result<SeaOrmFlags> {
+    let mut flags = SeaOrmFlags {
+        primary_key: false,
+        // the primary key is auto increment by default in sea_orm
+        auto_increment: true,
+        ignore: false,
+    };
+    for attr in &field.attrs {
+        if attr.path().is_ident("sea_orm") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("primary_key") {
+                    flags.primary_key = true;
+                } else if meta.path.is_ident("ignore") {
+                    flags.ignore = true;
+                } else if meta.path.is_ident("auto_increment") {
+                    flags.auto_increment = meta.value()?.parse::<LitBool>()?.value;
+                } else if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<TokenStream>()?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(flags)
+}
+
+/// `T` if the type is `Option<T>`
+pub(crate) fn option_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if segment.ident == "Option" && args.args.len() == 1 {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return Some(inner);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Fields of a DTO, and how they are set to the `ActiveModel`
+struct DtoFields {
+    fields: Punctuated<TokenStream, Comma>,
+    sets: Vec<TokenStream>,
+}
+
+impl DtoFields {
+    fn new() -> Self {
+        Self {
+            fields: Punctuated::new(),
+            sets: Vec::new(),
+        }
+    }
+}
+
+/// Generate the `<prefix>AddReq` , `<prefix>ModifyReq` and `<prefix>DetailResp` structs of the `Model`,
+/// with the conversions of the requests into the `ActiveModel`
+pub(crate) fn create_dto(prefix: &LitStr, fields: &Fields) -> Result<TokenStream> {
+    let prefix_value = prefix.value();
+    if syn::parse_str::<Ident>(&prefix_value).is_err() {
+        return Err(Error::new(prefix.span(), format!("dto prefix `{prefix_value}` is not a valid identifier")));
+    }
+    let add_req = format_ident!("{}AddReq", prefix_value, span = prefix.span());
+    let modify_req = format_ident!("{}ModifyReq", prefix_value, span = prefix.span());
+    let detail_resp = format_ident!("{}DetailResp", prefix_value, span = prefix.span());
+    let mod_name = format_ident!("__tardis_dto_{}", prefix_value.to_lowercase());
+
+    let mut add_fields = DtoFields::new();
+    let mut modify_fields = DtoFields::new();
+    let mut detail_fields: Punctuated<TokenStream, Comma> = Punctuated::new();
+    for field in fields {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let ty = &field.ty;
+        let sea_orm_flags = parse_sea_orm_flags(field)?;
+        if sea_orm_flags.ignore {
+            continue;
+        }
+        let dto_meta = parse_dto_meta(field)?;
+        // the fields filled by the context are not requested
+        let filled = field.attrs.iter().any(|attr| attr.path().is_ident("fill_ctx"));
+        let docs = field.attrs.iter().filter(|attr| attr.path().is_ident("doc")).collect::<Vec<&Attribute>>();
+        let (serde_rename, oai_rename) = match &dto_meta.rename {
+            Some(rename) => (quote! { #[serde(rename = #rename)] }, quote! { rename = #rename, }),
+            None => (TokenStream::new(), TokenStream::new()),
+        };
+        let oai_validator = match &dto_meta.validator {
+            Some(validator) => quote! { validator(#validator), },
+            None => TokenStream::new(),
+        };
+        let req_oai = if dto_meta.rename.is_some() || dto_meta.validator.is_some() {
+            quote! { #[oai(#oai_rename #oai_validator)] }
+        } else {
+            TokenStream::new()
+        };
+
+        if !dto_meta.skip_add && !filled && !(sea_orm_flags.primary_key && sea_orm_flags.auto_increment) {
+            add_fields.fields.push(quote! {
+                #(#docs)*
+                #serde_rename
+                #req_oai
+                pub #ident: #ty
+            });
+            add_fields.sets.push(quote! {
+                active_model.#ident = ::tardis::db::sea_orm::ActiveValue::Set(req.#ident);
+            });
+        }
+        if !dto_meta.skip_modify && !filled && !sea_orm_flags.primary_key {
+            // the nullable columns are cleared by `null` , and kept if absent
+            let (modify_ty, modify_serde, modify_set) = match option_inner(ty) {
+                Some(inner) => (
+                    quote! { ::tardis::web::poem_openapi::types::MaybeUndefined<#inner> },
+                    quote! { #[serde(default, skip_serializing_if = "::tardis::web::poem_openapi::types::MaybeUndefined::is_undefined")] },
+                    quote! {
+                        match req.#ident {
+                            ::tardis::web::poem_openapi::types::MaybeUndefined::Value(value) => {
+                                active_model.#ident = ::tardis::db::sea_orm::ActiveValue::Set(::std::option::Option::Some(value));
+                            }
+                            ::tardis::web::poem_openapi::types::MaybeUndefined::Null => {
+                                active_model.#ident = ::tardis::db::sea_orm::ActiveValue::Set(::std::option::Option::None);
+                            }
+                            ::tardis::web::poem_openapi::types::MaybeUndefined::Undefined => {}
+                        }
+                    },
+                ),
+                None => (
+                    quote! { ::std::option::Option<#ty> },
+                    TokenStream::new(),
+                    quote! {
+                        if let ::std::option::Option::Some(value) = req.#ident {
+                            active_model.#ident = ::tardis::db::sea_orm::ActiveValue::Set(value);
+                        }
+                    },
+                ),
+            };
+            modify_fields.fields.push(quote! {
+                #(#docs)*
+                #serde_rename
+                #modify_serde
+                #req_oai
+                pub #ident: #modify_ty
+            });
+            modify_fields.sets.push(modify_set);
+        }
+        if !dto_meta.skip_detail {
+            let detail_oai = match &dto_meta.rename {
+                Some(rename) => quote! { #[oai(rename = #rename)] },
+                None => TokenStream::new(),
+            };
+            detail_fields.push(quote! {
+                #(#docs)*
+                #serde_rename
+                #detail_oai
+                pub #ident: #ty
+            });
+        }
+    }
+
+    let doc = default_doc();
+    let add_fields_stat = add_fields.fields;
+    let add_sets = add_fields.sets;
+    let modify_fields_stat = modify_fields.fields;
+    let modify_sets = modify_fields.sets;
+    Ok(quote! {
+        #[doc(hidden)]
+        mod #mod_name {
+            #[allow(unused_imports)]
+            use super::*;
+            use ::tardis::db::sea_orm;
+            use ::tardis::web::poem_openapi;
+
+            #doc
+            #[derive(Clone, Debug, poem_openapi::Object, ::tardis::serde::Serialize, ::tardis::serde::Deserialize)]
+            #[serde(crate = "::tardis::serde")]
+            pub struct #add_req {
+                #add_fields_stat
+            }
+
+            #doc
+            #[derive(Clone, Debug, Default, poem_openapi::Object, ::tardis::serde::Serialize, ::tardis::serde::Deserialize)]
+            #[serde(crate = "::tardis::serde")]
+            pub struct #modify_req {
+                #modify_fields_stat
+            }
+
+            #doc
+            #[derive(Clone, Debug, poem_openapi::Object, sea_orm::FromQueryResult, ::tardis::serde::Serialize, ::tardis::serde::Deserialize)]
+            #[serde(crate = "::tardis::serde")]
+            pub struct #detail_resp {
+                #detail_fields
+            }
+
+            #doc
+            impl ::std::convert::From<#add_req> for super::ActiveModel {
+                #[allow(unused_mut, unused_variables)]
+                fn from(req: #add_req) -> Self {
+                    let mut active_model = <super::ActiveModel as ::std::default::Default>::default();
+                    #(#add_sets)*
+                    active_model
+                }
+            }
+
+            #doc
+            impl ::std::convert::From<#modify_req> for super::ActiveModel {
+                #[allow(unused_mut, unused_variables)]
+                fn from(req: #modify_req) -> Self {
+                    let mut active_model = <super::ActiveModel as ::std::default::Default>::default();
+                    #(#modify_sets)*
+                    active_model
+                }
+            }
+        }
+        pub use #mod_name::{#add_req, #detail_resp, #modify_req};
+    })
+}
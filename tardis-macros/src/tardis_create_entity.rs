@@ -1,5 +1,5 @@
-use crate::macro_helpers::helpers::default_doc;
-use crate::{tardis_create_index, tardis_create_table};
+use crate::macro_helpers::helpers::{default_doc, ConvertVariableHelpers};
+use crate::{tardis_create_dto, tardis_create_index, tardis_create_table};
 use darling::{FromField, FromMeta};
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
@@ -7,7 +7,7 @@ use quote::{quote, ToTokens};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Semi;
-use syn::{Data, Error, Fields, Result};
+use syn::{Attribute, Data, Error, Fields, LitStr, Result};
 
 #[derive(FromField, Debug, Clone)]
 #[darling(attributes(fill_ctx))]
@@ -34,7 +34,7 @@ fn true_default() -> bool {
     true
 }
 
-pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
+pub(crate) fn create_entity(ident: Ident, data: Data, attrs: Vec<Attribute>) -> Result<TokenStream> {
     if ident != "Model" {
         panic!("Struct name must be Model");
     }
@@ -42,8 +42,18 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
         Data::Struct(data_struct) => {
             let doc = default_doc();
             let create_table_stat = tardis_create_table::create_table(ident.clone(), data.clone(), None)?;
-            let create_index_stat = tardis_create_index::create_index(ident, data, None)?;
+            let create_index_stat = tardis_create_index::create_index(ident.clone(), data, None)?;
 
+            let entity_attrs = parse_entity_attrs(&attrs)?;
+            let own_paths_column_stat = create_own_paths_column_statement(&ident, &entity_attrs, &data_struct.fields)?;
+            let version_column_stat = create_version_column_statement(&entity_attrs, &data_struct.fields)?;
+            let audited_stat = create_audited_statement(&entity_attrs);
+            let tenant_table_stat = create_tenant_table_statement(&entity_attrs);
+            let audited_table_stat = create_audited_table_statement(&entity_attrs);
+            let dto_stat = match &entity_attrs.dto {
+                Some(prefix) => tardis_create_dto::create_dto(prefix, &data_struct.fields)?,
+                None => TokenStream::new(),
+            };
             let (insert_only_fill_ctx_stat, always_fill_ctx_stat) = create_fill_ctx_statement(data_struct.fields)?;
             Ok(quote! {
 
@@ -56,19 +66,38 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
                         #always_fill_ctx_stat;
                     }
 
+                    #own_paths_column_stat
+
+                    #version_column_stat
+
+                    #audited_stat
+
                     // Call the method automatically generated by TardisCreateTable macros
                     fn create_table_statement(db: ::tardis::db::sea_orm::DbBackend) -> ::tardis::db::sea_orm::sea_query::TableCreateStatement {
                         tardis_create_table_statement(db)
                     }
                     // Call the method automatically generated by TardisCreateTable macros
+                    fn create_compatible_table_statement(
+                        db: ::tardis::db::sea_orm::DbBackend,
+                        compatible_type: ::tardis::config::config_dto::CompatibleType,
+                    ) -> ::tardis::db::sea_orm::sea_query::TableCreateStatement {
+                        tardis_create_compatible_table_statement(db, compatible_type)
+                    }
+                    // Call the method automatically generated by TardisCreateTable macros
                     fn create_index_statement() -> Vec<::tardis::db::sea_orm::sea_query::IndexCreateStatement> {
                         tardis_create_index_statement()
                     }
                 }
+            #tenant_table_stat
+
+            #audited_table_stat
+
             #create_table_stat
 
             #create_index_stat
 
+            #dto_stat
+
             })
         }
         Data::Enum(_) => Err(Error::new(ident.span(), "enum is not support!")),
@@ -76,6 +105,122 @@ pub(crate) fn create_entity(ident: Ident, data: Data) -> Result<TokenStream> {
     }
 }
 
+/// `tardis_entity` attribute of the struct
+#[derive(Default)]
+pub(crate) struct EntityAttrs {
+    tenant_scoped: bool,
+    version: Option<LitStr>,
+    audited: bool,
+    pub(crate) dto: Option<LitStr>,
+}
+
+pub(crate) fn parse_entity_attrs(attrs: &[Attribute]) -> Result<EntityAttrs> {
+    let mut entity_attrs = EntityAttrs::default();
+    for attr in attrs {
+        if attr.path().is_ident("tardis_entity") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tenant_scoped") {
+                    entity_attrs.tenant_scoped = true;
+                    Ok(())
+                } else if meta.path.is_ident("version") {
+                    entity_attrs.version = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("audited") {
+                    entity_attrs.audited = true;
+                    Ok(())
+                } else if meta.path.is_ident("dto") {
+                    entity_attrs.dto = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported tardis_entity attribute, expected `tenant_scoped` , `version` , `audited` or `dto`"))
+                }
+            })?;
+        }
+    }
+    Ok(entity_attrs)
+}
+
+/// `Column` variant of the field with `#[fill_ctx(fill = "own_paths")]` , if any
+pub(crate) fn own_paths_column(fields: &Fields) -> Result<Option<Ident>> {
+    for field in fields {
+        if !field.attrs.iter().any(|attr| attr.path().is_ident("fill_ctx")) {
+            continue;
+        }
+        let field_fill_ctx_meta = FillCtxMeta::from_field(field).map_err(|err| Error::new(field.span(), err.to_string()))?;
+        if let (Fill::OwnPaths, Some(field_ident)) = (field_fill_ctx_meta.fill, field_fill_ctx_meta.ident) {
+            return Ok(Some(Ident::new(&ConvertVariableHelpers::underscore_to_camel(field_ident.to_string()), field_ident.span())));
+        }
+    }
+    Ok(None)
+}
+
+/// return `own_paths_column` method if the struct is marked with `#[tardis_entity(tenant_scoped)]`
+fn create_own_paths_column_statement(ident: &Ident, entity_attrs: &EntityAttrs, fields: &Fields) -> Result<TokenStream> {
+    if !entity_attrs.tenant_scoped {
+        return Ok(TokenStream::new());
+    }
+    match own_paths_column(fields)? {
+        Some(column) => Ok(quote! {
+            fn own_paths_column() -> ::std::option::Option<&'static str> {
+                ::std::option::Option::Some(::tardis::db::sea_orm::IdenStatic::as_str(&Column::#column))
+            }
+        }),
+        None => Err(Error::new(ident.span(), "tenant_scoped entity requires a field with #[fill_ctx(fill = \"own_paths\")]")),
+    }
+}
+
+/// register the table if the struct is marked with `#[tardis_entity(tenant_scoped)]`
+fn create_tenant_table_statement(entity_attrs: &EntityAttrs) -> TokenStream {
+    if !entity_attrs.tenant_scoped {
+        return TokenStream::new();
+    }
+    quote! {
+        ::tardis::db::reldb_tenant::inventory::submit! {
+            ::tardis::db::reldb_tenant::TenantTable::of::<ActiveModel>()
+        }
+    }
+}
+
+/// return `version_column` method if the struct is marked with `#[tardis_entity(version = "...")]`
+fn create_version_column_statement(entity_attrs: &EntityAttrs, fields: &Fields) -> Result<TokenStream> {
+    let Some(version) = &entity_attrs.version else {
+        return Ok(TokenStream::new());
+    };
+    if !fields.iter().any(|field| field.ident.as_ref().is_some_and(|field_ident| *field_ident == version.value())) {
+        return Err(Error::new(version.span(), format!("version field `{}` not found", version.value())));
+    }
+    let column = Ident::new(&ConvertVariableHelpers::underscore_to_camel(version.value()), version.span());
+    Ok(quote! {
+        fn version_column() -> ::std::option::Option<Column> {
+            ::std::option::Option::Some(Column::#column)
+        }
+    })
+}
+
+/// return `audited` method if the struct is marked with `#[tardis_entity(audited)]`
+fn create_audited_statement(entity_attrs: &EntityAttrs) -> TokenStream {
+    if !entity_attrs.audited {
+        return TokenStream::new();
+    }
+    quote! {
+        fn audited() -> bool {
+            true
+        }
+    }
+}
+
+/// register the table if the struct is marked with `#[tardis_entity(audited)]`
+fn create_audited_table_statement(entity_attrs: &EntityAttrs) -> TokenStream {
+    if !entity_attrs.audited {
+        return TokenStream::new();
+    }
+    quote! {
+        ::tardis::db::reldb_audit::inventory::submit! {
+            ::tardis::db::reldb_audit::AuditedTable::of::<ActiveModel>()
+        }
+    }
+}
+
 /// return (only_insert_statement,always_fill_statement)
 fn create_fill_ctx_statement(fields: Fields) -> Result<(TokenStream, TokenStream)> {
     let mut only_insert_stat: Punctuated<TokenStream, Semi> = Punctuated::new();
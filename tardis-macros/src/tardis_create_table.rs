@@ -112,6 +112,15 @@ pub(crate) fn create_table(ident: Ident, data: Data, _atr: impl IntoIterator<Ite
             Ok(quote! {
                 #doc
                 fn tardis_create_table_statement(db: ::tardis::db::sea_orm::DbBackend) -> ::tardis::db::sea_orm::sea_query::TableCreateStatement {
+                    tardis_create_compatible_table_statement(db, ::tardis::config::config_dto::CompatibleType::None)
+                }
+
+                #doc
+                fn tardis_create_compatible_table_statement(
+                    db: ::tardis::db::sea_orm::DbBackend,
+                    compatible_type: ::tardis::config::config_dto::CompatibleType,
+                ) -> ::tardis::db::sea_orm::sea_query::TableCreateStatement {
+                    let dialect = ::tardis::db::reldb_dialect::TardisRelDBDialect::new(db, compatible_type);
                     let mut builder = ::tardis::db::sea_orm::sea_query::Table::create();
                     builder
                         .table(Entity.table_ref())
@@ -222,9 +231,9 @@ fn create_single_col_token_statement(field: CreateTableMeta) -> Result<TokenStre
             ));
         }
         if attribute.is_empty() {
-            Ok(quote! {col(::tardis::db::sea_orm::sea_query::ColumnDef::new_with_type(Column::#ident,#col_type))})
+            Ok(quote! {col(::tardis::db::sea_orm::sea_query::ColumnDef::new_with_type(Column::#ident,dialect.column_type(#col_type)))})
         } else {
-            Ok(quote! {col(::tardis::db::sea_orm::sea_query::ColumnDef::new_with_type(Column::#ident,#col_type).#attribute)})
+            Ok(quote! {col(::tardis::db::sea_orm::sea_query::ColumnDef::new_with_type(Column::#ident,dialect.column_type(#col_type)).#attribute)})
         }
     } else {
         Ok(quote! {})
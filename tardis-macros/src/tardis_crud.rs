@@ -0,0 +1,343 @@
+use crate::macro_helpers::helpers::{default_doc, ConvertVariableHelpers};
+use crate::tardis_create_dto::{option_inner, parse_sea_orm_flags};
+use crate::tardis_create_entity::{own_paths_column, parse_entity_attrs};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, Error, Field, LitStr, Result, Type};
+
+/// How the records are deleted
+#[derive(Default)]
+enum DeleteMode {
+    #[default]
+    Soft,
+    Hard,
+}
+
+/// `tardis_crud` attribute of the struct
+#[derive(Default)]
+struct CrudAttrs {
+    delete: DeleteMode,
+    unscoped: bool,
+    api: Option<LitStr>,
+    module: Option<LitStr>,
+}
+
+fn parse_crud_attrs(attrs: &[Attribute]) -> Result<CrudAttrs> {
+    let mut crud_attrs = CrudAttrs::default();
+    for attr in attrs {
+        if attr.path().is_ident("tardis_crud") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("delete") {
+                    let delete: LitStr = meta.value()?.parse()?;
+                    crud_attrs.delete = match delete.value().as_str() {
+                        "soft" => DeleteMode::Soft,
+                        "hard" => DeleteMode::Hard,
+                        _ => return Err(Error::new(delete.span(), "unsupported delete mode, expected `soft` or `hard`")),
+                    };
+                } else if meta.path.is_ident("unscoped") {
+                    crud_attrs.unscoped = true;
+                } else if meta.path.is_ident("api") {
+                    crud_attrs.api = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("module") {
+                    crud_attrs.module = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported tardis_crud attribute, expected `delete` , `unscoped` , `api` or `module`"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(crud_attrs)
+}
+
+/// How the field filters the records, `None` if it's not a filter
+fn parse_filter(field: &Field) -> Result<Option<bool>> {
+    let mut filter = None;
+    for attr in &field.attrs {
+        if attr.path().is_ident("tardis_crud") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("filter") {
+                    if meta.input.peek(syn::Token![=]) {
+                        let mode: LitStr = meta.value()?.parse()?;
+                        filter = match mode.value().as_str() {
+                            "eq" => Some(false),
+                            "like" => Some(true),
+                            _ => return Err(Error::new(mode.span(), "unsupported filter mode, expected `eq` or `like`")),
+                        };
+                    } else {
+                        filter = Some(false);
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported tardis_crud attribute of the field, expected `filter`"))
+                }
+            })?;
+        }
+    }
+    Ok(filter)
+}
+
+/// Generate the `<prefix>FilterReq` struct, the `<prefix>Serv` service and, if `api` is specified, the `<prefix>Api` of the `Model`
+pub(crate) fn create_crud(ident: Ident, data: Data, attrs: Vec<Attribute>) -> Result<TokenStream> {
+    let Data::Struct(data_struct) = data else {
+        return Err(Error::new(ident.span(), "only struct is supported!"));
+    };
+    let Some(prefix) = parse_entity_attrs(&attrs)?.dto else {
+        return Err(Error::new(ident.span(), "TardisCrud requires the DTOs generated by `#[tardis_entity(dto = \"<prefix>\")]`"));
+    };
+    let crud_attrs = parse_crud_attrs(&attrs)?;
+    let prefix_value = prefix.value();
+    let add_req = format_ident!("{}AddReq", prefix_value, span = prefix.span());
+    let modify_req = format_ident!("{}ModifyReq", prefix_value, span = prefix.span());
+    let detail_resp = format_ident!("{}DetailResp", prefix_value, span = prefix.span());
+    let filter_req = format_ident!("{}FilterReq", prefix_value, span = prefix.span());
+    let serv = format_ident!("{}Serv", prefix_value, span = prefix.span());
+    let api = format_ident!("{}Api", prefix_value, span = prefix.span());
+    let mod_name = format_ident!("__tardis_crud_{}", prefix_value.to_lowercase());
+
+    let mut pk: Option<(Ident, Type, Ident)> = None;
+    let mut filter_fields = Vec::new();
+    let mut filter_stats = Vec::new();
+    let mut filter_params = Vec::new();
+    let mut filter_idents = Vec::new();
+    for field in &data_struct.fields {
+        let Some(field_ident) = &field.ident else {
+            continue;
+        };
+        let sea_orm_flags = parse_sea_orm_flags(field)?;
+        if sea_orm_flags.ignore {
+            continue;
+        }
+        let column = format_ident!("{}", ConvertVariableHelpers::underscore_to_camel(field_ident.to_string()));
+        if sea_orm_flags.primary_key {
+            if pk.is_some() {
+                return Err(Error::new(field_ident.span(), "TardisCrud does not support the composite primary key"));
+            }
+            pk = Some((field_ident.clone(), field.ty.clone(), column.clone()));
+        }
+        let Some(like) = parse_filter(field)? else {
+            continue;
+        };
+        let filter_ty = option_inner(&field.ty).unwrap_or(&field.ty);
+        let docs = field.attrs.iter().filter(|attr| attr.path().is_ident("doc")).collect::<Vec<&Attribute>>();
+        filter_fields.push(quote! {
+            #(#docs)*
+            pub #field_ident: ::std::option::Option<#filter_ty>
+        });
+        filter_stats.push(if like {
+            quote! {
+                if let ::std::option::Option::Some(value) = &filter.#field_ident {
+                    select = select.filter(super::Column::#column.like(::tardis::db::reldb_client::contains_like(&value.to_string())));
+                }
+            }
+        } else {
+            quote! {
+                if let ::std::option::Option::Some(value) = filter.#field_ident.clone() {
+                    select = select.filter(super::Column::#column.eq(value));
+                }
+            }
+        });
+        filter_params.push(quote! { #field_ident: Query<::std::option::Option<#filter_ty>> });
+        filter_idents.push(field_ident.clone());
+    }
+    let Some((pk_ident, pk_ty, pk_column)) = pk else {
+        return Err(Error::new(ident.span(), "TardisCrud requires a primary key"));
+    };
+    let scope_stat = match (own_paths_column(&data_struct.fields)?, crud_attrs.unscoped) {
+        (_, true) => quote! { sea_orm::Condition::all() },
+        (Some(own_paths_column), false) => quote! {
+            sea_orm::Condition::all().add(::tardis::db::reldb_tenant::own_paths_predicate(super::Column::#own_paths_column, &ctx.own_paths))
+        },
+        (None, false) => {
+            return Err(Error::new(
+                ident.span(),
+                "TardisCrud limits the records to the own paths of the context by the field with `#[fill_ctx(fill = \"own_paths\")]` , \
+                 add one or `#[tardis_crud(unscoped)]` to share the records among all the contexts",
+            ))
+        }
+    };
+
+    let delete_stat = match crud_attrs.delete {
+        DeleteMode::Soft => quote! {
+            let select = Self::scoped_select(id, ctx);
+            let pk_column = sea_orm::IdenStatic::as_str(&super::Column::#pk_column);
+            // `soft_delete` takes `id` as the primary key
+            if pk_column == "id" {
+                conn.soft_delete(select, &ctx.owner).await
+            } else {
+                Ok(conn.soft_delete_custom(select, pk_column).await?.len() as u64)
+            }
+        },
+        DeleteMode::Hard => quote! {
+            conn.hard_delete::<super::Entity>(Self::scope(ctx).add(super::Column::#pk_column.eq(id)), ctx).await
+        },
+    };
+
+    let doc = default_doc();
+    let api_stat = match &crud_attrs.api {
+        Some(path) => {
+            let module = crud_attrs.module.clone().unwrap_or_else(|| LitStr::new("", path.span()));
+            quote! {
+                use ::tardis::web::context_extractor::TardisContextExtractor;
+                use ::tardis::web::poem_openapi::param::{Path, Query};
+                use ::tardis::web::poem_openapi::payload::Json;
+                use ::tardis::web::web_resp::{TardisApiResult, TardisResp, Void};
+                use ::tardis::TardisFuns;
+
+                #doc
+                #[derive(Debug, Clone, Default)]
+                pub struct #api;
+
+                #[poem_openapi::OpenApi(prefix_path = #path)]
+                impl #api {
+                    #[oai(path = "/", method = "post")]
+                    async fn add(&self, add_req: Json<#add_req>, ctx: TardisContextExtractor) -> TardisApiResult<#pk_ty> {
+                        let mut conn = TardisFuns::reldb_by_module_or_default(#module).conn();
+                        conn.begin().await?;
+                        let id = #serv::add(add_req.0, &conn, &ctx.0).await?;
+                        conn.commit().await?;
+                        TardisResp::ok(id)
+                    }
+
+                    #[oai(path = "/:id", method = "put")]
+                    async fn modify(&self, id: Path<#pk_ty>, modify_req: Json<#modify_req>, ctx: TardisContextExtractor) -> TardisApiResult<Void> {
+                        let mut conn = TardisFuns::reldb_by_module_or_default(#module).conn();
+                        conn.begin().await?;
+                        #serv::modify(id.0, modify_req.0, &conn, &ctx.0).await?;
+                        conn.commit().await?;
+                        TardisResp::ok(Void {})
+                    }
+
+                    #[oai(path = "/:id", method = "get")]
+                    async fn get(&self, id: Path<#pk_ty>, ctx: TardisContextExtractor) -> TardisApiResult<#detail_resp> {
+                        let conn = TardisFuns::reldb_by_module_or_default(#module).conn();
+                        let detail = #serv::get_by_id(id.0, &conn, &ctx.0).await?.ok_or_else(|| TardisError::not_found("[Tardis.Crud] The record does not exist", "404-tardis-crud-not-exist"))?;
+                        TardisResp::ok(detail)
+                    }
+
+                    #[oai(path = "/", method = "get")]
+                    async fn paginate(
+                        &self,
+                        #(#filter_params,)*
+                        page_number: Query<u64>,
+                        page_size: Query<u64>,
+                        ctx: TardisContextExtractor,
+                    ) -> TardisApiResult<TardisPage<#detail_resp>> {
+                        let conn = TardisFuns::reldb_by_module_or_default(#module).conn();
+                        let filter = #filter_req {
+                            #(#filter_idents: #filter_idents.0,)*
+                        };
+                        TardisResp::ok(#serv::paginate(&filter, page_number.0, page_size.0, &conn, &ctx.0).await?)
+                    }
+
+                    #[oai(path = "/:id", method = "delete")]
+                    async fn delete(&self, id: Path<#pk_ty>, ctx: TardisContextExtractor) -> TardisApiResult<u64> {
+                        let mut conn = TardisFuns::reldb_by_module_or_default(#module).conn();
+                        conn.begin().await?;
+                        let delete_num = #serv::delete(id.0, &conn, &ctx.0).await?;
+                        conn.commit().await?;
+                        TardisResp::ok(delete_num)
+                    }
+                }
+            }
+        }
+        None => TokenStream::new(),
+    };
+    let api_export = if crud_attrs.api.is_some() {
+        quote! { , #api }
+    } else {
+        TokenStream::new()
+    };
+
+    Ok(quote! {
+        #[doc(hidden)]
+        mod #mod_name {
+            #[allow(unused_imports)]
+            use super::*;
+            use ::tardis::basic::dto::TardisContext;
+            use ::tardis::basic::error::TardisError;
+            use ::tardis::basic::result::TardisResult;
+            use ::tardis::db::reldb_client::TardisRelDBlConnection;
+            use ::tardis::db::sea_orm;
+            #[allow(unused_imports)]
+            use ::tardis::db::sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QueryTrait};
+            #[allow(unused_imports)]
+            use ::tardis::web::poem_openapi;
+            use ::tardis::web::web_resp::TardisPage;
+
+            #doc
+            #[derive(Clone, Debug, Default, poem_openapi::Object, ::tardis::serde::Serialize, ::tardis::serde::Deserialize)]
+            #[serde(crate = "::tardis::serde")]
+            pub struct #filter_req {
+                #(#filter_fields,)*
+            }
+
+            #doc
+            pub struct #serv;
+
+            impl #serv {
+                /// The records within the own paths of the context, all the records if unscoped
+                #[allow(unused_variables)]
+                fn scope(ctx: &TardisContext) -> sea_orm::Condition {
+                    #scope_stat
+                }
+
+                /// The record of the primary key within the own paths of the context
+                fn scoped_select(id: #pk_ty, ctx: &TardisContext) -> sea_orm::Select<super::Entity> {
+                    super::Entity::find_by_id(id).filter(Self::scope(ctx))
+                }
+
+                /// Add a record, returns the primary key / 添加记录，返回主键
+                pub async fn add(add_req: #add_req, conn: &TardisRelDBlConnection, ctx: &TardisContext) -> TardisResult<#pk_ty> {
+                    Ok(conn.insert_one(super::ActiveModel::from(add_req), ctx).await?.last_insert_id)
+                }
+
+                /// Modify the present fields of a record / 修改记录中存在的字段
+                pub async fn modify(id: #pk_ty, modify_req: #modify_req, conn: &TardisRelDBlConnection, ctx: &TardisContext) -> TardisResult<()> {
+                    let mut active_model = super::ActiveModel::from(modify_req);
+                    if !active_model.is_changed() {
+                        return Err(TardisError::bad_request("[Tardis.Crud] No field to be modified", "400-tardis-crud-modify-empty"));
+                    }
+                    active_model.#pk_ident = sea_orm::ActiveValue::Set(id);
+                    if conn.update_one_with_condition(active_model, Self::scope(ctx), ctx).await? == 0 {
+                        return Err(TardisError::not_found("[Tardis.Crud] The record does not exist", "404-tardis-crud-not-exist"));
+                    }
+                    Ok(())
+                }
+
+                /// Get a record by the primary key / 根据主键获取记录
+                pub async fn get_by_id(id: #pk_ty, conn: &TardisRelDBlConnection, ctx: &TardisContext) -> TardisResult<::std::option::Option<#detail_resp>> {
+                    conn.get_dto(&Self::scoped_select(id, ctx).into_query()).await
+                }
+
+                /// Paginate the records matching the filter, ordered by the primary key / 分页获取匹配过滤条件的记录，按主键排序
+                pub async fn paginate(
+                    filter: &#filter_req,
+                    page_number: u64,
+                    page_size: u64,
+                    conn: &TardisRelDBlConnection,
+                    ctx: &TardisContext,
+                ) -> TardisResult<TardisPage<#detail_resp>> {
+                    #[allow(unused_mut)]
+                    let mut select = super::Entity::find().filter(Self::scope(ctx));
+                    #(#filter_stats)*
+                    let (records, total_size) = conn.paginate_dtos(&select.order_by_asc(super::Column::#pk_column).into_query(), page_number, page_size).await?;
+                    Ok(TardisPage {
+                        page_size,
+                        page_number,
+                        total_size,
+                        records,
+                    })
+                }
+
+                /// Delete a record, returns the number of deleted records / 删除记录，返回删除的记录数
+                pub async fn delete(id: #pk_ty, conn: &TardisRelDBlConnection, ctx: &TardisContext) -> TardisResult<u64> {
+                    #delete_stat
+                }
+            }
+
+            #api_stat
+        }
+        pub use #mod_name::{#filter_req, #serv #api_export};
+    })
+}
@@ -4,8 +4,12 @@ pub mod component;
 pub mod dto;
 pub mod error;
 pub mod field;
+pub mod health;
 pub mod json;
+pub mod lifecycle;
 pub mod locale;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod result;
 pub mod tracing;
 pub mod uri;
@@ -17,6 +17,9 @@ pub static ERROR_DEFAULT_CODE: &str = "-1";
 pub struct TardisError {
     pub code: String,
     pub message: String,
+    /// Locale code of the message, e.g. `409-tardis-reldb-conflict` , not serialized / 消息的语言编码，如 `409-tardis-reldb-conflict` ，不序列化
+    #[serde(skip)]
+    pub locale_code: String,
 }
 
 impl Display for TardisError {
@@ -29,7 +32,11 @@ impl TardisError {
     fn error(code: &str, msg: &str, locale_code: &str) -> TardisError {
         warn!("[Tardis.Error] {}:{}", code, msg);
         let message = TardisLocale::env_message(if locale_code.trim().is_empty() { code } else { locale_code }, msg);
-        TardisError { code: code.to_string(), message }
+        TardisError {
+            code: code.to_string(),
+            message,
+            locale_code: locale_code.to_string(),
+        }
     }
 
     #[must_use]
@@ -105,7 +112,11 @@ impl TardisErrorWithExt {
         let code = format!("{}-{}-{}-{}", code, self.ext, obj_name, obj_opt);
         warn!("[Tardis.Error] {}:{}", code, msg);
         let message = self.localized_message(if locale_code.trim().is_empty() { &code } else { locale_code }, msg);
-        TardisError { code, message }
+        TardisError {
+            code,
+            message,
+            locale_code: locale_code.to_string(),
+        }
     }
     #[must_use]
     pub fn localized_message(&self, locale_code: &str, msg: &str) -> String {
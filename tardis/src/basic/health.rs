@@ -0,0 +1,124 @@
+//! Health check / 健康检查
+//!
+//! Each component client implements [`HealthCheck`], [`TardisFuns::health`](crate::TardisFuns::health) aggregates
+//! the results of all initialized modules into a [`TardisHealthReport`].
+//!
+//! 各组件客户端实现了 [`HealthCheck`] ， [`TardisFuns::health`](crate::TardisFuns::health) 将所有已初始化模块的检查结果汇总为 [`TardisHealthReport`] .
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+use crate::basic::result::TardisResult;
+
+/// Timeout of a single health check / 单次健康检查的超时时间
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Liveness of a backend / 后端的可用性检查
+#[async_trait::async_trait]
+pub trait HealthCheck: Send + Sync {
+    /// Check whether the backend is available, `Ok` means healthy / 检查后端是否可用，返回 `Ok` 表示健康
+    async fn health_check(&self) -> TardisResult<()>;
+}
+
+/// Health status / 健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthStatus {
+    #[default]
+    Up,
+    Down,
+}
+
+/// Health check result of a module / 模块的健康检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheckResult {
+    pub status: HealthStatus,
+    /// Elapsed time of the check, in milliseconds / 检查耗时，单位毫秒
+    pub elapsed_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Error message when the status is down / 不可用时的错误信息
+    pub error: Option<String>,
+}
+
+impl HealthCheckResult {
+    /// Run the health check with [`HEALTH_CHECK_TIMEOUT`] / 在 [`HEALTH_CHECK_TIMEOUT`] 内执行健康检查
+    pub async fn check<T: HealthCheck + ?Sized>(target: &T) -> HealthCheckResult {
+        let start = Instant::now();
+        let result = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, target.health_check()).await;
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        match result {
+            Ok(Ok(())) => HealthCheckResult {
+                status: HealthStatus::Up,
+                elapsed_ms,
+                error: None,
+            },
+            Ok(Err(e)) => HealthCheckResult {
+                status: HealthStatus::Down,
+                elapsed_ms,
+                error: Some(e.to_string()),
+            },
+            Err(_) => HealthCheckResult {
+                status: HealthStatus::Down,
+                elapsed_ms,
+                error: Some(format!("health check timeout after {}ms", HEALTH_CHECK_TIMEOUT.as_millis())),
+            },
+        }
+    }
+}
+
+/// Aggregated health report / 汇总的健康报告
+///
+/// # Examples
+/// ```json
+/// {
+///   "status": "DOWN",
+///   "components": {
+///     "reldb": { "": { "status": "UP", "elapsed_ms": 2 } },
+///     "cache": { "": { "status": "UP", "elapsed_ms": 1 }, "m1": { "status": "DOWN", "elapsed_ms": 5000, "error": "..." } }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TardisHealthReport {
+    /// Down if any module is down / 任一模块不可用时为 Down
+    pub status: HealthStatus,
+    /// Results keyed by component name and module code, the default module code is empty
+    ///
+    /// 以组件名称及模块编码为键的检查结果，默认模块的编码为空
+    pub components: HashMap<String, HashMap<String, HealthCheckResult>>,
+}
+
+impl TardisHealthReport {
+    pub fn is_up(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+
+    /// Check the modules of a component concurrently and add the results / 并发检查组件的各模块并添加结果
+    pub async fn check_component<T: HealthCheck + 'static>(&mut self, component: &str, modules: HashMap<String, Arc<T>>) {
+        if modules.is_empty() {
+            return;
+        }
+        let mut checks = JoinSet::new();
+        for (code, client) in modules {
+            checks.spawn(async move { (code, HealthCheckResult::check(client.as_ref()).await) });
+        }
+        let results = self.components.entry(component.to_string()).or_default();
+        while let Some(joined) = checks.join_next().await {
+            match joined {
+                Ok((code, result)) => {
+                    if result.status == HealthStatus::Down {
+                        self.status = HealthStatus::Down;
+                    }
+                    results.insert(code, result);
+                }
+                Err(e) => {
+                    tracing::error!("[Tardis.Health] Health check of {} panicked: {}", component, e);
+                    self.status = HealthStatus::Down;
+                }
+            }
+        }
+    }
+}
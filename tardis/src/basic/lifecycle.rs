@@ -0,0 +1,160 @@
+//! Lifecycle hooks / 生命周期钩子
+//!
+//! Startup hooks run after all components are initialized by [`TardisFuns::init_conf`],
+//! shutdown hooks run before the components are closed by [`TardisFuns::shutdown`].
+//!
+//! 启动钩子在 [`TardisFuns::init_conf`] 完成所有组件初始化后执行，关闭钩子在 [`TardisFuns::shutdown`] 关闭组件前执行.
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+use crate::basic::result::TardisResult;
+use crate::TardisFuns;
+
+type LifecycleHookFn = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = TardisResult<()>> + Send>> + Send + Sync>;
+
+#[derive(Clone)]
+struct LifecycleHook {
+    name: String,
+    priority: i32,
+    hook: LifecycleHookFn,
+}
+
+/// Lifecycle hooks and shutdown state of the framework / 框架的生命周期钩子及关闭状态
+pub struct TardisLifecycle {
+    startup_hooks: Mutex<Vec<LifecycleHook>>,
+    shutdown_hooks: Mutex<Vec<LifecycleHook>>,
+    signal_listening: AtomicBool,
+    shutdown_finished: watch::Sender<bool>,
+}
+
+impl Default for TardisLifecycle {
+    fn default() -> Self {
+        TardisLifecycle {
+            startup_hooks: Mutex::new(Vec::new()),
+            shutdown_hooks: Mutex::new(Vec::new()),
+            signal_listening: AtomicBool::new(false),
+            shutdown_finished: watch::channel(false).0,
+        }
+    }
+}
+
+impl TardisLifecycle {
+    pub(crate) fn add_startup_hook<F, T>(&self, name: &str, priority: i32, hook: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        Self::add_hook(&self.startup_hooks, name, priority, hook)
+    }
+
+    pub(crate) fn add_shutdown_hook<F, T>(&self, name: &str, priority: i32, hook: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        Self::add_hook(&self.shutdown_hooks, name, priority, hook)
+    }
+
+    fn add_hook<F, T>(hooks: &Mutex<Vec<LifecycleHook>>, name: &str, priority: i32, hook: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        hooks.lock().expect("encounter an poisoned lock when trying to lock lifecycle hooks").push(LifecycleHook {
+            name: name.to_string(),
+            priority,
+            hook: Arc::new(move || Box::pin(hook())),
+        });
+    }
+
+    /// Hooks ordered by priority, hooks with the same priority keep the registration order
+    fn sorted_hooks(hooks: &Mutex<Vec<LifecycleHook>>) -> Vec<LifecycleHook> {
+        let mut hooks = hooks.lock().expect("encounter an poisoned lock when trying to lock lifecycle hooks").clone();
+        hooks.sort_by_key(|hook| hook.priority);
+        hooks
+    }
+
+    /// Run startup hooks, the first failed hook aborts the startup
+    pub(crate) async fn run_startup_hooks(&self) -> TardisResult<()> {
+        for hook in Self::sorted_hooks(&self.startup_hooks) {
+            info!("[Tardis.Lifecycle] Running startup hook [{}]", hook.name);
+            if let Err(e) = (hook.hook)().await {
+                error!("[Tardis.Lifecycle] Startup hook [{}] failed: {}", hook.name, e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Run shutdown hooks until the deadline, failed hooks don't stop the shutdown
+    pub(crate) async fn run_shutdown_hooks(&self, deadline: Instant) {
+        for hook in Self::sorted_hooks(&self.shutdown_hooks) {
+            info!("[Tardis.Lifecycle] Running shutdown hook [{}]", hook.name);
+            match tokio::time::timeout_at(deadline, (hook.hook)()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error!("[Tardis.Lifecycle] Shutdown hook [{}] failed: {}", hook.name, e),
+                Err(_) => {
+                    warn!("[Tardis.Lifecycle] Shutdown hook [{}] exceeded the grace timeout, skip the remaining hooks", hook.name);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Spawn a task which calls [`TardisFuns::shutdown`] on `SIGTERM` / `SIGINT`, it only listens once for the whole process
+    pub(crate) fn listen_signal(&self) {
+        if self.signal_listening.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(async {
+            wait_signal().await;
+            info!("[Tardis.Lifecycle] Received shutdown signal");
+            if let Err(e) = TardisFuns::shutdown().await {
+                error!("[Tardis.Lifecycle] Shutdown error: {}", e);
+            }
+        });
+    }
+
+    pub(crate) fn mark_started(&self) {
+        self.shutdown_finished.send_replace(false);
+    }
+
+    pub(crate) fn mark_shutdown(&self) {
+        self.shutdown_finished.send_replace(true);
+    }
+
+    /// Wait until a shutdown finished
+    pub(crate) async fn wait_shutdown(&self) {
+        let mut shutdown_finished = self.shutdown_finished.subscribe();
+        // the sender lives as long as the framework instance, so it can't be closed
+        let _ = shutdown_finished.wait_for(|finished| *finished).await;
+    }
+}
+
+#[cfg(unix)]
+async fn wait_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = terminate.recv() => {},
+                _ = tokio::signal::ctrl_c() => {},
+            }
+        }
+        Err(e) => {
+            error!("[Tardis.Lifecycle] Failed to listen SIGTERM, only SIGINT is handled: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,153 @@
+//! Prometheus metrics / Prometheus指标
+//!
+//! Framework components record their metrics into [`TardisMetrics`], the web server exposes them
+//! on [`WebServerCommonConfig::metrics_path`](crate::config::config_dto::WebServerCommonConfig::metrics_path).
+//! Custom metrics can be registered into [`TardisMetrics::registry`].
+//!
+//! 框架各组件将指标记录到 [`TardisMetrics`] 中，Web服务在
+//! [`WebServerCommonConfig::metrics_path`](crate::config::config_dto::WebServerCommonConfig::metrics_path) 上暴露这些指标.
+//! 自定义指标可注册到 [`TardisMetrics::registry`] 中.
+//!
+//! | Metric | Labels |
+//! | --- | --- |
+//! | `tardis_web_requests_total` | `module`, `method`, `status` |
+//! | `tardis_web_request_duration_seconds` | `module`, `method` |
+//! | `tardis_reldb_pool_connections` | `module`, `state` (`idle` / `active`) |
+//! | `tardis_reldb_query_duration_seconds` | `op` |
+//! | `tardis_reldb_query_errors_total` | `op` |
+//! | `tardis_cache_command_duration_seconds` | `command` |
+//! | `tardis_cache_command_errors_total` | `command` |
+//! | `tardis_mq_published_total` | `topic` |
+//! | `tardis_mq_consumed_total` | `topic` |
+//! | `tardis_mq_handler_failures_total` | `topic` |
+//! | `tardis_web_client_requests_total` | `method`, `host`, `status` |
+//! | `tardis_web_client_request_duration_seconds` | `method`, `host` |
+use std::future::Future;
+use std::time::Instant;
+
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry};
+
+/// Metrics of the framework components / 框架组件的指标
+pub struct TardisMetrics {
+    registry: Registry,
+    pub(crate) web_requests: IntCounterVec,
+    pub(crate) web_request_duration: HistogramVec,
+    pub(crate) reldb_pool_connections: IntGaugeVec,
+    pub(crate) reldb_query_duration: HistogramVec,
+    pub(crate) reldb_query_errors: IntCounterVec,
+    pub(crate) cache_command_duration: HistogramVec,
+    pub(crate) cache_command_errors: IntCounterVec,
+    pub(crate) mq_published: IntCounterVec,
+    pub(crate) mq_consumed: IntCounterVec,
+    pub(crate) mq_handler_failures: IntCounterVec,
+    pub(crate) web_client_requests: IntCounterVec,
+    pub(crate) web_client_request_duration: HistogramVec,
+}
+
+crate::tardis_static! {
+    pub(crate) tardis_metrics: TardisMetrics = TardisMetrics::new();
+}
+
+impl TardisMetrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        TardisMetrics {
+            web_requests: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_web_requests_total", "Number of web requests"), &["module", "method", "status"]),
+            ),
+            web_request_duration: register(
+                &registry,
+                HistogramVec::new(HistogramOpts::new("tardis_web_request_duration_seconds", "Latency of web requests"), &["module", "method"]),
+            ),
+            reldb_pool_connections: register(
+                &registry,
+                IntGaugeVec::new(Opts::new("tardis_reldb_pool_connections", "Connections of the database pools"), &["module", "state"]),
+            ),
+            reldb_query_duration: register(
+                &registry,
+                HistogramVec::new(HistogramOpts::new("tardis_reldb_query_duration_seconds", "Latency of database operations"), &["op"]),
+            ),
+            reldb_query_errors: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_reldb_query_errors_total", "Number of failed database operations"), &["op"]),
+            ),
+            cache_command_duration: register(
+                &registry,
+                HistogramVec::new(HistogramOpts::new("tardis_cache_command_duration_seconds", "Latency of cache commands"), &["command"]),
+            ),
+            cache_command_errors: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_cache_command_errors_total", "Number of failed cache commands"), &["command"]),
+            ),
+            mq_published: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_mq_published_total", "Number of published mq messages"), &["topic"]),
+            ),
+            mq_consumed: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_mq_consumed_total", "Number of consumed mq messages"), &["topic"]),
+            ),
+            mq_handler_failures: register(
+                &registry,
+                IntCounterVec::new(Opts::new("tardis_mq_handler_failures_total", "Number of mq messages failed to be handled"), &["topic"]),
+            ),
+            web_client_requests: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("tardis_web_client_requests_total", "Number of outbound http requests"),
+                    &["method", "host", "status"],
+                ),
+            ),
+            web_client_request_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new("tardis_web_client_request_duration_seconds", "Latency of outbound http requests"),
+                    &["method", "host"],
+                ),
+            ),
+            registry,
+        }
+    }
+
+    /// Registry holding all metrics, custom metrics can be registered into it / 包含所有指标的注册表，可向其中注册自定义指标
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Render all metrics in the prometheus text format / 以Prometheus文本格式输出所有指标
+    pub fn render(&self) -> String {
+        #[cfg(feature = "reldb-core")]
+        self.refresh_reldb_pool_connections();
+        prometheus::TextEncoder::new().encode_to_string(&self.registry.gather()).expect("[Tardis.Metrics] Encode metrics error")
+    }
+
+    /// Pool usage is sampled when the metrics are collected
+    #[cfg(feature = "reldb-core")]
+    pub(crate) fn refresh_reldb_pool_connections(&self) {
+        self.reldb_pool_connections.reset();
+        for (code, client) in crate::tardis_instance().reldb.read().iter() {
+            if let Some((size, idle)) = client.pool_status() {
+                self.reldb_pool_connections.with_label_values(&[code, "idle"]).set(idle as i64);
+                self.reldb_pool_connections.with_label_values(&[code, "active"]).set(size as i64 - idle as i64);
+            }
+        }
+    }
+
+    /// Record the latency and the failure of an operation
+    pub(crate) async fn observe<T, E>(duration: &HistogramVec, errors: &IntCounterVec, label: &str, operation: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+        let start = Instant::now();
+        let result = operation.await;
+        duration.with_label_values(&[label]).observe(start.elapsed().as_secs_f64());
+        if result.is_err() {
+            errors.with_label_values(&[label]).inc();
+        }
+        result
+    }
+}
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(registry: &Registry, metric: prometheus::Result<T>) -> T {
+    let metric = metric.expect("[Tardis.Metrics] Create metric error");
+    registry.register(Box::new(metric.clone())).expect("[Tardis.Metrics] Register metric error");
+    metric
+}
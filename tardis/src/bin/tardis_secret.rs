@@ -0,0 +1,57 @@
+//! Generate the secret key and encrypt the config values / 生成密钥及加密配置值
+//!
+//! ```shell
+//! # Generate a key / 生成密钥
+//! export TARDIS_SECRET_KEY=$(tardis-secret gen-key)
+//! # Encrypt a value, read from stdin if the value is omitted / 加密值，未指定时从标准输入读取
+//! tardis-secret encrypt "postgres password"
+//! # Decrypt a value / 解密值
+//! tardis-secret decrypt '${secret:aead:...}'
+//! ```
+use std::io::Read;
+use std::process::ExitCode;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_secret::{decrypt_secret, encrypt_secret, generate_secret_key, load_secret_key};
+
+const USAGE: &str = "Usage: tardis-secret <gen-key | encrypt [VALUE] | decrypt [VALUE]>
+  gen-key   generate a base64 encoded 32 bytes key
+  encrypt   encrypt the value into ${secret:aead:...}
+  decrypt   decrypt the ${secret:aead:...} value
+The key is read from the environment variable TARDIS_SECRET_KEY or the file specified by TARDIS_SECRET_KEY_FILE, the value is read from stdin if omitted.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("gen-key") => Ok(generate_secret_key()),
+        Some("encrypt") => value(args.get(1)).and_then(|value| encrypt_secret(&value, &load_secret_key()?)),
+        Some("decrypt") => value(args.get(1)).and_then(|value| {
+            let value = value.trim();
+            let value = value.strip_prefix("${secret:aead:").and_then(|value| value.strip_suffix('}')).unwrap_or(value);
+            decrypt_secret(value, &load_secret_key()?)
+        }),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e.message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn value(arg: Option<&String>) -> TardisResult<String> {
+    if let Some(arg) = arg {
+        return Ok(arg.clone());
+    }
+    let mut value = String::new();
+    std::io::stdin().read_to_string(&mut value)?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
 use deadpool_redis::{Config, Connection, Pool, Runtime};
@@ -6,6 +7,7 @@ use redis::{AsyncCommands, ErrorKind, FromRedisValue, RedisError, RedisResult, T
 use tracing::{error, info, trace};
 
 use crate::basic::error::TardisError;
+use crate::basic::health::HealthCheck;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::cache::CacheModuleConfig;
 
@@ -42,6 +44,15 @@ impl InitBy<CacheModuleConfig> for TardisCacheClient {
     }
 }
 
+#[async_trait::async_trait]
+impl HealthCheck for TardisCacheClient {
+    /// Send `PING` to redis / 向redis发送 `PING`
+    async fn health_check(&self) -> TardisResult<()> {
+        let _: String = redis::cmd("PING").query_async(&mut self.get_connection().await?).await?;
+        Ok(())
+    }
+}
+
 impl TardisCacheClient {
     /// Initialize configuration / 初始化配置
     pub async fn init(CacheModuleConfig { url }: &CacheModuleConfig) -> TardisResult<TardisCacheClient> {
@@ -71,39 +82,62 @@ impl TardisCacheClient {
         self.pool.get().await.map_err(|error| RedisError::from((ErrorKind::IoError, "Get connection error", error.to_string())))
     }
 
+    /// Record the latency of a command when the `metrics` feature is enabled
+    async fn observe<T>(&self, command: &str, operation: impl Future<Output = RedisResult<T>>) -> RedisResult<T> {
+        #[cfg(feature = "metrics")]
+        {
+            let metrics = crate::TardisFuns::metrics();
+            crate::basic::metrics::TardisMetrics::observe(&metrics.cache_command_duration, &metrics.cache_command_errors, command, operation).await
+        }
+        #[cfg(not(feature = "metrics"))]
+        {
+            let _ = command;
+            operation.await
+        }
+    }
+
     pub async fn set(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] set, key:{}, value:{}", key, value);
-        self.get_connection().await?.set(key, value).await
+        self.observe("set", async { self.get_connection().await?.set(key, value).await }).await
     }
 
     pub async fn set_ex(&self, key: &str, value: &str, ex_sec: u64) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] set_ex, key:{}, value:{}, ex_sec:{}", key, value, ex_sec);
-        self.get_connection().await?.set_ex(key, value, ex_sec).await
+        self.observe("set_ex", async { self.get_connection().await?.set_ex(key, value, ex_sec).await }).await
     }
 
     pub async fn set_nx(&self, key: &str, value: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] set_nx, key:{}, value:{}", key, value);
-        self.get_connection().await?.set_nx(key, value).await
+        self.observe("set_nx", async { self.get_connection().await?.set_nx(key, value).await }).await
+    }
+
+    pub async fn set_nx_ex(&self, key: &str, value: &str, ex_sec: u64) -> RedisResult<bool> {
+        trace!("[Tardis.CacheClient] set_nx_ex, key:{}, value:{}, ex_sec:{}", key, value, ex_sec);
+        self.observe("set_nx_ex", async {
+            let result: Option<String> = redis::cmd("SET").arg(key).arg(value).arg("NX").arg("EX").arg(ex_sec).query_async(&mut self.get_connection().await?).await?;
+            Ok(result.is_some())
+        })
+        .await
     }
 
     pub async fn get(&self, key: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] get, key:{}", key);
-        self.get_connection().await?.get(key).await
+        self.observe("get", async { self.get_connection().await?.get(key).await }).await
     }
 
     pub async fn getset(&self, key: &str, value: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] getset, key:{}, value:{}", key, value);
-        self.get_connection().await?.getset(key, value).await
+        self.observe("getset", async { self.get_connection().await?.getset(key, value).await }).await
     }
 
     pub async fn incr(&self, key: &str, delta: isize) -> RedisResult<isize> {
         trace!("[Tardis.CacheClient] incr, key:{}, delta:{}", key, delta);
-        self.get_connection().await?.incr(key, delta).await
+        self.observe("incr", async { self.get_connection().await?.incr(key, delta).await }).await
     }
 
     pub async fn del(&self, key: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] del, key:{}", key);
-        self.get_connection().await?.del(key).await
+        self.observe("del", async { self.get_connection().await?.del(key).await }).await
     }
 
     pub async fn del_confirm(&self, key: &str) -> RedisResult<()> {
@@ -124,96 +158,96 @@ impl TardisCacheClient {
 
     pub async fn exists(&self, key: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] exists, key:{}", key);
-        self.get_connection().await?.exists(key).await
+        self.observe("exists", async { self.get_connection().await?.exists(key).await }).await
     }
 
     pub async fn expire(&self, key: &str, ex_sec: i64) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] expire, key:{}, ex_sec:{}", key, ex_sec);
-        self.get_connection().await?.expire(key, ex_sec).await
+        self.observe("expire", async { self.get_connection().await?.expire(key, ex_sec).await }).await
     }
 
     pub async fn expire_at(&self, key: &str, timestamp_sec: i64) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] expire_at, key:{}, timestamp_sec:{}", key, timestamp_sec);
-        self.get_connection().await?.expire_at(key, timestamp_sec).await
+        self.observe("expire_at", async { self.get_connection().await?.expire_at(key, timestamp_sec).await }).await
     }
 
     pub async fn ttl(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] ttl, key:{}", key);
-        self.get_connection().await?.ttl(key).await
+        self.observe("ttl", async { self.get_connection().await?.ttl(key).await }).await
     }
 
     // list operations
 
     pub async fn lpush(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] lpush, key:{}, value:{}", key, value);
-        self.get_connection().await?.lpush(key, value).await
+        self.observe("lpush", async { self.get_connection().await?.lpush(key, value).await }).await
     }
 
     pub async fn lpushmulti(&self, key: &str, value: Vec<&str>) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] lpush, key:{}, value:{:?}", key, value);
-        self.get_connection().await?.lpush(key, value).await
+        self.observe("lpushmulti", async { self.get_connection().await?.lpush(key, value).await }).await
     }
 
     pub async fn rpush(&self, key: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] rpush, key:{}, value:{}", key, value);
-        self.get_connection().await?.rpush(key, value).await
+        self.observe("rpush", async { self.get_connection().await?.rpush(key, value).await }).await
     }
 
     pub async fn rpushmulti(&self, key: &str, value: Vec<&str>) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] lpush, key:{}, value:{:?}", key, value);
-        self.get_connection().await?.rpush(key, value).await
+        self.observe("rpushmulti", async { self.get_connection().await?.rpush(key, value).await }).await
     }
 
     pub async fn lrangeall(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] lrangeall, key:{}", key);
-        self.get_connection().await?.lrange(key, 0, -1).await
+        self.observe("lrangeall", async { self.get_connection().await?.lrange(key, 0, -1).await }).await
     }
 
     pub async fn llen(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] llen, key:{}", key);
-        self.get_connection().await?.llen(key).await
+        self.observe("llen", async { self.get_connection().await?.llen(key).await }).await
     }
 
     pub async fn lrem(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] lrem, key:{}", key);
-        self.get_connection().await?.lrem(key, count, value).await
+        self.observe("lrem", async { self.get_connection().await?.lrem(key, count, value).await }).await
     }
 
     pub async fn linsert_after(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] linsert_after, key:{}", key);
-        self.get_connection().await?.linsert_after(key, count, value).await
+        self.observe("linsert_after", async { self.get_connection().await?.linsert_after(key, count, value).await }).await
     }
 
     pub async fn linsert_before(&self, key: &str, count: isize, value: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] linsert_before, key:{}", key);
-        self.get_connection().await?.linsert_before(key, count, value).await
+        self.observe("linsert_before", async { self.get_connection().await?.linsert_before(key, count, value).await }).await
     }
 
     pub async fn lset(&self, key: &str, count: isize, value: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] lset, key:{}", key);
-        self.get_connection().await?.lset(key, count, value).await
+        self.observe("lset", async { self.get_connection().await?.lset(key, count, value).await }).await
     }
 
     // hash operations
 
     pub async fn hget(&self, key: &str, field: &str) -> RedisResult<Option<String>> {
         trace!("[Tardis.CacheClient] hget, key:{}, field:{}", key, field);
-        self.get_connection().await?.hget(key, field).await
+        self.observe("hget", async { self.get_connection().await?.hget(key, field).await }).await
     }
 
     pub async fn hset(&self, key: &str, field: &str, value: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] hset, key:{}, field:{}, value:{}", key, field, value);
-        self.get_connection().await?.hset(key, field, value).await
+        self.observe("hset", async { self.get_connection().await?.hset(key, field, value).await }).await
     }
 
     pub async fn hset_nx(&self, key: &str, field: &str, value: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] hset_nx, key:{}, field:{}, value:{}", key, field, value);
-        self.get_connection().await?.hset_nx(key, field, value).await
+        self.observe("hset_nx", async { self.get_connection().await?.hset_nx(key, field, value).await }).await
     }
 
     pub async fn hdel(&self, key: &str, field: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] hdel, key:{}, field:{}", key, field);
-        self.get_connection().await?.hdel(key, field).await
+        self.observe("hdel", async { self.get_connection().await?.hdel(key, field).await }).await
     }
 
     pub async fn hdel_confirm(&self, key: &str, field: &str) -> RedisResult<()> {
@@ -234,60 +268,65 @@ impl TardisCacheClient {
 
     pub async fn hincr(&self, key: &str, field: &str, delta: isize) -> RedisResult<isize> {
         trace!("[Tardis.CacheClient] hincr, key:{}, field:{}, delta:{}", key, field, delta);
-        self.get_connection().await?.hincr(key, field, delta).await
+        self.observe("hincr", async { self.get_connection().await?.hincr(key, field, delta).await }).await
     }
 
     pub async fn hexists(&self, key: &str, field: &str) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] hexists, key:{}, field:{}", key, field);
-        self.get_connection().await?.hexists(key, field).await
+        self.observe("hexists", async { self.get_connection().await?.hexists(key, field).await }).await
     }
 
     pub async fn hkeys(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] hkeys, key:{}", key);
-        self.get_connection().await?.hkeys(key).await
+        self.observe("hkeys", async { self.get_connection().await?.hkeys(key).await }).await
     }
 
     pub async fn hvals(&self, key: &str) -> RedisResult<Vec<String>> {
         trace!("[Tardis.CacheClient] hvals, key:{}", key);
-        self.get_connection().await?.hvals(key).await
+        self.observe("hvals", async { self.get_connection().await?.hvals(key).await }).await
     }
 
     pub async fn hgetall(&self, key: &str) -> RedisResult<HashMap<String, String>> {
         trace!("[Tardis.CacheClient] hgetall, key:{}", key);
-        self.get_connection().await?.hgetall(key).await
+        self.observe("hgetall", async { self.get_connection().await?.hgetall(key).await }).await
     }
 
     pub async fn hlen(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] hlen, key:{}", key);
-        self.get_connection().await?.hlen(key).await
+        self.observe("hlen", async { self.get_connection().await?.hlen(key).await }).await
     }
 
     // bitmap operations
 
     pub async fn setbit(&self, key: &str, offset: usize, value: bool) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] setbit, key:{}, offset:{}, value:{}", key, offset, value);
-        self.get_connection().await?.setbit(key, offset, value).await
+        self.observe("setbit", async { self.get_connection().await?.setbit(key, offset, value).await }).await
     }
 
     pub async fn getbit(&self, key: &str, offset: usize) -> RedisResult<bool> {
         trace!("[Tardis.CacheClient] getbit, key:{}, offset:{}", key, offset);
-        self.get_connection().await?.getbit(key, offset).await
+        self.observe("getbit", async { self.get_connection().await?.getbit(key, offset).await }).await
     }
 
     pub async fn bitcount(&self, key: &str) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount, key:{}", key);
-        self.get_connection().await?.bitcount(key).await
+        self.observe("bitcount", async { self.get_connection().await?.bitcount(key).await }).await
     }
 
     pub async fn bitcount_range_by_byte(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount_range_by_byte, key:{}, start:{}, end:{}", key, start, end);
-        self.get_connection().await?.bitcount_range(key, start, end).await
+        self.observe("bitcount_range_by_byte", async { self.get_connection().await?.bitcount_range(key, start, end).await }).await
     }
 
     /// Supported from version redis 7.0.0
     pub async fn bitcount_range_by_bit(&self, key: &str, start: usize, end: usize) -> RedisResult<usize> {
         trace!("[Tardis.CacheClient] bitcount_range_by_bit, key:{}, start:{}, end:{}", key, start, end);
-        match redis::cmd("BITCOUNT").arg(key).arg(start).arg(end).arg("BIT").query_async(&mut self.get_connection().await?).await {
+        match self
+            .observe("bitcount_range_by_bit", async {
+                redis::cmd("BITCOUNT").arg(key).arg(start).arg(end).arg("BIT").query_async(&mut self.get_connection().await?).await
+            })
+            .await
+        {
             Ok(count) => Ok(count),
             Err(error) => Err(error),
         }
@@ -297,7 +336,7 @@ impl TardisCacheClient {
 
     pub async fn flushdb(&self) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] flushdb");
-        match redis::cmd("FLUSHDB").query_async(&mut self.get_connection().await?).await {
+        match self.observe("flushdb", async { redis::cmd("FLUSHDB").query_async(&mut self.get_connection().await?).await }).await {
             Ok(()) => Ok(()),
             Err(error) => Err(error),
         }
@@ -305,7 +344,7 @@ impl TardisCacheClient {
 
     pub async fn flushall(&self) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] flushall");
-        match redis::cmd("FLUSHALL").query_async(&mut self.get_connection().await?).await {
+        match self.observe("flushall", async { redis::cmd("FLUSHALL").query_async(&mut self.get_connection().await?).await }).await {
             Ok(()) => Ok(()),
             Err(error) => Err(error),
         }
@@ -365,7 +404,7 @@ impl TardisCacheClient {
     /// ```
     pub async fn publish(&self, channel: &str, message: &str) -> RedisResult<()> {
         trace!("[Tardis.CacheClient] publish, channel:{}, message:{}", channel, message);
-        self.get_connection().await?.publish(channel, message).await
+        self.observe("publish", async { self.get_connection().await?.publish(channel, message).await }).await
     }
 }
 
@@ -419,8 +458,12 @@ impl<'a> RedisScriptInvocation<'a> {
     }
     /// Do invoke the script.
     pub async fn invoke<T: FromRedisValue>(self) -> RedisResult<T> {
-        let mut conn = self.client.get_connection().await?;
-        self.invocation.invoke_async(&mut conn).await
+        self.client
+            .observe("script", async {
+                let mut conn = self.client.get_connection().await?;
+                self.invocation.invoke_async(&mut conn).await
+            })
+            .await
     }
 }
 
@@ -0,0 +1,20 @@
+//! # Cluster module.
+//!
+//! Node membership and messaging between the instances of the same application.
+//!
+//! 相同应用的多个实例之间的节点成员管理与消息通讯.
+//!
+//! ## Discovery
+//! Nodes are discovered from a static address list or from a registry kept in the cache,
+//! see [`ClusterWatchKind`](crate::config::config_dto::ClusterWatchKind).
+//!
+//! ## Heartbeat
+//! Each node connects to the `/tardis-cluster/ws` route of its peers with a [`TardisWSClient`](crate::web::ws_client::TardisWSClient)
+//! and sends heartbeats through it, membership changes are published as [`TardisClusterMembershipEvent`](cluster_processor::TardisClusterMembershipEvent).
+//!
+//! ## Messaging
+//! [`publish_event`](cluster_processor::TardisCluster::publish_event) and [`request_event`](cluster_processor::TardisCluster::request_event)
+//! deliver events to the [`TardisClusterSubscriber`](cluster_processor::TardisClusterSubscriber)s of other nodes.
+pub mod cluster_processor;
+mod cluster_receive;
+mod cluster_watch;
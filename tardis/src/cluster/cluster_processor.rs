@@ -0,0 +1,505 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{broadcast, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, trace, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::cache::cache_client::TardisCacheClient;
+use crate::config::config_dto::{ClusterConfig, ClusterWatchKind, FrameworkConfig};
+use crate::web::web_server::TardisWebServer;
+use crate::web::ws_client::{TardisWSClient, TardisWebSocketMessageExt};
+use crate::TardisFuns;
+
+use super::{cluster_receive, cluster_watch};
+
+/// Web server module code of the cluster route, the websocket endpoint is `/tardis-cluster/ws`
+pub const CLUSTER_MODULE_CODE: &str = "tardis-cluster";
+const CLUSTER_WS_PATH: &str = "ws";
+const MEMBERSHIP_CHANNEL_CAPACITY: usize = 64;
+
+/// A remote node of the cluster / 集群中的远程节点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisClusterNode {
+    /// Node identifier, the `fw.app.inst` of the remote node / 节点标识，即远程节点的 `fw.app.inst`
+    pub id: String,
+    /// Node address / 节点地址
+    pub addr: String,
+}
+
+/// Membership change of the cluster / 集群成员变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TardisClusterMembershipEvent {
+    /// A node answered the heartbeat for the first time / 节点首次响应心跳
+    Joined(TardisClusterNode),
+    /// A node stopped answering the heartbeat or was removed from discovery / 节点停止响应心跳或已从发现源中移除
+    Left(TardisClusterNode),
+}
+
+/// Target nodes of [publish_event](TardisCluster::publish_event) / [publish_event](TardisCluster::publish_event) 的目标节点
+#[derive(Debug, Clone)]
+pub enum ClusterEventTarget {
+    /// All alive nodes except the current one / 除当前节点外的所有存活节点
+    Broadcast,
+    /// A single node identified by its id / 指定标识的单个节点
+    Single(String),
+    /// Multiple nodes identified by their ids / 指定标识的多个节点
+    Multi(Vec<String>),
+}
+
+/// An event sent from one node to another / 节点间发送的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TardisClusterMessageReq {
+    /// Message identifier / 消息标识
+    pub msg_id: String,
+    /// Identifier of the requesting node / 请求节点的标识
+    pub req_node_id: String,
+    /// Event name / 事件名称
+    pub event: String,
+    /// Message body / 消息体
+    pub msg: Value,
+}
+
+/// Handler of cluster events / 集群事件处理器
+///
+/// # Examples
+/// ```ignore
+/// use tardis::cluster::cluster_processor::{TardisClusterMessageReq, TardisClusterSubscriber};
+/// struct EchoSubscriber;
+/// #[tardis::async_trait::async_trait]
+/// impl TardisClusterSubscriber for EchoSubscriber {
+///     fn event_name(&self) -> String {
+///         "echo".to_string()
+///     }
+///     async fn subscribe(&self, message_req: TardisClusterMessageReq) -> TardisResult<Option<Value>> {
+///         Ok(Some(message_req.msg))
+///     }
+/// }
+/// TardisFuns::cluster().subscribe(EchoSubscriber);
+/// ```
+#[async_trait::async_trait]
+pub trait TardisClusterSubscriber: Send + Sync + 'static {
+    /// The event name this subscriber handles / 处理的事件名称
+    fn event_name(&self) -> String;
+    /// Handle the event, the returned value is the response of [request_event](TardisCluster::request_event)
+    ///
+    /// 处理事件，返回值作为 [request_event](TardisCluster::request_event) 的响应
+    async fn subscribe(&self, message_req: TardisClusterMessageReq) -> TardisResult<Option<Value>>;
+}
+
+/// Frames exchanged on the cluster websocket
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum ClusterFrame {
+    Ping {
+        node_id: String,
+    },
+    Pong {
+        node_id: String,
+    },
+    Event {
+        req: TardisClusterMessageReq,
+        need_resp: bool,
+    },
+    Resp {
+        msg_id: String,
+        resp_node_id: String,
+        result: Result<Value, TardisError>,
+    },
+}
+
+/// Registry entry stored in the cache when `watch_kind` is `cache`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ClusterCacheEntry {
+    pub(crate) addr: String,
+    pub(crate) ts: i64,
+}
+
+pub(crate) struct ClusterRemoteNode {
+    pub(crate) addr: String,
+    pub(crate) id: Option<String>,
+    pub(crate) client: TardisWSClient,
+    pub(crate) last_pong: Option<Instant>,
+    pub(crate) alive: bool,
+}
+
+impl ClusterRemoteNode {
+    pub(crate) fn to_node(&self) -> TardisClusterNode {
+        TardisClusterNode {
+            id: self.id.clone().unwrap_or_default(),
+            addr: self.addr.clone(),
+        }
+    }
+}
+
+pub(crate) type ClusterResolver = Arc<dyn Fn() -> Arc<TardisCluster> + Send + Sync>;
+
+/// Cluster handle / 集群操作
+///
+/// # Steps to use / 使用步骤
+///
+/// 1. Enable the `cluster` feature and add the `[fw.cluster]` configuration, the web server must be configured too
+///
+///    启用 `cluster` 特性并添加 `[fw.cluster]` 配置，同时需要配置Web服务
+/// 2. Use `TardisFuns::cluster()` to operate the cluster / 使用 `TardisFuns::cluster()` 操作集群, E.g:
+/// ```ignore
+/// use tardis::TardisFuns;
+/// use tardis::cluster::cluster_processor::ClusterEventTarget;
+/// let mut membership = TardisFuns::cluster().watch_membership();
+/// TardisFuns::cluster().publish_event("cache_evict", json!({"key": "k1"}), ClusterEventTarget::Broadcast).await?;
+/// let resp = TardisFuns::cluster().request_event("echo", json!("hi"), "inst_xxx").await?;
+/// ```
+pub struct TardisCluster {
+    node_id: String,
+    app_id: String,
+    access_addr: String,
+    pub(crate) config: ClusterConfig,
+    pub(crate) cache: Option<Arc<TardisCacheClient>>,
+    pub(crate) nodes: RwLock<HashMap<String, ClusterRemoteNode>>,
+    /// addresses that turned out to point to the current node
+    pub(crate) ignored_addrs: RwLock<HashSet<String>>,
+    subscribers: RwLock<HashMap<String, Arc<dyn TardisClusterSubscriber>>>,
+    pending: Mutex<HashMap<String, oneshot::Sender<TardisResult<Value>>>>,
+    membership: broadcast::Sender<TardisClusterMembershipEvent>,
+    pub(crate) cancel: CancellationToken,
+}
+
+impl Default for TardisCluster {
+    fn default() -> Self {
+        TardisCluster {
+            node_id: String::new(),
+            app_id: String::new(),
+            access_addr: String::new(),
+            config: ClusterConfig::default(),
+            cache: None,
+            nodes: RwLock::new(HashMap::new()),
+            ignored_addrs: RwLock::new(HashSet::new()),
+            subscribers: RwLock::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            membership: broadcast::channel(MEMBERSHIP_CHANNEL_CAPACITY).0,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for TardisCluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TardisCluster")
+            .field("node_id", &self.node_id)
+            .field("app_id", &self.app_id)
+            .field("access_addr", &self.access_addr)
+            .field("config", &self.config)
+            .field("nodes", &self.nodes())
+            .finish()
+    }
+}
+
+impl TardisCluster {
+    /// Initialize by the framework configuration / 通过框架配置初始化
+    ///
+    /// The node id is `fw.app.inst`, the registry is grouped by `fw.app.id`.
+    pub async fn init_by_conf(conf: &FrameworkConfig) -> TardisResult<Arc<TardisCluster>> {
+        let cluster_config = conf.cluster.as_ref().ok_or_else(|| TardisError::format_error("[Tardis.Cluster] Missing cluster config", "406-tardis-cluster-config-error"))?;
+        let access_addr = if let Some(access_addr) = &cluster_config.access_addr {
+            access_addr.clone()
+        } else {
+            let web_server_config = conf.web_server.as_ref().ok_or_else(|| {
+                TardisError::format_error(
+                    "[Tardis.Cluster] The web server config is required to derive the access address",
+                    "406-tardis-cluster-config-error",
+                )
+            })?;
+            let mut host = web_server_config.access_host.unwrap_or(web_server_config.host);
+            if host.is_unspecified() {
+                warn!("[Tardis.Cluster] The web server host is unspecified, using localhost as the access address, set [fw.cluster.access_addr] for multi-host deployments");
+                host = crate::consts::IP_LOCALHOST;
+            }
+            let port = web_server_config.access_port.unwrap_or(web_server_config.port);
+            let protocol = if web_server_config.tls_key.is_some() { "wss" } else { "ws" };
+            format!("{protocol}://{}", SocketAddr::new(host, port))
+        };
+        let cache = match cluster_config.watch_kind {
+            ClusterWatchKind::Static => None,
+            ClusterWatchKind::Cache => Some(crate::tardis_instance().cache.get("").ok_or_else(|| {
+                TardisError::format_error(
+                    "[Tardis.Cluster] The default cache module is required when watch kind is cache",
+                    "406-tardis-cluster-config-error",
+                )
+            })?),
+        };
+        Self::init(&conf.app.inst, &conf.app.id, &access_addr, cluster_config, cache).await
+    }
+
+    /// Initialize a cluster node / 初始化集群节点
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - identifier of the current node / 当前节点标识
+    /// * `app_id` - nodes with the same app id form a cluster / 相同应用标识的节点组成一个集群
+    /// * `access_addr` - address other nodes use to reach the current node / 其它节点访问当前节点的地址
+    /// * `cache` - required when `watch_kind` is `cache` / `watch_kind` 为 `cache` 时必须提供
+    ///
+    /// The websocket route should be mounted to a web server by [mount](Self::mount).
+    pub async fn init(node_id: &str, app_id: &str, access_addr: &str, config: &ClusterConfig, cache: Option<Arc<TardisCacheClient>>) -> TardisResult<Arc<TardisCluster>> {
+        info!("[Tardis.Cluster] Initializing, node:{}, app:{}, access addr:{}", node_id, app_id, access_addr);
+        if config.watch_kind == ClusterWatchKind::Cache && cache.is_none() {
+            return Err(TardisError::format_error(
+                "[Tardis.Cluster] A cache client is required when watch kind is cache",
+                "406-tardis-cluster-config-error",
+            ));
+        }
+        let cluster = Arc::new(TardisCluster {
+            node_id: node_id.to_string(),
+            app_id: app_id.to_string(),
+            access_addr: normalize_addr(access_addr),
+            config: config.clone(),
+            cache,
+            ..Default::default()
+        });
+        cluster_watch::start(&cluster);
+        info!("[Tardis.Cluster] Initialized, node:{}, app:{}", node_id, app_id);
+        Ok(cluster)
+    }
+
+    /// Mount the websocket route of this node to the web server / 将本节点的websocket路由挂载到Web服务
+    ///
+    /// It should be called before the web server starts.
+    pub async fn mount(self: &Arc<Self>, web_server: &TardisWebServer) {
+        let cluster = self.clone();
+        web_server.add_module_raw(CLUSTER_MODULE_CODE, cluster_receive::route(Arc::new(move || cluster.clone()))).await;
+    }
+
+    /// Mount the route which always dispatches to `TardisFuns::cluster()`, so the cluster can be re-initialized independently of the web server
+    pub(crate) async fn mount_global(web_server: &TardisWebServer) {
+        web_server.add_module_raw(CLUSTER_MODULE_CODE, cluster_receive::route(Arc::new(TardisFuns::cluster))).await;
+    }
+
+    /// Identifier of the current node / 当前节点标识
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Application identifier of the cluster / 集群的应用标识
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// Address other nodes use to reach the current node / 其它节点访问当前节点的地址
+    pub fn access_addr(&self) -> &str {
+        &self.access_addr
+    }
+
+    /// Get the alive remote nodes / 获取存活的远程节点
+    pub fn nodes(&self) -> Vec<TardisClusterNode> {
+        self.nodes.read().expect("encounter an poisoned lock when trying to read cluster nodes").values().filter(|node| node.alive).map(ClusterRemoteNode::to_node).collect()
+    }
+
+    /// Watch membership changes / 监听成员变更
+    pub fn watch_membership(&self) -> broadcast::Receiver<TardisClusterMembershipEvent> {
+        self.membership.subscribe()
+    }
+
+    /// Register a subscriber, it replaces the previous one with the same event name / 注册订阅者，会替换相同事件名称的订阅者
+    pub fn subscribe(&self, subscriber: impl TardisClusterSubscriber) {
+        let event = subscriber.event_name();
+        debug!("[Tardis.Cluster] Subscribe event {}", event);
+        self.subscribers.write().expect("encounter an poisoned lock when trying to write cluster subscribers").insert(event, Arc::new(subscriber));
+    }
+
+    /// Remove the subscriber of the event / 移除事件的订阅者
+    pub fn unsubscribe(&self, event: &str) {
+        debug!("[Tardis.Cluster] Unsubscribe event {}", event);
+        self.subscribers.write().expect("encounter an poisoned lock when trying to write cluster subscribers").remove(event);
+    }
+
+    /// Publish an event to other nodes without waiting for responses / 向其它节点发布事件，不等待响应
+    pub async fn publish_event(&self, event: impl Into<String>, msg: Value, target: ClusterEventTarget) -> TardisResult<()> {
+        let event = event.into();
+        let clients = self.target_clients(&target)?;
+        trace!("[Tardis.Cluster] Publish event {} to {} nodes", event, clients.len());
+        let frame = ClusterFrame::Event {
+            req: self.new_message_req(event, msg),
+            need_resp: false,
+        };
+        let mut result = Ok(());
+        for (node_id, client) in clients {
+            if let Err(e) = client.send_obj(&frame).await {
+                warn!("[Tardis.Cluster] Publish event to node {} error: {}", node_id, e);
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Send an event to a node and wait for the response of its subscriber / 向节点发送事件并等待其订阅者的响应
+    pub async fn request_event(&self, event: impl Into<String>, msg: Value, node_id: &str) -> TardisResult<Value> {
+        let event = event.into();
+        let (_, client) = self.target_clients(&ClusterEventTarget::Single(node_id.to_string()))?.pop().expect("single target should resolve to one node");
+        let req = self.new_message_req(event, msg);
+        let msg_id = req.msg_id.clone();
+        trace!("[Tardis.Cluster] Request event {} to node {}, msg id:{}", req.event, node_id, msg_id);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().expect("encounter an poisoned lock when trying to lock cluster pending requests").insert(msg_id.clone(), tx);
+        if let Err(e) = client.send_obj(&ClusterFrame::Event { req, need_resp: true }).await {
+            self.remove_pending(&msg_id);
+            return Err(e);
+        }
+        match tokio::time::timeout(Duration::from_millis(self.config.request_timeout_ms), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(TardisError::internal_error(
+                &format!("[Tardis.Cluster] Request {msg_id} to node {node_id} was dropped"),
+                "-1-tardis-cluster-receive-message-error",
+            )),
+            Err(_) => {
+                self.remove_pending(&msg_id);
+                Err(TardisError::timeout(
+                    &format!("[Tardis.Cluster] Request {msg_id} to node {node_id} timeout"),
+                    "408-tardis-cluster-request-timeout",
+                ))
+            }
+        }
+    }
+
+    /// Stop heartbeats and disconnect from other nodes / 停止心跳并断开与其它节点的连接
+    pub async fn shutdown(&self) -> TardisResult<()> {
+        if self.node_id.is_empty() || self.cancel.is_cancelled() {
+            return Ok(());
+        }
+        info!("[Tardis.Cluster] Shutdown node {}", self.node_id);
+        self.cancel.cancel();
+        if let Some(cache) = &self.cache {
+            cache.hdel(&cluster_watch::cache_registry_key(&self.app_id), &self.node_id).await?;
+        }
+        let nodes = std::mem::take(&mut *self.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes"));
+        for node in nodes.into_values() {
+            let _ = node.client.send_raw(Message::Close(None)).await;
+        }
+        self.pending.lock().expect("encounter an poisoned lock when trying to lock cluster pending requests").clear();
+        Ok(())
+    }
+
+    fn new_message_req(&self, event: String, msg: Value) -> TardisClusterMessageReq {
+        TardisClusterMessageReq {
+            msg_id: TardisFuns::field.nanoid(),
+            req_node_id: self.node_id.clone(),
+            event,
+            msg,
+        }
+    }
+
+    fn remove_pending(&self, msg_id: &str) -> Option<oneshot::Sender<TardisResult<Value>>> {
+        self.pending.lock().expect("encounter an poisoned lock when trying to lock cluster pending requests").remove(msg_id)
+    }
+
+    fn target_clients(&self, target: &ClusterEventTarget) -> TardisResult<Vec<(String, TardisWSClient)>> {
+        let nodes = self.nodes.read().expect("encounter an poisoned lock when trying to read cluster nodes");
+        let alive = nodes.values().filter(|node| node.alive).filter_map(|node| node.id.as_ref().map(|id| (id.clone(), node.client.clone())));
+        let node_ids = match target {
+            ClusterEventTarget::Broadcast => return Ok(alive.collect()),
+            ClusterEventTarget::Single(node_id) => std::slice::from_ref(node_id),
+            ClusterEventTarget::Multi(node_ids) => node_ids.as_slice(),
+        };
+        let mut alive = alive.collect::<HashMap<_, _>>();
+        node_ids
+            .iter()
+            .map(|node_id| {
+                alive.remove(node_id).map(|client| (node_id.clone(), client)).ok_or_else(|| {
+                    TardisError::not_found(
+                        &format!("[Tardis.Cluster] Node {node_id} doesn't exist or isn't alive"),
+                        "404-tardis-cluster-publish-message-node-not-exit",
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Dispatch an event from another node to the local subscriber
+    pub(crate) async fn dispatch(&self, req: TardisClusterMessageReq) -> TardisResult<Option<Value>> {
+        let subscriber = self.subscribers.read().expect("encounter an poisoned lock when trying to read cluster subscribers").get(&req.event).cloned();
+        match subscriber {
+            Some(subscriber) => subscriber.subscribe(req).await,
+            None => Err(TardisError::not_found(
+                &format!("[Tardis.Cluster] No subscriber found for event {}", req.event),
+                "404-tardis-cluster-subscriber-not-exist",
+            )),
+        }
+    }
+
+    /// Handle a message received by the websocket client connected to `addr`
+    pub(crate) fn on_peer_message(&self, addr: &str, message: Message) {
+        if !message.is_text() {
+            return;
+        }
+        let frame = match message.str_to_obj::<ClusterFrame>() {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("[Tardis.Cluster] Invalid message from {}: {}", addr, e);
+                return;
+            }
+        };
+        match frame {
+            ClusterFrame::Pong { node_id } => self.on_pong(addr, node_id),
+            ClusterFrame::Resp { msg_id, resp_node_id, result } => {
+                trace!("[Tardis.Cluster] Receive response {} from node {}", msg_id, resp_node_id);
+                if let Some(tx) = self.remove_pending(&msg_id) {
+                    let _ = tx.send(result);
+                }
+            }
+            frame => debug!("[Tardis.Cluster] Unexpected message from {}: {:?}", addr, frame),
+        }
+    }
+
+    fn on_pong(&self, addr: &str, node_id: String) {
+        if node_id == self.node_id {
+            debug!("[Tardis.Cluster] Address {} points to the current node, ignore it", addr);
+            self.ignored_addrs.write().expect("encounter an poisoned lock when trying to write cluster ignored addresses").insert(addr.to_string());
+            self.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes").remove(addr);
+            return;
+        }
+        let joined = {
+            let mut nodes = self.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes");
+            let Some(node) = nodes.get_mut(addr) else {
+                return;
+            };
+            node.last_pong = Some(Instant::now());
+            node.id = Some(node_id);
+            if node.alive {
+                None
+            } else {
+                node.alive = true;
+                Some(node.to_node())
+            }
+        };
+        if let Some(node) = joined {
+            info!("[Tardis.Cluster] Node {} joined, addr:{}", node.id, node.addr);
+            self.notify_membership(TardisClusterMembershipEvent::Joined(node));
+        }
+    }
+
+    pub(crate) fn notify_membership(&self, event: TardisClusterMembershipEvent) {
+        // no receiver is fine
+        let _ = self.membership.send(event);
+    }
+}
+
+/// Normalize a node address into `ws(s)://host:port`
+pub(crate) fn normalize_addr(addr: &str) -> String {
+    let addr = addr.trim().trim_end_matches('/');
+    if addr.contains("://") {
+        addr.to_string()
+    } else {
+        format!("ws://{addr}")
+    }
+}
+
+/// Websocket url of the cluster route of a node
+pub(crate) fn node_ws_url(addr: &str) -> String {
+    format!("{addr}/{CLUSTER_MODULE_CODE}/{CLUSTER_WS_PATH}")
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use poem::web::websocket::{Message, WebSocket};
+use poem::web::Data;
+use poem::{handler, EndpointExt, IntoResponse, Route};
+use tokio::sync::mpsc;
+use tracing::{debug, trace, warn};
+
+use crate::TardisFuns;
+
+use super::cluster_processor::{ClusterFrame, ClusterResolver};
+
+pub(crate) fn route(resolver: ClusterResolver) -> Route {
+    Route::new().at("/ws", poem::get(ws.data(resolver)))
+}
+
+#[handler]
+async fn ws(websocket: WebSocket, resolver: Data<&ClusterResolver>) -> impl IntoResponse {
+    let cluster = resolver();
+    websocket.on_upgrade(move |socket| async move {
+        let (mut sink, mut stream) = socket.split();
+        let (tx, mut rx) = mpsc::unbounded_channel::<ClusterFrame>();
+        let writer = tokio::spawn(async move {
+            while let Some(frame) = rx.recv().await {
+                match TardisFuns::json.obj_to_string(&frame) {
+                    Ok(text) => {
+                        if let Err(e) = sink.send(Message::Text(text)).await {
+                            debug!("[Tardis.Cluster] Send message error: {}", e);
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("[Tardis.Cluster] Serialize message error: {}", e),
+                }
+            }
+        });
+        loop {
+            // upgraded connections outlive the web server, so stop answering once the node is shut down
+            let message = tokio::select! {
+                _ = cluster.cancel.cancelled() => break,
+                message = stream.next() => message,
+            };
+            let Some(Ok(message)) = message else {
+                break;
+            };
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let frame = match TardisFuns::json.str_to_obj::<ClusterFrame>(&text) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    warn!("[Tardis.Cluster] Invalid message {}: {}", text, e);
+                    continue;
+                }
+            };
+            match frame {
+                ClusterFrame::Ping { node_id } => {
+                    trace!("[Tardis.Cluster] Receive heartbeat from node {}", node_id);
+                    let _ = tx.send(ClusterFrame::Pong {
+                        node_id: cluster.node_id().to_string(),
+                    });
+                }
+                ClusterFrame::Event { req, need_resp } => {
+                    let cluster = Arc::clone(&cluster);
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        trace!("[Tardis.Cluster] Receive event {} from node {}, msg id:{}", req.event, req.req_node_id, req.msg_id);
+                        let msg_id = req.msg_id.clone();
+                        let result = cluster.dispatch(req).await.map(Option::unwrap_or_default);
+                        if need_resp {
+                            let _ = tx.send(ClusterFrame::Resp {
+                                msg_id,
+                                resp_node_id: cluster.node_id().to_string(),
+                                result,
+                            });
+                        } else if let Err(e) = result {
+                            warn!("[Tardis.Cluster] Handle event error: {}", e);
+                        }
+                    });
+                }
+                frame => debug!("[Tardis.Cluster] Unexpected message: {:?}", frame),
+            }
+        }
+        drop(tx);
+        let _ = writer.await;
+    })
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use tracing::{debug, info, trace, warn};
+
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::ClusterWatchKind;
+use crate::web::ws_client::TardisWSClient;
+use crate::TardisFuns;
+
+use super::cluster_processor::{node_ws_url, normalize_addr, ClusterCacheEntry, ClusterFrame, ClusterRemoteNode, TardisCluster, TardisClusterMembershipEvent};
+
+pub(crate) fn cache_registry_key(app_id: &str) -> String {
+    format!("tardis:cluster:{app_id}")
+}
+
+/// Spawn the heartbeat loop, it stops when the cluster is shut down or dropped
+pub(crate) fn start(cluster: &Arc<TardisCluster>) {
+    let weak = Arc::downgrade(cluster);
+    let cancel = cluster.cancel.clone();
+    let interval = Duration::from_secs(cluster.config.heartbeat_interval_sec.max(1));
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            let Some(cluster) = weak.upgrade() else {
+                break;
+            };
+            if let Err(e) = tick(&cluster).await {
+                warn!("[Tardis.Cluster] Heartbeat error: {}", e);
+            }
+        }
+        debug!("[Tardis.Cluster] Heartbeat stopped");
+    });
+}
+
+async fn tick(cluster: &Arc<TardisCluster>) -> TardisResult<()> {
+    let discovered = discover(cluster).await?;
+    sync_nodes(cluster, discovered).await;
+    expire_nodes(cluster);
+    send_heartbeat(cluster).await;
+    Ok(())
+}
+
+/// Get the addresses of other nodes, with their ids if known
+async fn discover(cluster: &TardisCluster) -> TardisResult<HashMap<String, Option<String>>> {
+    let ignored_addrs = cluster.ignored_addrs.read().expect("encounter an poisoned lock when trying to read cluster ignored addresses").clone();
+    let mut discovered = HashMap::new();
+    match cluster.config.watch_kind {
+        ClusterWatchKind::Static => {
+            for addr in &cluster.config.static_nodes {
+                discovered.insert(normalize_addr(addr), None);
+            }
+        }
+        ClusterWatchKind::Cache => {
+            let Some(cache) = &cluster.cache else {
+                return Ok(discovered);
+            };
+            let key = cache_registry_key(cluster.app_id());
+            let now = chrono::Utc::now().timestamp_millis();
+            let entry = ClusterCacheEntry {
+                addr: cluster.access_addr().to_string(),
+                ts: now,
+            };
+            cache.hset(&key, cluster.node_id(), &TardisFuns::json.obj_to_string(&entry)?).await?;
+            let timeout_ms = (cluster.config.heartbeat_timeout_sec * 1000) as i64;
+            for (node_id, value) in cache.hgetall(&key).await? {
+                if node_id == cluster.node_id() {
+                    continue;
+                }
+                match TardisFuns::json.str_to_obj::<ClusterCacheEntry>(&value) {
+                    Ok(entry) if now - entry.ts <= timeout_ms => {
+                        discovered.insert(normalize_addr(&entry.addr), Some(node_id));
+                    }
+                    Ok(_) => {
+                        debug!("[Tardis.Cluster] Remove stale registry of node {}", node_id);
+                        cache.hdel(&key, &node_id).await?;
+                    }
+                    Err(e) => {
+                        warn!("[Tardis.Cluster] Invalid registry of node {}: {}", node_id, e);
+                        cache.hdel(&key, &node_id).await?;
+                    }
+                }
+            }
+        }
+    }
+    discovered.remove(cluster.access_addr());
+    discovered.retain(|addr, _| !ignored_addrs.contains(addr));
+    Ok(discovered)
+}
+
+/// Timeout of connecting or sending to a node, so that an unreachable node doesn't hold up the heartbeats of the others
+fn peer_timeout(cluster: &TardisCluster) -> Duration {
+    Duration::from_secs(cluster.config.heartbeat_interval_sec.max(1))
+}
+
+/// Connect to newly discovered nodes concurrently and remove vanished ones
+async fn sync_nodes(cluster: &Arc<TardisCluster>, discovered: HashMap<String, Option<String>>) {
+    let new_addrs = {
+        let nodes = cluster.nodes.read().expect("encounter an poisoned lock when trying to read cluster nodes");
+        discovered.iter().filter(|(addr, _)| !nodes.contains_key(*addr)).map(|(addr, id)| (addr.clone(), id.clone())).collect::<Vec<_>>()
+    };
+    let timeout = peer_timeout(cluster);
+    let connects = new_addrs.into_iter().map(|(addr, id)| {
+        let weak = Arc::downgrade(cluster);
+        let callback_addr = addr.clone();
+        let on_message = move |message| {
+            if let Some(cluster) = weak.upgrade() {
+                cluster.on_peer_message(&callback_addr, message);
+            }
+            std::future::ready(None)
+        };
+        async move {
+            let result = tokio::time::timeout(timeout, TardisWSClient::connect(&node_ws_url(&addr), on_message)).await;
+            (addr, id, result)
+        }
+    });
+    for (addr, id, result) in join_all(connects).await {
+        match result {
+            Ok(Ok(client)) => {
+                trace!("[Tardis.Cluster] Connected to {}", addr);
+                cluster.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes").insert(
+                    addr.clone(),
+                    ClusterRemoteNode {
+                        addr,
+                        id,
+                        client,
+                        last_pong: None,
+                        alive: false,
+                    },
+                );
+            }
+            // retried on the next tick
+            Ok(Err(e)) => debug!("[Tardis.Cluster] Node {} isn't reachable: {}", addr, e),
+            Err(_) => debug!("[Tardis.Cluster] Node {} isn't reachable: connect timed out after {:?}", addr, timeout),
+        }
+    }
+    let removed = {
+        let mut nodes = cluster.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes");
+        let removed_addrs = nodes.keys().filter(|addr| !discovered.contains_key(*addr)).cloned().collect::<Vec<_>>();
+        removed_addrs.into_iter().filter_map(|addr| nodes.remove(&addr)).collect::<Vec<_>>()
+    };
+    for node in removed {
+        if node.alive {
+            info!("[Tardis.Cluster] Node {} left, addr:{}", node.id.as_deref().unwrap_or_default(), node.addr);
+            cluster.notify_membership(TardisClusterMembershipEvent::Left(node.to_node()));
+        }
+    }
+}
+
+/// Mark the nodes without heartbeat response as left
+fn expire_nodes(cluster: &TardisCluster) {
+    let timeout = Duration::from_secs(cluster.config.heartbeat_timeout_sec);
+    let expired = {
+        let mut nodes = cluster.nodes.write().expect("encounter an poisoned lock when trying to write cluster nodes");
+        nodes
+            .values_mut()
+            .filter(|node| node.alive && !matches!(node.last_pong, Some(last_pong) if last_pong.elapsed() <= timeout))
+            .map(|node| {
+                node.alive = false;
+                node.to_node()
+            })
+            .collect::<Vec<_>>()
+    };
+    for node in expired {
+        info!("[Tardis.Cluster] Node {} left, addr:{}", node.id, node.addr);
+        cluster.notify_membership(TardisClusterMembershipEvent::Left(node));
+    }
+}
+
+async fn send_heartbeat(cluster: &TardisCluster) {
+    let clients = {
+        let nodes = cluster.nodes.read().expect("encounter an poisoned lock when trying to read cluster nodes");
+        nodes.values().map(|node| (node.addr.clone(), node.client.clone())).collect::<Vec<_>>()
+    };
+    let ping = ClusterFrame::Ping {
+        node_id: cluster.node_id().to_string(),
+    };
+    let timeout = peer_timeout(cluster);
+    let sends = clients.into_iter().map(|(addr, client)| {
+        let ping = &ping;
+        async move {
+            // a disconnected client reconnects while sending
+            match tokio::time::timeout(timeout, client.send_obj(ping)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => debug!("[Tardis.Cluster] Send heartbeat to {} error: {}", addr, e),
+                Err(_) => debug!("[Tardis.Cluster] Send heartbeat to {} timed out after {:?}", addr, timeout),
+            }
+        }
+    });
+    join_all(sends).await;
+}
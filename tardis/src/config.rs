@@ -1,13 +1,44 @@
 //! # Configuration module.
 //!
 //! ## Config Center
-//! using nacos as a config center
+//! built-in config centers: nacos, http, local directory and redis,
+//! custom ones can be registered by [`TardisConfig::register_conf_center`](config_dto::TardisConfig::register_conf_center)
 //!
 //! ## Config Processor
 //!
+//! ## Environment Variables
+//! environment variables like `TARDIS_FW__DB__URL` override the config files, see [`config_env`],
+//! the effective config can be dumped by [`TardisConfig::dump`](config_dto::TardisConfig::dump)
+//!
+//! ## Config Secret
+//! secret placeholders like `${secret:env:NAME}` can be used in any string value, see [`config_secret`]
+//!
 
+#[cfg(feature = "conf-remote")]
+pub mod config_dir;
+#[cfg(feature = "conf-remote")]
+pub(crate) mod config_document;
 pub mod config_dto;
+pub mod config_dump;
+pub mod config_env;
+#[cfg(feature = "conf-remote")]
+pub mod config_http;
 #[cfg(feature = "conf-remote")]
 pub mod config_nacos;
+pub mod config_notifier;
 pub mod config_processor;
+#[cfg(all(feature = "conf-remote", feature = "cache"))]
+pub mod config_redis;
+#[cfg(feature = "conf-schema")]
+pub mod config_schema;
+pub mod config_secret;
 pub(crate) mod config_utils;
+pub mod config_validation;
+#[cfg(feature = "conf-watch")]
+pub(crate) mod config_watcher;
+
+/// The underlying [config](https://docs.rs/config) crate, used to implement custom [`ConfCenterProcess`](config_processor::ConfCenterProcess)
+///
+/// 底层的 [config](https://docs.rs/config) 库，用于实现自定义的 [`ConfCenterProcess`](config_processor::ConfCenterProcess)
+#[cfg(feature = "conf-remote")]
+pub use ::config as config_rs;
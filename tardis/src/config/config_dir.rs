@@ -0,0 +1,116 @@
+//! Config center of a local directory / 基于本地目录的配置中心
+//!
+//! The documents are the files `<url>/<data_id>.<extension of format>` , e.g. `/etc/my-app/my-app-default.toml` .
+//! Changes are detected by watching the directory, polling is used if the directory can't be watched.
+//! It's suitable for configurations mounted into containers, e.g. kubernetes config maps.
+//!
+//! 文档为文件 `<url>/<data_id>.<格式的扩展名>` ，如 `/etc/my-app/my-app-default.toml` .
+//! 通过监听目录检测变更，无法监听时改为轮询. 适用于挂载到容器中的配置，如kubernetes的config map.
+//!
+//! ```toml
+//! [fw.conf_center]
+//! kind = "dir"
+//! url = "/etc/my-app"
+//! ```
+use std::path::PathBuf;
+
+use config::FileStoredFormat;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tracing::{debug, warn};
+
+use super::config_document::{ConfDocumentLoader, ConfDocumentProcessor};
+use super::config_processor::{ConfCenterContext, ConfCenterProcess};
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+
+#[derive(Debug)]
+pub(crate) struct ConfDirLoader {
+    dir: PathBuf,
+    extension: &'static str,
+}
+
+impl ConfDirLoader {
+    fn file_name(&self, data_id: &str) -> String {
+        format!("{}.{}", data_id, self.extension)
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfDocumentLoader for ConfDirLoader {
+    async fn load(&self, data_id: &str) -> TardisResult<Option<String>> {
+        let path = self.dir.join(self.file_name(data_id));
+        match tokio::fs::read_to_string(&path).await {
+            Ok(text) => Ok(Some(text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(TardisError::io_error(&format!("[Tardis.Config] Read {path:?} error: {e}"), "-1-tardis-config-error")),
+        }
+    }
+}
+
+/// # Local directory config processor
+#[derive(Debug)]
+pub(crate) struct ConfDirProcessor {
+    dir: PathBuf,
+    processor: ConfDocumentProcessor<ConfDirLoader>,
+}
+
+impl ConfDirProcessor {
+    /// create a new local directory config processor
+    pub(crate) fn init(ctx: &ConfCenterContext) -> TardisResult<ConfDirProcessor> {
+        let dir = PathBuf::from(ctx.config.url.strip_prefix("file://").unwrap_or(&ctx.config.url));
+        if !dir.is_dir() {
+            return Err(TardisError::not_found(
+                &format!("[Tardis.Config] Config directory {dir:?} not found"),
+                "404-tardis-config-not-exist",
+            ));
+        }
+        let loader = ConfDirLoader {
+            dir: dir.clone(),
+            extension: ctx.format.file_extensions()[0],
+        };
+        let processor = ConfDocumentProcessor::new(ctx, loader);
+        Ok(ConfDirProcessor { dir, processor })
+    }
+
+    fn watch_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) -> notify::Result<()> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<notify::Event>();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => warn!("[Tardis.Config] Watch config directory error: {e}"),
+        })?;
+        watcher.watch(&self.dir, RecursiveMode::NonRecursive)?;
+        let sources = self.processor.sources.clone();
+        let reload_notifier = reload_notifier.clone();
+        tokio::spawn(async move {
+            // the watcher stops when dropped
+            let _watcher = watcher;
+            while let Some(event) = rx.recv().await {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                // the events are not filtered by the file names, since kubernetes updates the config maps by swapping the `..data` symlink,
+                // only reload when the content changed, since editors may touch the files without modification
+                if reload_notifier.is_closed() || ConfDocumentProcessor::check_update(&sources, &reload_notifier).await {
+                    break;
+                }
+            }
+            debug!("[Tardis.Config] Config directory listener closed");
+        });
+        Ok(())
+    }
+}
+
+impl ConfCenterProcess for ConfDirProcessor {
+    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        if let Err(e) = self.watch_update(reload_notifier) {
+            warn!("[Tardis.Config] Watch config directory {:?} error: {e}, fallback to polling", self.dir);
+            self.processor.poll_update(reload_notifier);
+        }
+    }
+
+    fn register_to_config(&self, conf: config::ConfigBuilder<config::builder::AsyncState>) -> config::ConfigBuilder<config::builder::AsyncState> {
+        self.processor.register_to_config(conf)
+    }
+}
@@ -0,0 +1,167 @@
+//! Config centers storing the configuration as whole documents / 以完整文档存储配置的配置中心
+//!
+//! Like nacos, each application has two documents: `<fw.app.id>-default` and `<fw.app.id>-<profile>`,
+//! the content is parsed with [`ConfCenterConfig::format`]. Changes are detected by comparing the content
+//! with the last collected one.
+//!
+//! 与nacos一样，每个应用有两个文档： `<fw.app.id>-default` 及 `<fw.app.id>-<profile>` ，
+//! 内容按 [`ConfCenterConfig::format`] 解析. 通过与上次获取的内容比对来检测变更.
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use config::builder::AsyncState;
+use config::{ConfigBuilder, ConfigError, FileFormat, Format};
+use tracing::{debug, trace, warn};
+
+use super::config_dto::ConfCenterConfig;
+use super::config_processor::{ConfCenterContext, ConfCenterProcess};
+use crate::basic::result::TardisResult;
+use crate::config::config_utils::config_foreign_err;
+
+/// Loader of the documents / 文档加载器
+#[async_trait::async_trait]
+pub(crate) trait ConfDocumentLoader: Send + Sync + Debug + 'static {
+    /// Load the content of a document, `None` if it doesn't exist
+    async fn load(&self, data_id: &str) -> TardisResult<Option<String>>;
+}
+
+/// A source corresponding to a document
+#[derive(Debug)]
+pub(crate) struct ConfDocumentSource<L: ConfDocumentLoader> {
+    pub(crate) data_id: String,
+    loader: Arc<L>,
+    format: FileFormat,
+    /// content of the last collection, `None` if not collected yet
+    content: Arc<Mutex<Option<Option<String>>>>,
+}
+
+impl<L: ConfDocumentLoader> Clone for ConfDocumentSource<L> {
+    fn clone(&self) -> Self {
+        Self {
+            data_id: self.data_id.clone(),
+            loader: self.loader.clone(),
+            format: self.format,
+            content: self.content.clone(),
+        }
+    }
+}
+
+impl<L: ConfDocumentLoader> ConfDocumentSource<L> {
+    /// Whether the document differs from the last collected one, always `false` before the first collection
+    pub(crate) async fn changed(&self) -> TardisResult<bool> {
+        let current = self.loader.load(&self.data_id).await?;
+        let content = self.content.lock().expect("[Tardis.Config] Document content lock poisoned");
+        Ok(content.as_ref().map(|content| content != &current).unwrap_or(false))
+    }
+}
+
+#[async_trait::async_trait]
+impl<L: ConfDocumentLoader> config::AsyncSource for ConfDocumentSource<L> {
+    async fn collect(&self) -> Result<config::Map<String, config::Value>, ConfigError> {
+        let content = self.loader.load(&self.data_id).await.map_err(config_foreign_err)?;
+        *self.content.lock().expect("[Tardis.Config] Document content lock poisoned") = Some(content.clone());
+        match content {
+            Some(text) => {
+                trace!("[Tardis.Config] Config document {} content: {}", self.data_id, text);
+                self.format.parse(Some(&self.data_id), &text).map_err(ConfigError::Foreign)
+            }
+            None => {
+                warn!("[Tardis.Config] Config document not found: {}, loader: {:?}", self.data_id, self.loader);
+                Ok(config::Map::new())
+            }
+        }
+    }
+}
+
+/// Processor of the document based config centers, changes are detected by polling
+#[derive(Debug)]
+pub(crate) struct ConfDocumentProcessor<L: ConfDocumentLoader> {
+    pub(crate) sources: Vec<ConfDocumentSource<L>>,
+    polling_interval: Duration,
+}
+
+impl<L: ConfDocumentLoader> ConfDocumentProcessor<L> {
+    pub(crate) fn new(ctx: &ConfCenterContext, loader: L) -> Self {
+        let loader = Arc::new(loader);
+        let mut data_ids = vec![format!("{}-default", ctx.app_id)];
+        if !ctx.profile.is_empty() {
+            data_ids.push(format!("{}-{}", ctx.app_id, ctx.profile));
+        }
+        let sources = data_ids
+            .into_iter()
+            .map(|data_id| ConfDocumentSource {
+                data_id,
+                loader: loader.clone(),
+                format: ctx.format,
+                content: Arc::new(Mutex::new(None)),
+            })
+            .collect();
+        Self {
+            sources,
+            polling_interval: polling_interval(&ctx.config),
+        }
+    }
+
+    /// Check all documents, notify and return `true` once any of them changed
+    pub(crate) async fn check_update(sources: &[ConfDocumentSource<L>], reload_notifier: &tokio::sync::mpsc::Sender<()>) -> bool {
+        for source in sources {
+            match source.changed().await {
+                Ok(true) => {
+                    debug!("[Tardis.Config] Config document {} changed", source.data_id);
+                    notify_update(reload_notifier).await;
+                    return true;
+                }
+                Ok(false) => {}
+                // if request failed, wait for next poll
+                Err(e) => warn!("[Tardis.Config] Check config document {} error: {}", source.data_id, e),
+            }
+        }
+        false
+    }
+
+    pub(crate) fn poll_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        let sources = self.sources.clone();
+        let polling_interval = self.polling_interval;
+        let reload_notifier = reload_notifier.clone();
+        tokio::spawn(async move {
+            debug!(
+                "[Tardis.Config] Config document listener start for {:?}",
+                sources.iter().map(|s| &s.data_id).collect::<Vec<_>>()
+            );
+            loop {
+                tokio::time::sleep(polling_interval).await;
+                if reload_notifier.is_closed() || Self::check_update(&sources, &reload_notifier).await {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl<L: ConfDocumentLoader> ConfCenterProcess for ConfDocumentProcessor<L> {
+    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        self.poll_update(reload_notifier)
+    }
+
+    fn register_to_config(&self, mut conf: ConfigBuilder<AsyncState>) -> ConfigBuilder<AsyncState> {
+        for source in &self.sources {
+            conf = conf.add_async_source(source.clone());
+        }
+        conf
+    }
+}
+
+/// Config change polling interval, default is 5s
+pub(crate) fn polling_interval(config: &ConfCenterConfig) -> Duration {
+    Duration::from_millis(config.config_change_polling_interval.unwrap_or(5000))
+}
+
+/// Send the reload signal, the listeners stop after that since tardis will be reloaded
+pub(crate) async fn notify_update(reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+    match reload_notifier.send(()).await {
+        Ok(_) => debug!("[Tardis.Config] Remote config updated, send update notifier"),
+        // if receiver dropped, stop watching, since tardis wont be reboot anyway
+        Err(e) => warn!("[Tardis.Config] Remote config updated, but no receiver found, stop watching, error: {e}"),
+    }
+}
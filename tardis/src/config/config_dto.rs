@@ -6,11 +6,18 @@ use std::collections::HashMap;
 
 use typed_builder::TypedBuilder;
 pub(crate) mod component;
+pub mod lifecycle;
 pub mod log;
+#[cfg(feature = "conf-watch")]
+pub mod watch;
 pub use component::*;
+pub use lifecycle::*;
 pub use log::*;
+#[cfg(feature = "conf-watch")]
+pub use watch::*;
 /// Configuration of Tardis / Tardis的配置
 #[derive(Serialize, Deserialize, Clone, TypedBuilder, Debug)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct TardisConfig {
     #[builder(default, setter(into))]
     /// Project custom configuration / 项目自定义的配置
@@ -23,6 +30,7 @@ pub struct TardisConfig {
 /// Configuration of each function of the Tardis framework / Tardis框架的各功能配置
 /// The `web_client` module and the `log` module is enabled by default / `web_client` 和 `log` 模块应当默认启用
 #[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 // TODO Replace with options / enums
 #[builder(field_defaults(default, setter(strip_option, into)))]
 #[serde(default)]
@@ -33,6 +41,9 @@ pub struct FrameworkConfig {
     #[builder(setter(!strip_option))]
     /// Advanced configuration / 高级配置
     pub adv: AdvConfig,
+    #[builder(setter(!strip_option))]
+    /// Lifecycle configuration / 生命周期配置
+    pub lifecycle: LifecycleConfig,
     /// Database configuration / 数据库配置
     pub db: Option<DBConfig>,
     /// Web service configuration / Web服务配置
@@ -50,9 +61,15 @@ pub struct FrameworkConfig {
     pub mail: Option<MailConfig>,
     /// Object Storage configuration / 对象存储配置
     pub os: Option<OSConfig>,
+    /// Cluster configuration / 集群配置
+    pub cluster: Option<ClusterConfig>,
     /// Config center configuration / 配置中心的配置
     #[cfg(feature = "conf-remote")]
     pub conf_center: Option<ConfCenterConfig>,
+    #[cfg(feature = "conf-watch")]
+    #[builder(setter(!strip_option))]
+    /// Local config files watch configuration / 本地配置文件监听配置
+    pub conf_watch: ConfWatchConfig,
     #[builder(!default, default = Some(LogConfig::default()))]
     /// log configuration / 日志配置
     pub log: Option<LogConfig>,
@@ -113,6 +130,12 @@ impl FrameworkConfig {
     pub fn os(&self) -> &OSConfig {
         self.os.as_ref().expect("missing component config of os")
     }
+    /// Get cluster config
+    /// # Panic
+    /// If the config of cluster is none, this will be panic.
+    pub fn cluster(&self) -> &ClusterConfig {
+        self.cluster.as_ref().expect("missing component config of cluster")
+    }
     /// Get log config
     /// # Panic
     /// If the config of log is none, this will be panic.
@@ -138,6 +161,7 @@ impl FrameworkConfig {
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct AppConfig {
     #[builder(default)]
@@ -187,6 +211,7 @@ impl Default for AppConfig {
 
 /// ConfCenterConfig / 配置中心的配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct ConfCenterConfig {
     /// kind of config center / 配置中心的类型
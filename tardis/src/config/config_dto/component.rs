@@ -19,6 +19,8 @@ pub(crate) mod mail;
 pub use mail::*;
 pub(crate) mod os;
 pub use os::*;
+pub(crate) mod cluster;
+pub use cluster::*;
 
 use crate::redact::Redact;
 
@@ -38,6 +40,7 @@ use crate::redact::Redact;
 /// SomeConfig::from(submodule_config);
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct TardisComponentConfig<T, C: Default = ()> {
     #[serde(flatten)]
     #[builder(default, setter(into))]
@@ -48,6 +51,7 @@ pub struct TardisComponentConfig<T, C: Default = ()> {
     pub default: T,
     #[builder(default, setter(into))]
     #[serde(default = "Default::default")]
+    #[cfg_attr(feature = "conf-schema", schemars(default))]
     /// submodule configs
     pub modules: HashMap<String, T>,
 }
@@ -101,6 +105,7 @@ pub type OSConfig = TardisComponentConfig<os::OSModuleConfig>;
 
 /// Advanced configuration / 高级配置
 #[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct AdvConfig {
     /// Whether to capture the error stack / 是否捕捉错误堆栈
@@ -119,8 +124,23 @@ pub struct AdvConfig {
     /// . Open <https://www.javainuse.com/aesgenerator> and output the following:
     /// `Enter Plain Text to Encrypt ` = `Value to be encrypted` , `Select Mode` = `ECB` , `Key Size in Bits` = `128` , `Enter Secret Key` = `Value of this field` , `Output Text Format` = `Hex`
     /// . Click `Encrypt` to wrap the generated value in `ENC(xx)` to replace the original value
+    ///
+    /// Deprecated, the salt has to be stored along with the encrypted values, use the secret placeholders instead,
+    /// see [`config_secret`](crate::config::config_secret).
+    ///
+    /// 已废弃，盐值需要与密文存放在一起，请使用密文占位符，见 [`config_secret`](crate::config::config_secret).
     #[builder(default)]
     pub salt: String,
+
+    /// Whether to reject the invalid configs, default is `false` / 是否拒绝无效的配置，默认为 `false`
+    ///
+    /// If enabled, [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) fails and the hot reloads are rejected when [`TardisConfig::validate`](crate::config::config_dto::TardisConfig::validate)
+    /// reports any problem, otherwise the problems are logged as warnings.
+    ///
+    /// 启用后，当 [`TardisConfig::validate`](crate::config::config_dto::TardisConfig::validate) 发现问题时 [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) 失败且拒绝热重载，
+    /// 否则这些问题作为警告记录在日志中.
+    #[builder(default = false)]
+    pub strict_validation: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, TypedBuilder)]
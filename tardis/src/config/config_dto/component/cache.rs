@@ -18,6 +18,7 @@ use crate::redact::Redact;
 ///};
 /// ```
 #[derive(Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct CacheModuleConfig {
     /// Cache access Url, Url with permission information / 缓存访问Url，Url带权限信息
     pub url: Url,
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// Cluster configuration / 集群配置
+///
+/// Cluster operations need to be enabled ```#[cfg(feature = "cluster")]``` .
+///
+/// 集群操作需要启用 ```#[cfg(feature = "cluster")]``` .
+///
+/// Nodes of the same application (same `fw.app.id`) find each other through the [watch_kind](Self::watch_kind),
+/// each node is identified by its `fw.app.inst`.
+///
+/// 相同应用（相同的 `fw.app.id`）的节点通过 [watch_kind](Self::watch_kind) 相互发现，每个节点以 `fw.app.inst` 作为标识.
+///
+/// # Examples
+/// ```toml
+/// [fw.cluster]
+/// watch_kind = "static"
+/// static_nodes = ["10.0.0.1:8080", "10.0.0.2:8080"]
+/// heartbeat_interval_sec = 5
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ClusterConfig {
+    #[builder(default)]
+    /// How to discover other nodes, default is `cache` / 发现其它节点的方式，默认为 `cache`
+    pub watch_kind: ClusterWatchKind,
+    #[builder(default, setter(into))]
+    /// Node addresses used when `watch_kind` is `static` / `watch_kind` 为 `static` 时使用的节点地址
+    ///
+    /// Formatted as `host:port` or a full `ws(s)://host:port` url / 格式为 `host:port` 或完整的 `ws(s)://host:port` 地址
+    pub static_nodes: Vec<String>,
+    #[builder(default, setter(strip_option, into))]
+    /// Address other nodes use to reach this node, derived from the web server config by default
+    ///
+    /// 其它节点访问本节点的地址，默认由Web服务配置推导
+    pub access_addr: Option<String>,
+    #[builder(default = 5)]
+    /// Heartbeat interval, default is `5` seconds / 心跳间隔，默认 `5` 秒
+    pub heartbeat_interval_sec: u64,
+    #[builder(default = 15)]
+    /// A node without heartbeat response for this long is considered left, default is `15` seconds
+    ///
+    /// 超过该时长没有心跳响应的节点被视为已离开，默认 `15` 秒
+    pub heartbeat_timeout_sec: u64,
+    #[builder(default = 10000)]
+    /// Timeout of [request_event](crate::cluster::cluster_processor::TardisCluster::request_event), default is `10000` ms
+    ///
+    /// [request_event](crate::cluster::cluster_processor::TardisCluster::request_event) 的超时时间，默认 `10000` 毫秒
+    pub request_timeout_ms: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        ClusterConfig::builder().build()
+    }
+}
+
+/// Node discovery kind / 节点发现方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ClusterWatchKind {
+    /// Fixed node list from [static_nodes](ClusterConfig::static_nodes) / 使用 [static_nodes](ClusterConfig::static_nodes) 的固定节点列表
+    Static,
+    /// Registry kept in the default cache module / 使用默认缓存模块维护的节点注册表
+    #[default]
+    Cache,
+}
@@ -19,6 +19,7 @@ use crate::redact::Redact;
 /// };
 /// ```
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct DBModuleConfig {
     #[builder(setter(into))]
@@ -39,6 +40,29 @@ pub struct DBModuleConfig {
     /// Compatible database type / 兼容数据库类型
     #[builder(default)]
     pub compatible_type: CompatibleType,
+    /// Read replica Urls / 只读副本Url
+    ///
+    /// The multi-row queries outside a transaction ( `find_dtos` , `paginate_dtos` , `paginate_dtos_by_keyset` , `stream_dtos` , `count` , `query_all`
+    /// and their `_by_sql` variants) go to the replicas, the others, including the single-row queries `get_dto` , `get_dto_by_sql` and `query_one` ,
+    /// go to the primary database, i.e. `url` .
+    /// The replicas share the connection pool settings with the primary.
+    ///
+    /// 事务外的多行查询（ `find_dtos` 、 `paginate_dtos` 、 `paginate_dtos_by_keyset` 、 `stream_dtos` 、 `count` 、 `query_all` 及其 `_by_sql` 版本）使用只读副本，
+    /// 其它操作，包括单行查询 `get_dto` 、 `get_dto_by_sql` 及 `query_one` ，使用主库，即 `url` . 只读副本与主库使用相同的连接池配置.
+    #[builder(default, setter(into))]
+    pub replica_urls: Vec<String>,
+    /// Load balancing policy of the replicas / 只读副本的负载均衡策略
+    #[builder(default)]
+    pub replica_policy: DBReplicaPolicy,
+    /// Schema migration configuration / 数据库结构迁移配置
+    #[builder(default)]
+    pub migration: DBMigrationConfig,
+    /// Configuration of the soft deleted records / 软删除记录配置
+    #[builder(default)]
+    pub del_record: DBDelRecordConfig,
+    /// Slow query configuration / 慢查询配置
+    #[builder(default)]
+    pub slow_query: DBSlowQueryConfig,
 }
 
 impl Default for DBModuleConfig {
@@ -62,12 +86,116 @@ impl std::fmt::Debug for DBModuleConfig {
             .field("connect_timeout_sec", &self.connect_timeout_sec)
             .field("idle_timeout_sec", &self.idle_timeout_sec)
             .field("compatible_type", &self.compatible_type)
+            .field(
+                "replica_urls",
+                &self.replica_urls.iter().map(|url| url::Url::parse(url).map(|url| url.redact().to_string()).unwrap_or_else(|_| url.to_string())).collect::<Vec<_>>(),
+            )
+            .field("replica_policy", &self.replica_policy)
+            .field("migration", &self.migration)
+            .field("del_record", &self.del_record)
+            .field("slow_query", &self.slow_query)
             .finish()
     }
 }
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub enum CompatibleType {
     #[default]
     None,
     Oracle,
 }
+
+/// Load balancing policy of the read replicas / 只读副本的负载均衡策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DBReplicaPolicy {
+    /// Pick the replicas in turn / 轮流选择
+    #[default]
+    RoundRobin,
+    /// Pick a replica randomly / 随机选择
+    Random,
+}
+
+/// Schema migration configuration / 数据库结构迁移配置
+///
+/// See [`TardisMigrator`](crate::db::reldb_migration::TardisMigrator) for the layout of the migration files.
+///
+/// 迁移文件的格式见 [`TardisMigrator`](crate::db::reldb_migration::TardisMigrator) .
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct DBMigrationConfig {
+    /// Run the pending migrations on startup, default false / 启动时执行待执行的迁移，默认 false
+    #[builder(default)]
+    pub enabled: bool,
+    /// Directory of the SQL migration files / SQL迁移文件的目录
+    #[builder(default, setter(strip_option, into))]
+    pub dir: Option<String>,
+    /// Only log the pending migrations without running them / 只输出待执行的迁移而不执行
+    #[builder(default)]
+    pub dry_run: bool,
+    /// Seconds to wait for the migration lock held by another instance, default 300 / 等待其它实例持有的迁移锁的秒数，默认 300
+    ///
+    /// The holder refreshes the lock while migrating, a lock not refreshed within it is considered to be left by a crashed instance and is taken over.
+    ///
+    /// 持有者在迁移期间会刷新锁，超过该时间未刷新的锁视为崩溃实例遗留的锁，会被接管.
+    #[builder(default = 300)]
+    pub lock_timeout_sec: u64,
+}
+
+impl Default for DBMigrationConfig {
+    fn default() -> Self {
+        DBMigrationConfig::builder().build()
+    }
+}
+
+/// Configuration of the soft deleted records / 软删除记录配置
+///
+/// The soft deleted records are kept in the `tardis_del_record` table, see
+/// [`TardisRelDBlConnection::soft_delete`](crate::db::reldb_client::TardisRelDBlConnection::soft_delete).
+///
+/// 软删除记录保存在 `tardis_del_record` 表中，见 [`TardisRelDBlConnection::soft_delete`](crate::db::reldb_client::TardisRelDBlConnection::soft_delete).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct DBDelRecordConfig {
+    /// Seconds to keep the soft deleted records, the older ones are purged periodically, default None, i.e. kept forever
+    ///
+    /// 软删除记录的保留秒数，更早的记录会被定期清除，默认 None，即永久保留
+    #[builder(default, setter(strip_option))]
+    pub retention_sec: Option<u64>,
+    /// Interval seconds of the purge, default 3600 / 清除的间隔秒数，默认 3600
+    #[builder(default = 3600)]
+    pub purge_interval_sec: u64,
+}
+
+impl Default for DBDelRecordConfig {
+    fn default() -> Self {
+        DBDelRecordConfig::builder().build()
+    }
+}
+
+/// Slow query configuration / 慢查询配置
+///
+/// The queries exceeding the threshold are logged with their SQL, the string literals of the SQL are redacted and the bound values are omitted,
+/// see `tardis::db::reldb_trace`.
+///
+/// 超过阈值的查询会输出其SQL，SQL中的字符串字面量会被脱敏且不输出绑定的参数值，见 `tardis::db::reldb_trace`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct DBSlowQueryConfig {
+    /// Milliseconds of a slow query, default None, i.e. not logged / 慢查询的毫秒数，默认 None，即不输出
+    #[builder(default, setter(strip_option))]
+    pub threshold_ms: Option<u64>,
+    /// Capture the query plan of the slow queries by `EXPLAIN` , default false / 通过 `EXPLAIN` 获取慢查询的查询计划，默认 false
+    #[builder(default)]
+    pub explain: bool,
+}
+
+impl Default for DBSlowQueryConfig {
+    fn default() -> Self {
+        DBSlowQueryConfig::builder().build()
+    }
+}
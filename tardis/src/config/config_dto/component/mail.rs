@@ -7,6 +7,7 @@ use crate::redact::Redact;
 /// Mail module configuration / 邮件模块配置
 ///
 #[derive(Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct MailModuleConfig {
     /// SMTP host
@@ -19,6 +19,7 @@ use crate::redact::Redact;
 ///};
 /// ```
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct MQModuleConfig {
     /// Message queue access Url, Url with permission information / 消息队列访问Url，Url带权限信息
     pub url: Url,
@@ -5,6 +5,7 @@ use typed_builder::TypedBuilder;
 use crate::redact::Redact;
 
 #[derive(Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct OSModuleConfig {
     /// s3/oss/obs, Support amazon s3 / aliyun oss / huaweicloud obs
@@ -7,6 +7,7 @@ use typed_builder::TypedBuilder;
 ///
 /// Web客户端操作需要启用 ```#[cfg(feature = "web-client")]``` .
 #[derive(Debug, Serialize, Deserialize, Clone, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct WebClientModuleConfig {
     #[builder(default = 60, setter(into))]
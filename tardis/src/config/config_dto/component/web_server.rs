@@ -33,6 +33,7 @@ use typed_builder::TypedBuilder;
 /// ```
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct WebServerCommonConfig {
     #[builder(default = IpAddr::V4(Ipv4Addr::UNSPECIFIED), setter(into))]
@@ -61,6 +62,25 @@ pub struct WebServerCommonConfig {
     pub context_conf: WebServerContextConfig,
     #[builder(default = false)]
     pub security_hide_err_msg: bool,
+    #[builder(default = false)]
+    /// Whether to expose the `/health/live` and `/health/ready` routes, default is `false`
+    ///
+    /// 是否开放 `/health/live` 及 `/health/ready` 路由，默认为 `false`
+    ///
+    /// The readiness route responds `503` when any component is down, see [TardisFuns::health](crate::TardisFuns::health).
+    ///
+    /// 任一组件不可用时就绪路由返回 `503` ，见 [TardisFuns::health](crate::TardisFuns::health).
+    pub health_check: bool,
+    #[builder(default, setter(strip_option, into))]
+    /// Path of the prometheus metrics route, e.g. `/metrics` , `None` to disable it, requires the `metrics` feature, default is `None`
+    ///
+    /// Prometheus指标路由的路径，如 `/metrics` ， `None` 表示关闭，需要开启 `metrics` 特性，默认为 `None`
+    ///
+    /// The route is not authenticated and reveals the module names and the outbound hosts,
+    /// enable it only on a web server not exposed to the public.
+    ///
+    /// 该路由没有认证且会暴露模块名及外部请求的主机，仅在不对公网开放的Web服务上开启.
+    pub metrics_path: Option<String>,
 }
 
 /// Tardis context configuration / Tardis上下文配置
@@ -79,6 +99,7 @@ pub struct WebServerCommonConfig {
 ///
 /// 首先会尝试从请求头信息中获取 [context_header_name](Self::context_header_name) ,如果没指定或是没有值时会尝试从缓存中获取.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct WebServerContextConfig {
     /// Tardis context identifier, used to specify the request header name, default is `Tardis-Context`
@@ -114,6 +135,7 @@ pub struct WebServerContextConfig {
 /// };
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct WebServerModuleConfig {
     #[builder(default = "Tardis-based application".to_string(), setter(into))]
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// # Lifecycle configure / 生命周期配置
+///
+/// - grace_timeout_sec: how long the shutdown waits for hooks, in-flight web requests and mq consumers, default to `5`
+/// - handle_signal: trigger the shutdown on `SIGTERM` / `SIGINT`, default to `false`
+/// ## Example
+/// ```toml
+/// [fw.lifecycle]
+/// grace_timeout_sec = 30
+/// handle_signal = true
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct LifecycleConfig {
+    #[builder(default = 5)]
+    /// Grace timeout of the shutdown, in seconds / 关闭的宽限时间，单位秒
+    ///
+    /// Shutdown hooks, in-flight web requests and mq consumers are waited for at most this long before the connections are closed.
+    ///
+    /// 在关闭连接前，最多等待该时长以完成关闭钩子、处理中的Web请求及MQ消费.
+    pub grace_timeout_sec: u64,
+    #[builder(default = false)]
+    /// Whether to shutdown on `SIGTERM` / `SIGINT` / 是否在收到 `SIGTERM` / `SIGINT` 时关闭
+    pub handle_signal: bool,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        LifecycleConfig::builder().build()
+    }
+}
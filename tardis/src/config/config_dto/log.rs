@@ -24,14 +24,17 @@ pub use tracing_appender::*;
 /// directives = ["tardis=debug", "sqlx=info"]
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct LogConfig {
     #[builder(default, setter(into))]
     #[serde(deserialize_with = "deserialize_directive", serialize_with = "serialize_directive")]
+    #[cfg_attr(feature = "conf-schema", schemars(with = "Option<String>"))]
     /// the default log level
     pub level: Option<Directive>,
     #[builder(default, setter(into))]
     #[serde(deserialize_with = "deserialize_directives", serialize_with = "serialize_directives")]
+    #[cfg_attr(feature = "conf-schema", schemars(with = "Vec<String>"))]
     /// tracing filtering directive, e.g. `tardis=debug,sqlx=off`
     pub directives: Vec<Directive>,
     #[cfg(feature = "tracing")]
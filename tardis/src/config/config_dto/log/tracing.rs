@@ -48,12 +48,14 @@ impl<'de> Deserialize<'de> for OtlpProtocol {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct TracingConfig {
     #[cfg(feature = "tracing")]
     #[builder(default = "http://localhost:4317".to_string(), setter(into))]
     pub endpoint: String,
     #[cfg(feature = "tracing")]
     #[builder(default)]
+    #[cfg_attr(feature = "conf-schema", schemars(with = "String"))]
     pub protocol: OtlpProtocol,
     #[cfg(feature = "tracing")]
     #[builder(default = "tardis-tracing".to_string(), setter(into))]
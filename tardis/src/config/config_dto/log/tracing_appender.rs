@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use tracing_appender::rolling::Rotation;
 use typed_builder::TypedBuilder;
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TypedBuilder, Default)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 pub struct TracingAppenderConfig {
     #[builder(default, setter(into))]
     pub rotation: TracingAppenderRotation,
@@ -12,6 +13,7 @@ pub struct TracingAppenderConfig {
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum TracingAppenderRotation {
     #[default]
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+/// # Local config files watch configure / 本地配置文件监听配置
+///
+/// Watch `conf-default.*` , `conf-<profile>.*` and the `locale` directory under the relative path passed to
+/// [`TardisConfig::init`](crate::config::config_dto::TardisConfig::init), hot reload tardis when they change.
+///
+/// 监听传入 [`TardisConfig::init`](crate::config::config_dto::TardisConfig::init) 的相对路径下的 `conf-default.*` 、
+/// `conf-<profile>.*` 及 `locale` 目录，变更时热重载tardis.
+///
+/// - enabled: whether to watch the local config files, default to `false`
+/// - debounce_ms: reload after no change for this long, in milliseconds, default to `500`
+/// - watch_locale: whether to watch the `locale` directory, default to `true`
+/// ## Example
+/// ```toml
+/// [fw.conf_watch]
+/// enabled = true
+/// debounce_ms = 1000
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TypedBuilder)]
+#[cfg_attr(feature = "conf-schema", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct ConfWatchConfig {
+    #[builder(default = false)]
+    /// Whether to watch the local config files / 是否监听本地配置文件
+    pub enabled: bool,
+    #[builder(default = 500)]
+    /// Debounce time, in milliseconds / 防抖时间，单位毫秒
+    ///
+    /// Files are often written several times in a row, the reload happens after no change for this long.
+    ///
+    /// 文件常被连续多次写入，在该时长内无变更后才重载.
+    pub debounce_ms: u64,
+    #[builder(default = true)]
+    /// Whether to watch the `locale` directory / 是否监听 `locale` 目录
+    pub watch_locale: bool,
+}
+
+impl Default for ConfWatchConfig {
+    fn default() -> Self {
+        ConfWatchConfig::builder().build()
+    }
+}
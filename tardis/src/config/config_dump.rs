@@ -0,0 +1,129 @@
+//! Effective config dump / 生效配置导出
+//!
+//! [`TardisConfig::dump`] lists the values merged by the last [`TardisConfig::init`] and where each value came from,
+//! i.e. the config file, the config center or the environment variable. Secrets are redacted.
+//!
+//! [`TardisConfig::dump`] 列出最后一次 [`TardisConfig::init`] 合并后的值及其来源，即配置文件、配置中心或环境变量. 密文会被隐藏.
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use config::{Config, ConfigError, Source, Value, ValueKind};
+
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::TardisConfig;
+use crate::redact::Redact;
+
+const SENSITIVE_KEYS: &[&str] = &["password", "passwd", "pwd", "secret", "token", "sk", "salt", "key"];
+
+/// A value of the effective config / 生效配置的值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TardisConfigEntry {
+    /// Path of the value, e.g. `fw.db.modules.m1.url` / 值的路径，如 `fw.db.modules.m1.url`
+    pub path: String,
+    /// The value / 值
+    pub value: String,
+    /// Where the value came from / 值的来源
+    pub origin: Option<String>,
+}
+
+impl Redact for TardisConfigEntry {
+    /// Redact the secrets, the values with sensitive names and the passwords of the urls
+    fn redact(&self) -> Self {
+        let name = self.path.rsplit('.').next().and_then(|name| name.split('[').next()).unwrap_or_default().to_lowercase();
+        let sensitive = SENSITIVE_KEYS.iter().any(|key| name == *key || name.starts_with(&format!("{key}_")) || name.ends_with(&format!("_{key}")));
+        let value = if sensitive || self.value.contains("${secret:") {
+            self.value.redact()
+        } else {
+            match url::Url::parse(&self.value) {
+                Ok(url) if url.password().is_some() => url.redact().to_string(),
+                _ => self.value.clone(),
+            }
+        };
+        TardisConfigEntry {
+            path: self.path.clone(),
+            value,
+            origin: self.origin.clone(),
+        }
+    }
+}
+
+impl Display for TardisConfigEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.path, self.value)?;
+        if let Some(origin) = &self.origin {
+            write!(f, " # {origin}")?;
+        }
+        Ok(())
+    }
+}
+
+crate::tardis_static! {
+    effective_config: RwLock<Vec<TardisConfigEntry>>;
+}
+
+impl TardisConfig {
+    /// Dump the effective config with the secrets redacted / 导出生效的配置，密文会被隐藏
+    ///
+    /// # Examples
+    /// ```ignore
+    /// for entry in TardisConfig::dump() {
+    ///     // fw.db.url = postgres://postgres:**@localhost/test # env:TARDIS_FW__DB__URL
+    ///     println!("{entry}");
+    /// }
+    /// ```
+    pub fn dump() -> Vec<TardisConfigEntry> {
+        effective_config().read().expect("[Tardis.Config] Effective config lock poisoned").clone()
+    }
+}
+
+/// Record the merged config before the secrets are resolved
+pub(crate) fn record(config: &Config) -> TardisResult<()> {
+    let mut entries = Vec::new();
+    let mut table = config.collect()?.into_iter().collect::<Vec<_>>();
+    table.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (key, value) in table {
+        collect_entries(key, value, &mut entries);
+    }
+    *effective_config().write().expect("[Tardis.Config] Effective config lock poisoned") = entries;
+    Ok(())
+}
+
+fn collect_entries(path: String, value: Value, entries: &mut Vec<TardisConfigEntry>) {
+    match value.kind {
+        ValueKind::Table(table) if !table.is_empty() => {
+            let mut table = table.into_iter().collect::<Vec<_>>();
+            table.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, value) in table {
+                collect_entries(format!("{path}.{key}"), value, entries);
+            }
+            return;
+        }
+        ValueKind::Array(array) if !array.is_empty() => {
+            for (index, value) in array.into_iter().enumerate() {
+                collect_entries(format!("{path}[{index}]"), value, entries);
+            }
+            return;
+        }
+        _ => {}
+    }
+    let origin = origin(&value);
+    let value = match value.kind {
+        ValueKind::Table(_) => "{}".to_string(),
+        ValueKind::Array(_) => "[]".to_string(),
+        ValueKind::String(value) => value,
+        kind => kind.to_string(),
+    };
+    entries.push(TardisConfigEntry { path, value, origin }.redact());
+}
+
+/// `Value::origin` is private, recover it from the type error
+fn origin(value: &Value) -> Option<String> {
+    let error = match value.kind {
+        ValueKind::Table(_) => value.clone().into_array().err(),
+        _ => value.clone().into_table().err(),
+    };
+    match error {
+        Some(ConfigError::Type { origin, .. }) => origin,
+        _ => None,
+    }
+}
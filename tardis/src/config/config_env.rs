@@ -0,0 +1,166 @@
+//! Environment variable overlay / 环境变量覆盖
+//!
+//! Environment variables starting with `TARDIS_` override the values of the config files, the rest of the name is the
+//! path of the value, whose segments are separated by `__` (or `.` ):
+//!
+//! 以 `TARDIS_` 开头的环境变量会覆盖配置文件中的值，其余部分为值的路径，路径各段以 `__` （或 `.` ）分隔：
+//!
+//! ```shell
+//! TARDIS_FW__DB__URL=postgres://localhost/test
+//! TARDIS_FW__DB__MODULES__M1__URL=mysql://localhost/test
+//! # numeric segments are array indexes / 数字段表示数组下标
+//! TARDIS_FW__CLUSTER__STATIC_NODES__0=10.0.0.1:8080
+//! # the scalar values are strings, which are converted to the types of the `fw` fields / 标量值为字符串，会被转换为 `fw` 字段的类型
+//! TARDIS_FW__DB__MAX_CONNECTIONS=30
+//! TARDIS_CS__TOKEN=123456
+//! # the values starting with `[` , `{` or `"` are parsed as JSON / 以 `[` 、 `{` 或 `"` 开头的值解析为JSON
+//! TARDIS_FW__WEB_SERVER__MODULES__M1__DOC_URLS='[["test env", "http://localhost:8080/"]]'
+//! ```
+//!
+//! The custom sections are deserialized strictly, so their numeric and boolean fields can't be set by the scalar values.
+//!
+//! 自定义配置按严格模式反序列化，因此其数字及布尔字段无法通过标量值设置.
+//!
+//! The prefix, the separators and the keys of the lists separated by `,` can be changed by [`TardisConfig::set_env_overlay`].
+//!
+//! 前缀、分隔符及以 `,` 分隔的列表的键可通过 [`TardisConfig::set_env_overlay`] 修改.
+use std::sync::RwLock;
+
+use config::{ConfigError, Map, Source, Value, ValueKind};
+use typed_builder::TypedBuilder;
+
+use crate::config::config_dto::TardisConfig;
+use crate::config::config_secret::{SECRET_KEY_ENV, SECRET_KEY_FILE_ENV};
+
+/// Environment variable overlay configuration / 环境变量覆盖配置
+///
+/// - prefix: prefix of the variables, default to `TARDIS`
+/// - separator: separator of the path segments, default to `__` , `.` is always supported
+/// - list_separator: separator of the list values, default to `,`
+/// - list_keys: paths of the values that are lists separated by `list_separator` , e.g. `fw.cluster.static_nodes`
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TardisEnvOverlay {
+    #[builder(default = "TARDIS".to_string(), setter(into))]
+    /// Prefix of the variables, the `_` after it is omitted / 变量的前缀，省略其后的 `_`
+    pub prefix: String,
+    #[builder(default = "__".to_string(), setter(into))]
+    /// Separator of the path segments / 路径各段的分隔符
+    pub separator: String,
+    #[builder(default = ",".to_string(), setter(into))]
+    /// Separator of the list values / 列表值的分隔符
+    pub list_separator: String,
+    #[builder(default, setter(into))]
+    /// Paths of the list values / 列表值的路径
+    pub list_keys: Vec<String>,
+}
+
+impl Default for TardisEnvOverlay {
+    fn default() -> Self {
+        TardisEnvOverlay::builder().build()
+    }
+}
+
+crate::tardis_static! {
+    env_overlay: RwLock<TardisEnvOverlay>;
+}
+
+impl TardisConfig {
+    /// Set the environment variable overlay / 设置环境变量覆盖
+    ///
+    /// Takes effect on the next [`TardisConfig::init`].
+    ///
+    /// 在下一次 [`TardisConfig::init`] 时生效.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// TardisConfig::set_env_overlay(TardisEnvOverlay::builder().prefix("MY_APP").list_keys(vec!["fw.cluster.static_nodes".to_string()]).build());
+    /// ```
+    pub fn set_env_overlay(overlay: TardisEnvOverlay) {
+        *env_overlay().write().expect("[Tardis.Config] Env overlay lock poisoned") = overlay;
+    }
+}
+
+/// Config source of the environment variables
+#[derive(Debug, Clone)]
+pub(crate) struct EnvOverlaySource {
+    overlay: TardisEnvOverlay,
+    vars: Vec<(String, String)>,
+}
+
+impl EnvOverlaySource {
+    pub(crate) fn new() -> Self {
+        EnvOverlaySource {
+            overlay: env_overlay().read().expect("[Tardis.Config] Env overlay lock poisoned").clone(),
+            vars: std::env::vars().collect(),
+        }
+    }
+
+    /// Convert the variable name to the config path, `None` if it isn't an overlay
+    fn path(&self, name: &str) -> Option<String> {
+        if name.eq_ignore_ascii_case(SECRET_KEY_ENV) || name.eq_ignore_ascii_case(SECRET_KEY_FILE_ENV) {
+            return None;
+        }
+        let name = name.to_lowercase();
+        let path = name.strip_prefix(&format!("{}_", self.overlay.prefix.to_lowercase()))?;
+        let mut result = String::with_capacity(path.len());
+        for segment in path.split(self.overlay.separator.as_str()).flat_map(|segment| segment.split('.')) {
+            if segment.is_empty() {
+                return None;
+            }
+            if !result.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                result.push_str(&format!("[{segment}]"));
+            } else {
+                if !result.is_empty() {
+                    result.push('.');
+                }
+                result.push_str(segment);
+            }
+        }
+        Some(result)
+    }
+
+    fn value(&self, path: &str, name: &str, value: &str) -> Value {
+        let origin = format!("env:{name}");
+        // the scalars are kept as strings, e.g. a token `123456` or `true`
+        if value.trim_start().starts_with(['[', '{', '"']) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(value) {
+                return json_to_value(&origin, json);
+            }
+        }
+        if self.overlay.list_keys.iter().any(|key| key.eq_ignore_ascii_case(path)) {
+            let list = value.split(self.overlay.list_separator.as_str()).map(|item| Value::new(Some(&origin), item.trim())).collect::<Vec<_>>();
+            return Value::new(Some(&origin), list);
+        }
+        Value::new(Some(&origin), value)
+    }
+}
+
+impl Source for EnvOverlaySource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.vars.iter().filter_map(|(name, value)| self.path(name).map(|path| (path.clone(), self.value(&path, name, value)))).collect())
+    }
+}
+
+fn json_to_value(origin: &String, json: serde_json::Value) -> Value {
+    let kind = match json {
+        serde_json::Value::Null => ValueKind::Nil,
+        serde_json::Value::Bool(value) => ValueKind::Boolean(value),
+        serde_json::Value::Number(value) => {
+            if let Some(value) = value.as_i64() {
+                ValueKind::I64(value)
+            } else if let Some(value) = value.as_u64() {
+                ValueKind::U64(value)
+            } else {
+                ValueKind::Float(value.as_f64().unwrap_or_default())
+            }
+        }
+        serde_json::Value::String(value) => ValueKind::String(value),
+        serde_json::Value::Array(values) => ValueKind::Array(values.into_iter().map(|value| json_to_value(origin, value)).collect()),
+        serde_json::Value::Object(values) => ValueKind::Table(values.into_iter().map(|(key, value)| (key, json_to_value(origin, value))).collect()),
+    };
+    Value::new(Some(origin), kind)
+}
@@ -0,0 +1,82 @@
+//! Config center of plain http endpoints / 基于普通HTTP接口的配置中心
+//!
+//! The documents are fetched by `GET <url>/<data_id>` , with `group` and `namespace` as query parameters if set,
+//! basic authentication is used when the username is not empty. `404` means the document doesn't exist.
+//! Changes are detected by polling every `config_change_polling_interval` milliseconds.
+//!
+//! 通过 `GET <url>/<data_id>` 获取文档，若设置了 `group` 及 `namespace` 则作为查询参数，用户名不为空时使用Basic认证.
+//! `404` 表示文档不存在. 每隔 `config_change_polling_interval` 毫秒轮询检测变更.
+//!
+//! The connection times out after 5 seconds and the request after 10 seconds, so an unresponsive config server doesn't block the startup.
+//!
+//! 连接超时为5秒，请求超时为10秒，避免无响应的配置服务阻塞启动.
+//!
+//! ```toml
+//! [fw.conf_center]
+//! kind = "http"
+//! url = "http://config-server/configs"
+//! format = "json"
+//! ```
+use std::time::Duration;
+
+use tracing::trace;
+
+use super::config_document::{ConfDocumentLoader, ConfDocumentProcessor};
+use super::config_processor::ConfCenterContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub(crate) struct ConfHttpLoader {
+    client: reqwest::Client,
+    url: String,
+    username: String,
+    password: String,
+    query: Vec<(&'static str, String)>,
+}
+
+#[async_trait::async_trait]
+impl ConfDocumentLoader for ConfHttpLoader {
+    async fn load(&self, data_id: &str) -> TardisResult<Option<String>> {
+        let url = format!("{}/{}", self.url, data_id);
+        let mut request = self.client.get(&url).query(&self.query);
+        if !self.username.is_empty() {
+            request = request.basic_auth(&self.username, Some(&self.password));
+        }
+        let resp = request.send().await.map_err(|e| TardisError::internal_error(&format!("[Tardis.Config] Fetch {url} error: {e}"), "-1-tardis-config-error"))?;
+        trace!("[Tardis.Config] Http config center response: {:?}", resp);
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let resp = resp.error_for_status().map_err(|e| TardisError::internal_error(&format!("[Tardis.Config] Fetch {url} error: {e}"), "-1-tardis-config-error"))?;
+        let text = resp.text().await.map_err(|e| TardisError::internal_error(&format!("[Tardis.Config] Fetch {url} error: {e}"), "-1-tardis-config-error"))?;
+        Ok(Some(text))
+    }
+}
+
+/// create a new http config processor
+pub(crate) fn init(ctx: &ConfCenterContext) -> TardisResult<ConfDocumentProcessor<ConfHttpLoader>> {
+    let mut query = Vec::new();
+    if let Some(group) = &ctx.config.group {
+        query.push(("group", group.clone()));
+    }
+    if let Some(namespace) = &ctx.config.namespace {
+        query.push(("namespace", namespace.clone()));
+    }
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| TardisError::internal_error(&format!("[Tardis.Config] Build http client error: {e}"), "-1-tardis-config-error"))?;
+    let loader = ConfHttpLoader {
+        client,
+        url: ctx.config.url.trim_end_matches('/').to_string(),
+        username: ctx.config.username.clone(),
+        password: ctx.config.password.clone(),
+        query,
+    };
+    Ok(ConfDocumentProcessor::new(ctx, loader))
+}
@@ -0,0 +1,30 @@
+//! Config change notification / 配置变更通知
+//!
+//! Subscribers are created by [`TardisFuns::watch_cs_config`](crate::TardisFuns::watch_cs_config) and
+//! [`TardisFuns::watch_fw_config`](crate::TardisFuns::watch_fw_config), they are notified after
+//! [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) and [`TardisFuns::hot_reload`](crate::TardisFuns::hot_reload).
+//!
+//! 订阅者由 [`TardisFuns::watch_cs_config`](crate::TardisFuns::watch_cs_config) 及
+//! [`TardisFuns::watch_fw_config`](crate::TardisFuns::watch_fw_config) 创建，在
+//! [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) 及 [`TardisFuns::hot_reload`](crate::TardisFuns::hot_reload) 后得到通知.
+use std::sync::Mutex;
+
+/// Check the current config, return `false` if the subscriber is closed
+type ConfigSubscriber = Box<dyn Fn() -> bool + Send + Sync>;
+
+/// Subscribers of the config changes / 配置变更的订阅者
+#[derive(Default)]
+pub struct TardisConfigNotifier {
+    subscribers: Mutex<Vec<ConfigSubscriber>>,
+}
+
+impl TardisConfigNotifier {
+    pub(crate) fn subscribe(&self, subscriber: impl Fn() -> bool + Send + Sync + 'static) {
+        self.subscribers.lock().expect("[Tardis.Config] Config subscribers lock poisoned").push(Box::new(subscriber));
+    }
+
+    /// Notify the subscribers with the current config, closed subscribers are removed
+    pub(crate) fn notify(&self) {
+        self.subscribers.lock().expect("[Tardis.Config] Config subscribers lock poisoned").retain(|subscriber| subscriber());
+    }
+}
@@ -1,11 +1,14 @@
 use config::builder::AsyncState;
-use config::{ConfigBuilder, ConfigError, Environment, File};
+use config::{ConfigBuilder, ConfigError, File};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 #[cfg(feature = "conf-remote")]
-use {config::FileFormat, std::sync::Arc};
+use {
+    config::FileFormat,
+    std::{future::Future, pin::Pin, sync::Arc, sync::RwLock},
+};
 
 use crate::basic::error::TardisError;
 use crate::basic::fetch_profile;
@@ -30,7 +33,22 @@ use super::config_dto::{ConfCenterConfig, TardisConfig};
 /// 1. Remote file: <fw.app.id>-default
 ///      ``Requires [conf-remote] feature``
 /// 1. Remote file: <fw.app.id>-<profile>
-/// 1. Environment variables starting with TARDIS
+/// 1. Environment variables starting with TARDIS_, see [`config_env`](crate::config::config_env)
+///
+/// The merged values and their origins can be dumped by [`TardisConfig::dump`].
+///
+/// 合并后的值及其来源可通过 [`TardisConfig::dump`] 导出.
+///
+/// Secret placeholders like `${secret:env:NAME}` are resolved after fetching, see [`config_secret`](crate::config::config_secret).
+///
+/// 获取后解析形如 `${secret:env:NAME}` 的密文占位符，见 [`config_secret`](crate::config::config_secret).
+///
+/// ## Hot reload
+///
+/// 1. Remote config changes, see [`ConfCenterConfig::reload_on_remote_config_change`]
+///    ``Requires [conf-remote] feature``
+/// 1. Local file changes, see [`ConfWatchConfig`](crate::config::config_dto::ConfWatchConfig)
+///    ``Requires [conf-watch] feature``
 ///
 impl TardisConfig {
     pub async fn init(relative_path: Option<&str>) -> TardisResult<TardisConfig> {
@@ -64,6 +82,8 @@ impl TardisConfig {
 
         if let Some(relative_path) = relative_path {
             TardisLocale::init(Path::new(relative_path))?;
+            #[cfg(feature = "conf-watch")]
+            crate::config::config_watcher::watch(relative_path, &profile, &config.fw.conf_watch)?;
         }
         Ok(config)
     }
@@ -105,10 +125,13 @@ impl TardisConfig {
                 );
                 // listen reload signal
                 let reload_notifier = conf_center.reload_on_remote_config_change(relative_path);
-                let processor: Box<dyn ConfCenterProcess> = match conf_center.kind.to_lowercase().as_str() {
-                    "nacos" => Box::new(crate::config::config_nacos::ConfNacosProcessor::init(conf_center, profile, app_id, &Arc::new(format)).await?),
-                    _ => return Err(TardisError::format_error("[Tardis.Config] The kind of config center only supports [nacos]", "")),
-                };
+                let processor = TardisConfig::init_conf_center(ConfCenterContext {
+                    config: conf_center.clone(),
+                    profile: profile.to_string(),
+                    app_id: app_id.to_string(),
+                    format,
+                })
+                .await?;
                 conf = processor.register_to_config(conf);
                 // listen update, if update, send reload signal
                 processor.listen_update(&reload_notifier);
@@ -116,9 +139,12 @@ impl TardisConfig {
         }
 
         // Fetch from ENV
-        debug!("[Tardis.Config] Fetch env with prefix: TARDIS");
-        conf = conf.add_source(Environment::with_prefix("TARDIS"));
+        debug!("[Tardis.Config] Fetch env overlay");
+        conf = conf.add_source(crate::config::config_env::EnvOverlaySource::new());
         let conf = conf.build().await?;
+        crate::config::config_dump::record(&conf)?;
+        // Resolve secrets before deserialization
+        let conf = crate::config::config_secret::resolve_secrets(conf)?;
 
         let mut workspace_config: HashMap<String, Value> = Default::default();
         match conf.get::<Value>("cs") {
@@ -165,6 +191,7 @@ impl TardisConfig {
             #[cfg(not(feature = "crypto"))]
             return Err(TardisError::format_error("[Tardis.Config] Configuration encryption must depend on the crypto feature", ""));
             #[cfg(feature = "crypto")]
+            #[allow(deprecated)]
             {
                 // decryption processing
                 let salt = framework_config.adv.salt.clone();
@@ -182,16 +209,91 @@ impl TardisConfig {
     }
 }
 
+/// Config center processor / 配置中心处理器
+///
+/// Register custom processors by [`TardisConfig::register_conf_center`].
+///
+/// 通过 [`TardisConfig::register_conf_center`] 注册自定义的处理器.
 #[cfg(feature = "conf-remote")]
-// temporarily don't need async_trait
-// #[async_trait]
-pub(crate) trait ConfCenterProcess: Sync + Send + std::fmt::Debug {
-    /// listen the config-center processor change
+pub trait ConfCenterProcess: Sync + Send + std::fmt::Debug {
+    /// Listen the changes of the config center, send a signal to `reload_notifier` when changed
+    ///
+    /// 监听配置中心的变更，变更时向 `reload_notifier` 发送信号
     fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>);
-    /// Add all sources to config
+    /// Add all sources to config / 将所有配置源添加到配置中
     fn register_to_config(&self, conf: ConfigBuilder<AsyncState>) -> ConfigBuilder<AsyncState>;
 }
 
+/// Context to create a config center processor / 创建配置中心处理器的上下文
+#[cfg(feature = "conf-remote")]
+#[derive(Debug, Clone)]
+pub struct ConfCenterContext {
+    /// Config center configuration / 配置中心的配置
+    pub config: ConfCenterConfig,
+    /// Current profile / 当前的环境
+    pub profile: String,
+    /// Application id, i.e. `fw.app.id` / 应用Id，即 `fw.app.id`
+    pub app_id: String,
+    /// Format of the remote config / 远程配置的格式
+    pub format: FileFormat,
+}
+
+#[cfg(feature = "conf-remote")]
+type ConfCenterFactory = Arc<dyn Fn(ConfCenterContext) -> Pin<Box<dyn Future<Output = TardisResult<Box<dyn ConfCenterProcess>>> + Send>> + Send + Sync>;
+
+#[cfg(feature = "conf-remote")]
+crate::tardis_static! {
+    conf_center_factories: RwLock<HashMap<String, ConfCenterFactory>>;
+}
+
+#[cfg(feature = "conf-remote")]
+impl TardisConfig {
+    /// Register a config center processor / 注册配置中心处理器
+    ///
+    /// The processor is created by `factory` when `fw.conf_center.kind` equals to `kind` (case insensitive),
+    /// registered kinds take precedence over the built-in ones: `nacos`, `http`, `dir` and `redis` (requires the `cache` feature).
+    ///
+    /// 当 `fw.conf_center.kind` 等于 `kind` （不区分大小写）时由 `factory` 创建处理器，
+    /// 注册的类型优先于内置类型： `nacos` 、 `http` 、 `dir` 及 `redis` （需要开启 `cache` 特性）.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tardis::config::config_dto::TardisConfig;
+    /// TardisConfig::register_conf_center("etcd", |ctx| async move { Ok(Box::new(EtcdProcessor::init(&ctx).await?) as Box<dyn ConfCenterProcess>) });
+    /// ```
+    pub fn register_conf_center<F, T>(kind: &str, factory: F)
+    where
+        F: Fn(ConfCenterContext) -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<Box<dyn ConfCenterProcess>>> + Send + 'static,
+    {
+        let factory: ConfCenterFactory = Arc::new(move |ctx| Box::pin(factory(ctx)));
+        conf_center_factories().write().expect("[Tardis.Config] Config center factories lock poisoned").insert(kind.to_lowercase(), factory);
+    }
+
+    async fn init_conf_center(ctx: ConfCenterContext) -> TardisResult<Box<dyn ConfCenterProcess>> {
+        let kind = ctx.config.kind.to_lowercase();
+        let factory = conf_center_factories().read().expect("[Tardis.Config] Config center factories lock poisoned").get(&kind).cloned();
+        if let Some(factory) = factory {
+            return factory(ctx).await;
+        }
+        let processor: Box<dyn ConfCenterProcess> = match kind.as_str() {
+            "nacos" => Box::new(crate::config::config_nacos::ConfNacosProcessor::init(&ctx.config, &ctx.profile, &ctx.app_id, &Arc::new(ctx.format)).await?),
+            "http" => Box::new(crate::config::config_http::init(&ctx)?),
+            "dir" => Box::new(crate::config::config_dir::ConfDirProcessor::init(&ctx)?),
+            #[cfg(feature = "cache")]
+            "redis" => Box::new(crate::config::config_redis::init(&ctx).await?),
+            _ => {
+                return Err(TardisError::format_error(
+                    &format!("[Tardis.Config] The kind of config center [{kind}] is not supported, register it by TardisConfig::register_conf_center"),
+                    "",
+                ))
+            }
+        };
+        Ok(processor)
+    }
+}
+
 #[cfg(feature = "conf-remote")]
 impl ConfCenterConfig {
     /// Reload configuration on remote configuration change / 远程配置变更时重新加载配置
@@ -207,17 +309,18 @@ impl ConfCenterConfig {
                     return;
                 }
             };
-            if let Ok(config) = TardisConfig::init(relative_path.as_deref()).await {
-                match crate::TardisFuns::hot_reload(config).await {
+            match TardisConfig::init(relative_path.as_deref()).await.and_then(|config| config.check().map(|_| config)) {
+                Ok(config) => match crate::TardisFuns::hot_reload(config).await {
                     Ok(_) => {
                         tracing::info!("[Tardis.config] Tardis hot reloaded");
                     }
                     Err(e) => {
                         tracing::error!("[Tardis.config] Tardis shutdown with error {}", e);
                     }
+                },
+                Err(e) => {
+                    tracing::error!("[Tardis.config] Configuration update failed: {}", e);
                 }
-            } else {
-                tracing::error!("[Tardis.config] Configuration update failed: Failed to load configuration");
             }
             tracing::debug!("[Tardis.config] Configuration update listener closed")
         });
@@ -225,7 +328,9 @@ impl ConfCenterConfig {
     }
 }
 
+/// Decrypt the legacy `ENC(...)` values by `fw.adv.salt` / 使用 `fw.adv.salt` 解密旧的 `ENC(...)` 值
 #[cfg(feature = "crypto")]
+#[deprecated(note = "use the secret placeholders instead, see tardis::config::config_secret")]
 pub fn decryption(text: &str, salt: &str) -> TardisResult<String> {
     use crate::crypto::crypto_aead::algorithm::Aes128;
     if salt.len() != 16 {
@@ -0,0 +1,54 @@
+//! Config center of a redis hash / 基于Redis哈希的配置中心
+//!
+//! The documents are the fields of the hash `namespace` (default is `tardis:config`) in the redis of `url`,
+//! e.g. `HSET tardis:config my-app-default "<content>"` . Changes are detected by polling every
+//! `config_change_polling_interval` milliseconds.
+//!
+//! 文档为 `url` 所指Redis中哈希 `namespace` （默认为 `tardis:config` ）的各字段，
+//! 如 `HSET tardis:config my-app-default "<content>"` . 每隔 `config_change_polling_interval` 毫秒轮询检测变更.
+//!
+//! ```toml
+//! [fw.conf_center]
+//! kind = "redis"
+//! url = "redis://:password@127.0.0.1:6379/0"
+//! ```
+use url::Url;
+
+use super::config_document::{ConfDocumentLoader, ConfDocumentProcessor};
+use super::config_processor::ConfCenterContext;
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::cache::cache_client::TardisCacheClient;
+use crate::config::config_dto::CacheModuleConfig;
+
+/// Default key of the hash
+const DEFAULT_CONF_REDIS_KEY: &str = "tardis:config";
+
+pub(crate) struct ConfRedisLoader {
+    client: TardisCacheClient,
+    key: String,
+}
+
+impl std::fmt::Debug for ConfRedisLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfRedisLoader").field("key", &self.key).finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfDocumentLoader for ConfRedisLoader {
+    async fn load(&self, data_id: &str) -> TardisResult<Option<String>> {
+        Ok(self.client.hget(&self.key, data_id).await?)
+    }
+}
+
+/// create a new redis config processor
+pub(crate) async fn init(ctx: &ConfCenterContext) -> TardisResult<ConfDocumentProcessor<ConfRedisLoader>> {
+    let url = Url::parse(&ctx.config.url).map_err(|e| TardisError::format_error(&format!("[Tardis.Config] Invalid redis url: {e}"), "406-tardis-config-parse-error"))?;
+    let client = TardisCacheClient::init(&CacheModuleConfig { url }).await?;
+    let loader = ConfRedisLoader {
+        client,
+        key: ctx.config.namespace.clone().unwrap_or_else(|| DEFAULT_CONF_REDIS_KEY.to_string()),
+    };
+    Ok(ConfDocumentProcessor::new(ctx, loader))
+}
@@ -0,0 +1,106 @@
+//! JSON Schema of the config files / 配置文件的JSON Schema
+//!
+//! [`TardisConfig::schema`] exports the schema of the whole config file, i.e. the `fw` section and the custom
+//! sections (`cs` and `csm.<code>`) registered by [`TardisConfig::register_cs_schema`]. It can be used to lint the
+//! config files before deployment, e.g. by editors or CI.
+//!
+//! [`TardisConfig::schema`] 导出整个配置文件的Schema，即 `fw` 部分及通过 [`TardisConfig::register_cs_schema`]
+//! 注册的自定义部分（ `cs` 及 `csm.<code>` ）. 可用于在部署前检查配置文件，如在编辑器或CI中.
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::config::config_dto::{FrameworkConfig, TardisConfig};
+
+struct CsSchema {
+    schema: fn(&mut SchemaGenerator) -> Schema,
+    check: fn(&Value) -> Result<(), String>,
+}
+
+crate::tardis_static! {
+    cs_schemas: RwLock<BTreeMap<String, CsSchema>>;
+}
+
+impl TardisConfig {
+    /// Register the type of a custom configuration / 注册自定义配置的类型
+    ///
+    /// The type is used by [`schema`](Self::schema) and [`validate`](Self::validate), the empty `code` means the `cs` section,
+    /// others mean the `csm.<code>` sections.
+    ///
+    /// 该类型用于 [`schema`](Self::schema) 及 [`validate`](Self::validate) ，空的 `code` 表示 `cs` 部分，其它表示 `csm.<code>` 部分.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::schemars::JsonSchema;
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct ModuleConfig { ... }
+    /// TardisConfig::register_cs_schema::<ModuleConfig>("m1");
+    /// ```
+    pub fn register_cs_schema<T: JsonSchema + DeserializeOwned>(code: &str) {
+        cs_schemas().write().expect("[Tardis.Config] Custom config schemas lock poisoned").insert(
+            code.to_lowercase(),
+            CsSchema {
+                schema: |generator| generator.subschema_for::<T>(),
+                check: |value| serde_json::from_value::<T>(value.clone()).map(|_| ()).map_err(|e| e.to_string()),
+            },
+        );
+    }
+
+    /// JSON Schema of the framework configuration, i.e. the `fw` section / 框架配置即 `fw` 部分的JSON Schema
+    pub fn fw_schema() -> Value {
+        serde_json::to_value(SchemaSettings::draft07().into_generator().into_root_schema_for::<FrameworkConfig>()).expect("[Tardis.Config] Serialize schema error")
+    }
+
+    /// JSON Schema of the whole config file / 整个配置文件的JSON Schema
+    pub fn schema() -> Value {
+        let mut generator = SchemaSettings::draft07().into_generator();
+        let mut root = ObjectValidation::default();
+        root.properties.insert("fw".to_string(), generator.subschema_for::<FrameworkConfig>());
+        let mut csm = ObjectValidation::default();
+        for (code, cs_schema) in cs_schemas().read().expect("[Tardis.Config] Custom config schemas lock poisoned").iter() {
+            let schema = (cs_schema.schema)(&mut generator);
+            if code.is_empty() {
+                root.properties.insert("cs".to_string(), schema);
+            } else {
+                csm.properties.insert(code.clone(), schema);
+            }
+        }
+        if !csm.properties.is_empty() {
+            root.properties.insert("csm".to_string(), object_schema(csm).into());
+        }
+        let root = RootSchema {
+            meta_schema: generator.settings().meta_schema.clone(),
+            schema: object_schema(root),
+            definitions: generator.take_definitions(),
+        };
+        serde_json::to_value(root).expect("[Tardis.Config] Serialize schema error")
+    }
+}
+
+fn object_schema(object: ObjectValidation) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    }
+}
+
+/// Check the custom configurations against the registered types
+pub(crate) fn validate_cs(config: &TardisConfig, problems: &mut Vec<String>) {
+    for (code, cs_schema) in cs_schemas().read().expect("[Tardis.Config] Custom config schemas lock poisoned").iter() {
+        let path = if code.is_empty() { "cs".to_string() } else { format!("csm.{code}") };
+        match config.cs.get(code).or_else(|| config.cs.get("")) {
+            Some(value) => {
+                if let Err(e) = (cs_schema.check)(value) {
+                    problems.push(format!("{path} is invalid: {e}"));
+                }
+            }
+            None => problems.push(format!("{path} is missing")),
+        }
+    }
+}
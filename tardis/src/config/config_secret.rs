@@ -0,0 +1,229 @@
+//! Config secrets / 配置密文
+//!
+//! Any string value of the config files may contain secret placeholders, they are resolved before the configurations
+//! are deserialized, so every field (including the custom ones) can be a secret:
+//!
+//! - `${secret:env:NAME}` : the value of the environment variable `NAME`
+//! - `${secret:file:/path}` : the content of the file `/path` , the trailing newlines are trimmed
+//! - `${secret:aead:...}` : the value encrypted by AES-GCM, requires the `crypto` feature.
+//!   The key is the base64 encoded 16 or 32 bytes read from the environment variable [`SECRET_KEY_ENV`] or the file specified by [`SECRET_KEY_FILE_ENV`].
+//!   Use [`generate_secret_key`] and [`encrypt_secret`] or the `tardis-secret` bin to generate the key and the values.
+//!
+//! Other kinds can be registered by [`TardisConfig::register_secret_resolver`].
+//!
+//! 配置文件中的任意字符串值都可以包含密文占位符，占位符在配置反序列化前解析，所以任意字段（包括自定义配置）都可以是密文：
+//!
+//! - `${secret:env:NAME}` : 环境变量 `NAME` 的值
+//! - `${secret:file:/path}` : 文件 `/path` 的内容，会去除末尾的换行
+//! - `${secret:aead:...}` : 使用AES-GCM加密的值，需要开启 `crypto` 特性.
+//!   密钥为base64编码的16或32字节，从环境变量 [`SECRET_KEY_ENV`] 或 [`SECRET_KEY_FILE_ENV`] 指定的文件中读取.
+//!   使用 [`generate_secret_key`] 及 [`encrypt_secret`] 或 `tardis-secret` 程序生成密钥及密文.
+//!
+//! 其它类型可通过 [`TardisConfig::register_secret_resolver`] 注册.
+//!
+//! # Examples
+//! ```toml
+//! [fw.db]
+//! url = "postgres://${secret:env:DB_USER}:${secret:aead:2n4pQ...}@localhost/test"
+//! ```
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use config::{Config, ConfigError, Map, Source, Value, ValueKind};
+use regex::Regex;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::TardisConfig;
+
+/// Environment variable of the base64 encoded secret key / 存放base64编码密钥的环境变量
+pub const SECRET_KEY_ENV: &str = "TARDIS_SECRET_KEY";
+/// Environment variable of the secret key file path / 存放密钥文件路径的环境变量
+pub const SECRET_KEY_FILE_ENV: &str = "TARDIS_SECRET_KEY_FILE";
+
+const SECRET_PLACEHOLDER_PREFIX: &str = "${secret:";
+#[cfg(feature = "crypto")]
+const SECRET_NONCE_LEN: usize = 12;
+
+type SecretResolver = Arc<dyn Fn(&str) -> TardisResult<String> + Send + Sync>;
+
+crate::tardis_static! {
+    secret_resolvers: RwLock<HashMap<String, SecretResolver>>;
+    secret_placeholder_regex: Regex = Regex::new(r"\$\{secret:(?P<kind>[A-Za-z0-9_-]+):(?P<value>[^}]*)\}").expect("[Tardis.Config] Invalid secret placeholder regex");
+}
+
+impl TardisConfig {
+    /// Register a secret resolver / 注册密文解析器
+    ///
+    /// `resolver` resolves the placeholders like `${secret:<kind>:<value>}` , it receives the `<value>` part.
+    /// Registered kinds take precedence over the built-in ones: `env` , `file` and `aead` .
+    ///
+    /// `resolver` 用于解析形如 `${secret:<kind>:<value>}` 的占位符，其参数为 `<value>` 部分.
+    /// 注册的类型优先于内置类型： `env` 、 `file` 及 `aead` .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// TardisConfig::register_secret_resolver("vault", |path| vault_client.read(path));
+    /// ```
+    pub fn register_secret_resolver(kind: &str, resolver: impl Fn(&str) -> TardisResult<String> + Send + Sync + 'static) {
+        secret_resolvers().write().expect("[Tardis.Config] Secret resolvers lock poisoned").insert(kind.to_lowercase(), Arc::new(resolver));
+    }
+}
+
+/// Resolve the secret placeholders of all string values
+pub(crate) fn resolve_secrets(config: Config) -> TardisResult<Config> {
+    let mut table = config.collect()?;
+    let mut resolved = false;
+    for value in table.values_mut() {
+        resolved = resolve_value(value)? || resolved;
+    }
+    if !resolved {
+        return Ok(config);
+    }
+    Ok(Config::builder().add_source(ResolvedSource(table)).build()?)
+}
+
+fn resolve_value(value: &mut Value) -> TardisResult<bool> {
+    match &mut value.kind {
+        ValueKind::String(text) if text.contains(SECRET_PLACEHOLDER_PREFIX) => {
+            *text = resolve_text(text)?;
+            Ok(true)
+        }
+        ValueKind::Table(table) => table.values_mut().try_fold(false, |resolved, value| Ok(resolve_value(value)? || resolved)),
+        ValueKind::Array(array) => array.iter_mut().try_fold(false, |resolved, value| Ok(resolve_value(value)? || resolved)),
+        _ => Ok(false),
+    }
+}
+
+fn resolve_text(text: &str) -> TardisResult<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for captures in secret_placeholder_regex().captures_iter(text) {
+        let placeholder = captures.get(0).expect("[Tardis.Config] Secret placeholder regex must match");
+        result.push_str(&text[last..placeholder.start()]);
+        result.push_str(&resolve_secret(&captures["kind"], &captures["value"])?);
+        last = placeholder.end();
+    }
+    result.push_str(&text[last..]);
+    Ok(result)
+}
+
+fn resolve_secret(kind: &str, value: &str) -> TardisResult<String> {
+    let kind = kind.to_lowercase();
+    let resolver = secret_resolvers().read().expect("[Tardis.Config] Secret resolvers lock poisoned").get(&kind).cloned();
+    if let Some(resolver) = resolver {
+        return resolver(value);
+    }
+    match kind.as_str() {
+        "env" => std::env::var(value).map_err(|_| {
+            TardisError::not_found(
+                &format!("[Tardis.Config] Secret environment variable [{value}] not found"),
+                "404-tardis-config-secret-not-exist",
+            )
+        }),
+        "file" => std::fs::read_to_string(value)
+            .map(|content| content.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|e| TardisError::not_found(&format!("[Tardis.Config] Secret file [{value}] read error: {e}"), "404-tardis-config-secret-not-exist")),
+        #[cfg(feature = "crypto")]
+        "aead" => decrypt_secret(value, &load_secret_key()?),
+        #[cfg(not(feature = "crypto"))]
+        "aead" => Err(TardisError::format_error(
+            "[Tardis.Config] The aead secret must depend on the crypto feature",
+            "406-tardis-config-secret-invalid",
+        )),
+        _ => Err(TardisError::format_error(
+            &format!("[Tardis.Config] The kind of secret [{kind}] is not supported, register it by TardisConfig::register_secret_resolver"),
+            "406-tardis-config-secret-invalid",
+        )),
+    }
+}
+
+/// Config source of the resolved values
+#[derive(Debug, Clone)]
+struct ResolvedSource(Map<String, Value>);
+
+impl Source for ResolvedSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Generate a base64 encoded 32 bytes secret key / 生成base64编码的32字节密钥
+#[cfg(feature = "crypto")]
+pub fn generate_secret_key() -> String {
+    crate::TardisFuns::crypto.base64.encode(crate::TardisFuns::crypto.key.rand_32_bytes())
+}
+
+/// Load the secret key from [`SECRET_KEY_ENV`] or [`SECRET_KEY_FILE_ENV`] / 从 [`SECRET_KEY_ENV`] 或 [`SECRET_KEY_FILE_ENV`] 加载密钥
+#[cfg(feature = "crypto")]
+pub fn load_secret_key() -> TardisResult<Vec<u8>> {
+    let key = match (std::env::var(SECRET_KEY_ENV), std::env::var(SECRET_KEY_FILE_ENV)) {
+        (Ok(key), _) => key,
+        (_, Ok(path)) => std::fs::read_to_string(&path)
+            .map_err(|e| TardisError::not_found(&format!("[Tardis.Config] Secret key file [{path}] read error: {e}"), "404-tardis-config-secret-not-exist"))?,
+        _ => {
+            return Err(TardisError::not_found(
+                &format!("[Tardis.Config] Secret key not found, set it by the environment variable [{SECRET_KEY_ENV}] or [{SECRET_KEY_FILE_ENV}]"),
+                "404-tardis-config-secret-not-exist",
+            ))
+        }
+    };
+    let key = crate::TardisFuns::crypto.base64.decode(key.trim())?;
+    if key.len() != 16 && key.len() != 32 {
+        return Err(TardisError::format_error(
+            "[Tardis.Config] Secret key must be 16 or 32 bytes",
+            "406-tardis-config-secret-invalid",
+        ));
+    }
+    Ok(key)
+}
+
+/// Encrypt a value into the `${secret:aead:...}` placeholder / 将值加密为 `${secret:aead:...}` 占位符
+///
+/// `key` is 16 (AES-128-GCM) or 32 (AES-256-GCM) bytes.
+///
+/// `key` 为16（AES-128-GCM）或32（AES-256-GCM）字节.
+#[cfg(feature = "crypto")]
+pub fn encrypt_secret(value: &str, key: &[u8]) -> TardisResult<String> {
+    use crate::crypto::crypto_aead::algorithm::{Aes128Gcm, Aes256Gcm};
+    let aead = &crate::TardisFuns::crypto.aead;
+    let nonce = aead.random_nonce::<Aes256Gcm>();
+    let (ciphertext, nonce) = match key.len() {
+        16 => aead.encrypt::<Aes128Gcm>(key, b"", nonce, value)?,
+        32 => aead.encrypt::<Aes256Gcm>(key, b"", nonce, value)?,
+        _ => {
+            return Err(TardisError::format_error(
+                "[Tardis.Config] Secret key must be 16 or 32 bytes",
+                "406-tardis-config-secret-invalid",
+            ))
+        }
+    };
+    Ok(format!(
+        "{SECRET_PLACEHOLDER_PREFIX}aead:{}}}",
+        crate::TardisFuns::crypto.base64.encode([nonce, ciphertext].concat())
+    ))
+}
+
+/// Decrypt the value of the `${secret:aead:...}` placeholder / 解密 `${secret:aead:...}` 占位符的值
+#[cfg(feature = "crypto")]
+pub fn decrypt_secret(value: &str, key: &[u8]) -> TardisResult<String> {
+    use crate::crypto::crypto_aead::algorithm::{Aes128Gcm, Aes256Gcm};
+    let invalid = || TardisError::format_error("[Tardis.Config] Invalid aead secret", "406-tardis-config-secret-invalid");
+    let data = crate::TardisFuns::crypto.base64.decode(value).map_err(|_| invalid())?;
+    if data.len() <= SECRET_NONCE_LEN {
+        return Err(invalid());
+    }
+    let (nonce, ciphertext) = data.split_at(SECRET_NONCE_LEN);
+    let aead = &crate::TardisFuns::crypto.aead;
+    let plaintext = match key.len() {
+        16 => aead.decrypt::<Aes128Gcm>(key, b"", nonce, ciphertext),
+        32 => aead.decrypt::<Aes256Gcm>(key, b"", nonce, ciphertext),
+        _ => Err(invalid()),
+    }
+    .map_err(|_| invalid())?;
+    String::from_utf8(plaintext).map_err(|_| invalid())
+}
@@ -0,0 +1,194 @@
+//! Config validation / 配置校验
+//!
+//! Catch the misconfigured sections before the components are initialized, see [`TardisConfig::validate`].
+//!
+//! 在组件初始化前发现错误的配置，见 [`TardisConfig::validate`] .
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+use tracing::warn;
+use url::Url;
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::{ClusterWatchKind, FrameworkConfig, TardisComponentConfig, TardisConfig};
+
+const DB_SCHEMES: &[&str] = &["postgres", "postgresql", "mysql", "sqlite"];
+const CACHE_SCHEMES: &[&str] = &["redis", "rediss", "redis+unix", "unix"];
+const MQ_SCHEMES: &[&str] = &["amqp", "amqps"];
+const HTTP_SCHEMES: &[&str] = &["http", "https"];
+
+impl TardisConfig {
+    /// Validate the configuration / 校验配置
+    ///
+    /// Checks the url schemes of the components, the connection pool sizes, the TLS key/cert pairs, the module codes
+    /// and the cluster settings, as well as the custom sections registered by `TardisConfig::register_cs_schema` (requires the `conf-schema` feature).
+    /// All problems are reported in one error.
+    ///
+    /// 校验各组件的url协议、连接池大小、TLS key/cert配对、模块编码及集群配置，以及通过 `TardisConfig::register_cs_schema`
+    /// 注册的自定义配置（需要开启 `conf-schema` 特性）. 所有问题在一个错误中返回.
+    ///
+    /// The module codes are the keys of the `modules` of each component, e.g. `fw.web_server.modules` whose codes are the route prefixes
+    /// of the web modules, they are checked to be non-empty path segments without `/` or whitespaces.
+    /// A web module resolves its components and custom config by its code, see [`TardisFuns::inst`](crate::TardisFuns::inst),
+    /// so each code of `fw.web_server.modules` is checked to be configured in the `modules` of another component or in the `csm` sections.
+    ///
+    /// 模块编码为各组件 `modules` 的键，如 `fw.web_server.modules` 的编码为Web模块的路由前缀，校验其为不含 `/` 及空白字符的非空路径段.
+    /// Web模块按其编码获取组件及自定义配置，见 [`TardisFuns::inst`](crate::TardisFuns::inst) ，
+    /// 因此 `fw.web_server.modules` 的每个编码需要配置在其它组件的 `modules` 或 `csm` 部分中.
+    ///
+    /// The configuration is checked by [`check`](Self::check) in [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) before any component is initialized,
+    /// and before the config files are hot reloaded.
+    ///
+    /// [`TardisFuns::init_conf`](crate::TardisFuns::init_conf) 在初始化任何组件前、配置文件在热重载前会通过 [`check`](Self::check) 检查配置.
+    pub fn validate(&self) -> TardisResult<()> {
+        let mut problems = Vec::new();
+        validate_fw(&self.fw, &self.cs, &mut problems);
+        #[cfg(feature = "conf-schema")]
+        crate::config::config_schema::validate_cs(self, &mut problems);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(TardisError::format_error(
+                &format!("[Tardis.Config] Invalid config: {}", problems.join("; ")),
+                "406-tardis-config-invalid",
+            ))
+        }
+    }
+
+    /// Validate the configuration by [`validate`](Self::validate), the problems are only logged unless `fw.adv.strict_validation` is enabled /
+    /// 通过 [`validate`](Self::validate) 校验配置，除非启用了 `fw.adv.strict_validation` ，否则问题仅记录在日志中
+    pub fn check(&self) -> TardisResult<()> {
+        match self.validate() {
+            Err(error) if self.fw.adv.strict_validation => Err(error),
+            Err(error) => {
+                warn!("{}", error.message);
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+}
+
+fn validate_fw(fw: &FrameworkConfig, cs: &HashMap<String, Value>, problems: &mut Vec<String>) {
+    if let Some(db) = &fw.db {
+        validate_modules("fw.db", db, problems, |path, module, problems| {
+            validate_url(&format!("{path}.url"), &module.url, DB_SCHEMES, problems);
+            for (index, url) in module.replica_urls.iter().enumerate() {
+                validate_url(&format!("{path}.replica_urls[{index}]"), url, DB_SCHEMES, problems);
+            }
+            if module.max_connections == 0 {
+                problems.push(format!("{path}.max_connections must be greater than 0"));
+            }
+            if module.min_connections > module.max_connections {
+                problems.push(format!(
+                    "{path}.min_connections ({}) must not be greater than max_connections ({})",
+                    module.min_connections, module.max_connections
+                ));
+            }
+        });
+    }
+    if let Some(web_server) = &fw.web_server {
+        if web_server.tls_key.is_some() != web_server.tls_cert.is_some() {
+            problems.push("fw.web_server.tls_key and fw.web_server.tls_cert must be set together".to_string());
+        }
+        validate_modules("fw.web_server", web_server, problems, |path, module, problems| {
+            for (env, url) in &module.doc_urls {
+                validate_url(&format!("{path}.doc_urls.{env}"), url, HTTP_SCHEMES, problems);
+            }
+        });
+        let module_codes = component_module_codes(fw).chain(cs.keys()).map(|code| code.to_lowercase()).collect::<HashSet<_>>();
+        for code in web_server.modules.keys() {
+            if !module_codes.contains(&code.to_lowercase()) {
+                problems.push(format!("fw.web_server.modules.{code} is not configured in the modules of the other components or in csm"));
+            }
+        }
+    }
+    if let Some(cache) = &fw.cache {
+        validate_modules("fw.cache", cache, problems, |path, module, problems| {
+            validate_scheme(&format!("{path}.url"), &module.url, CACHE_SCHEMES, problems)
+        });
+    }
+    if let Some(mq) = &fw.mq {
+        validate_modules("fw.mq", mq, problems, |path, module, problems| {
+            validate_scheme(&format!("{path}.url"), &module.url, MQ_SCHEMES, problems)
+        });
+    }
+    if let Some(search) = &fw.search {
+        validate_modules("fw.search", search, problems, |path, module, problems| {
+            validate_scheme(&format!("{path}.url"), &module.url, HTTP_SCHEMES, problems)
+        });
+    }
+    if let Some(mail) = &fw.mail {
+        validate_modules("fw.mail", mail, problems, |_, _, _| {});
+    }
+    if let Some(os) = &fw.os {
+        validate_modules("fw.os", os, problems, |path, module, problems| {
+            if !module.endpoint.is_empty() {
+                validate_url(&format!("{path}.endpoint"), &module.endpoint, HTTP_SCHEMES, problems);
+            }
+        });
+    }
+    if let Some(web_client) = &fw.web_client {
+        validate_modules("fw.web_client", web_client, problems, |_, _, _| {});
+    }
+    if let Some(cluster) = &fw.cluster {
+        match cluster.watch_kind {
+            ClusterWatchKind::Static if cluster.static_nodes.is_empty() => problems.push("fw.cluster.static_nodes must not be empty when watch_kind is static".to_string()),
+            ClusterWatchKind::Cache if fw.cache.is_none() => problems.push("fw.cache must be set when fw.cluster.watch_kind is cache".to_string()),
+            _ => {}
+        }
+        if cluster.heartbeat_timeout_sec <= cluster.heartbeat_interval_sec {
+            problems.push(format!(
+                "fw.cluster.heartbeat_timeout_sec ({}) must be greater than heartbeat_interval_sec ({})",
+                cluster.heartbeat_timeout_sec, cluster.heartbeat_interval_sec
+            ));
+        }
+    }
+    #[cfg(feature = "conf-remote")]
+    if let Some(conf_center) = &fw.conf_center {
+        match conf_center.kind.to_lowercase().as_str() {
+            "nacos" | "http" => validate_url("fw.conf_center.url", &conf_center.url, HTTP_SCHEMES, problems),
+            "redis" => validate_url("fw.conf_center.url", &conf_center.url, CACHE_SCHEMES, problems),
+            _ => {}
+        }
+    }
+    #[cfg(feature = "tracing")]
+    if let Some(tracing) = fw.log.as_ref().and_then(|log| log.tracing.as_ref()) {
+        validate_url("fw.log.tracing.endpoint", &tracing.endpoint, HTTP_SCHEMES, problems);
+    }
+}
+
+/// Module codes of the components except the web server
+fn component_module_codes(fw: &FrameworkConfig) -> impl Iterator<Item = &String> {
+    fn codes<T, C: Default>(config: &Option<TardisComponentConfig<T, C>>) -> impl Iterator<Item = &String> {
+        config.iter().flat_map(|config| config.modules.keys())
+    }
+    codes(&fw.db).chain(codes(&fw.cache)).chain(codes(&fw.mq)).chain(codes(&fw.search)).chain(codes(&fw.mail)).chain(codes(&fw.os)).chain(codes(&fw.web_client))
+}
+
+/// Validate the default module and the submodules of a component
+fn validate_modules<T, C: Default>(path: &str, config: &TardisComponentConfig<T, C>, problems: &mut Vec<String>, validate: impl Fn(&str, &T, &mut Vec<String>)) {
+    validate(path, &config.default, problems);
+    for (code, module) in &config.modules {
+        if code.is_empty() || code.contains(|c: char| c == '/' || c.is_whitespace()) {
+            problems.push(format!(
+                "{path}.modules has an invalid module code [{code}], it must be non-empty without '/' or whitespaces"
+            ));
+        }
+        validate(&format!("{path}.modules.{code}"), module, problems);
+    }
+}
+
+fn validate_url(path: &str, url: &str, schemes: &[&str], problems: &mut Vec<String>) {
+    match Url::parse(url) {
+        Ok(url) => validate_scheme(path, &url, schemes, problems),
+        Err(e) => problems.push(format!("{path} is not a valid url: {e}")),
+    }
+}
+
+fn validate_scheme(path: &str, url: &Url, schemes: &[&str], problems: &mut Vec<String>) {
+    if !schemes.contains(&url.scheme()) {
+        problems.push(format!("{path} has an unsupported scheme [{}], expected one of [{}]", url.scheme(), schemes.join(",")));
+    }
+}
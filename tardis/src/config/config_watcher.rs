@@ -0,0 +1,125 @@
+//! Local config files watcher / 本地配置文件监听
+//!
+//! Enabled by [`ConfWatchConfig::enabled`], see [`ConfWatchConfig`] for the watched files.
+//! Kubernetes ConfigMap updates, which swap the `..data` symlink of the mounted directory, are detected as well.
+//!
+//! The new config is loaded by [`TardisConfig::init`] and checked by [`TardisConfig::check`] first,
+//! it's rejected and logged if it's invalid and `fw.adv.strict_validation` is enabled, the running components are kept in that case.
+//!
+//! 由 [`ConfWatchConfig::enabled`] 开启，监听的文件见 [`ConfWatchConfig`] .
+//! Kubernetes ConfigMap的更新（替换挂载目录下的 `..data` 符号链接）同样会被检测到.
+//!
+//! 新配置先通过 [`TardisConfig::init`] 加载并经 [`TardisConfig::check`] 检查，无效且启用了 `fw.adv.strict_validation` 时拒绝该配置并记录日志，此时保留正在运行的组件.
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{debug, error, info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::{ConfWatchConfig, TardisConfig};
+
+struct ConfWatcher {
+    path: PathBuf,
+    config: ConfWatchConfig,
+    // the listener stops when the watcher is dropped
+    _watcher: RecommendedWatcher,
+}
+
+crate::tardis_static! {
+    conf_watcher: Mutex<Option<ConfWatcher>>;
+}
+
+/// Watch the local config files of `relative_path`, the watcher is replaced only when the path or the watch config changed
+pub(crate) fn watch(relative_path: &str, profile: &str, config: &ConfWatchConfig) -> TardisResult<()> {
+    let mut current = conf_watcher().lock().map_err(|error| TardisError::internal_error(&format!("{error:?}"), ""))?;
+    if !config.enabled {
+        if current.take().is_some() {
+            info!("[Tardis.Config] Local config files watcher stopped");
+        }
+        return Ok(());
+    }
+    let path = PathBuf::from(relative_path);
+    if current.as_ref().map(|watcher| watcher.path == path && &watcher.config == config).unwrap_or(false) {
+        return Ok(());
+    }
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<notify::Event>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+        Ok(event) => {
+            let _ = tx.send(event);
+        }
+        Err(e) => warn!("[Tardis.Config] Watch local config files error: {e}"),
+    })
+    .map_err(|e| TardisError::io_error(&format!("[Tardis.Config] Create local config files watcher error: {e}"), "-1-tardis-config-error"))?;
+    watcher.watch(&path, RecursiveMode::NonRecursive).map_err(|e| TardisError::io_error(&format!("[Tardis.Config] Watch {path:?} error: {e}"), "-1-tardis-config-error"))?;
+    let locale_path = path.join("locale");
+    let locale_path = if config.watch_locale && locale_path.is_dir() {
+        watcher
+            .watch(&locale_path, RecursiveMode::NonRecursive)
+            .map_err(|e| TardisError::io_error(&format!("[Tardis.Config] Watch {locale_path:?} error: {e}"), "-1-tardis-config-error"))?;
+        Some(locale_path)
+    } else {
+        None
+    };
+    let mut file_prefixes = vec!["conf-default.".to_string(), "..data".to_string()];
+    if !profile.is_empty() {
+        file_prefixes.push(format!("conf-{profile}."));
+    }
+    info!("[Tardis.Config] Watching local config files in {:?}", path);
+    tokio::spawn(listen(rx, relative_path.to_string(), file_prefixes, locale_path, Duration::from_millis(config.debounce_ms)));
+    *current = Some(ConfWatcher {
+        path,
+        config: config.clone(),
+        _watcher: watcher,
+    });
+    Ok(())
+}
+
+/// Stop watching the local config files
+pub(crate) fn unwatch() {
+    if let Ok(mut current) = conf_watcher().lock() {
+        current.take();
+    }
+}
+
+async fn listen(mut rx: tokio::sync::mpsc::UnboundedReceiver<notify::Event>, relative_path: String, file_prefixes: Vec<String>, locale_path: Option<PathBuf>, debounce: Duration) {
+    let is_watched = |path: &Path| {
+        if locale_path.as_ref().map(|locale_path| path.parent() == Some(locale_path.as_path())).unwrap_or(false) {
+            return true;
+        }
+        path.file_name().and_then(|name| name.to_str()).map(|name| file_prefixes.iter().any(|prefix| name.starts_with(prefix))).unwrap_or(false)
+    };
+    while let Some(event) = rx.recv().await {
+        if matches!(event.kind, EventKind::Access(_)) || !event.paths.iter().any(|path| is_watched(path)) {
+            continue;
+        }
+        debug!("[Tardis.Config] Local config files changed: {:?}", event.paths);
+        // wait until the files are quiet
+        loop {
+            match tokio::time::timeout(debounce, rx.recv()).await {
+                Ok(Some(_)) => continue,
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+        reload(&relative_path).await;
+    }
+    debug!("[Tardis.Config] Local config files listener closed");
+}
+
+async fn reload(relative_path: &str) {
+    info!("[Tardis.Config] Local config files changed, reloading");
+    let config = match TardisConfig::init(Some(relative_path)).await.and_then(|config| config.check().map(|_| config)) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("[Tardis.Config] Reject the invalid local config files, keep running with the current config: {e}");
+            return;
+        }
+    };
+    match crate::TardisFuns::hot_reload(config).await {
+        Ok(_) => info!("[Tardis.Config] Tardis hot reloaded"),
+        Err(e) => error!("[Tardis.Config] Tardis hot reload with error {e}"),
+    }
+}
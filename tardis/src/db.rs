@@ -1,4 +1,10 @@
 pub use sea_orm;
 pub mod domain;
+pub mod reldb_audit;
 pub mod reldb_client;
+pub(crate) mod reldb_del_record;
+pub mod reldb_dialect;
+pub mod reldb_migration;
+pub mod reldb_tenant;
+pub mod reldb_trace;
 pub use sqlx;
@@ -1,2 +1,4 @@
+pub mod tardis_db_audit_record;
 pub mod tardis_db_config;
 pub mod tardis_db_del_record;
+pub mod tardis_db_migration;
@@ -0,0 +1,100 @@
+use chrono::Utc;
+
+use crate::basic::dto::TardisContext;
+use crate::db::reldb_client::TardisActiveModel;
+use crate::db::sea_orm::entity::prelude::*;
+use crate::db::sea_orm::sea_query::{Alias, ColumnDef, Table, TableCreateStatement};
+use crate::db::sea_orm::ActiveValue::Set;
+use crate::db::sea_orm::DbBackend;
+use crate::TardisFuns;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "tardis_audit_record")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(indexed)]
+    pub entity_name: String,
+    #[sea_orm(indexed)]
+    pub record_id: String,
+    /// `insert` , `update` or `delete`
+    pub operation: String,
+    /// Changed fields before the operation, json object / 操作前的变更字段，json对象
+    #[sea_orm(column_type = "Text", nullable)]
+    pub before: Option<String>,
+    /// Changed fields after the operation, json object / 操作后的变更字段，json对象
+    #[sea_orm(column_type = "Text", nullable)]
+    pub after: Option<String>,
+    pub owner: String,
+    pub ak: String,
+    /// Own paths of the record if its table is tenant-scoped / 记录的所属路径，如果其表是租户隔离表
+    pub own_paths: String,
+    /// Strictly increasing within a process at the microsecond precision / 在同一进程内以微秒精度严格递增
+    pub create_time: chrono::DateTime<Utc>,
+}
+
+impl TardisActiveModel for ActiveModel {
+    fn fill_ctx(&mut self, _: &TardisContext, _: bool) {}
+
+    fn create_table_statement(db_type: DbBackend) -> TableCreateStatement {
+        match db_type {
+            DbBackend::MySql => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .engine("InnoDB")
+                .character_set("utf8mb4")
+                .collate("utf8mb4_0900_as_cs")
+                .col(ColumnDef::new(Column::Id).not_null().string().primary_key())
+                .col(ColumnDef::new(Column::EntityName).not_null().string())
+                .col(ColumnDef::new(Column::RecordId).not_null().string())
+                .col(ColumnDef::new(Column::Operation).not_null().string())
+                .col(ColumnDef::new(Column::Before).null().text())
+                .col(ColumnDef::new(Column::After).null().text())
+                .col(ColumnDef::new(Column::Owner).not_null().string())
+                .col(ColumnDef::new(Column::Ak).not_null().string())
+                .col(ColumnDef::new(Column::OwnPaths).not_null().string())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP(6)".to_string()).custom(Alias::new("TIMESTAMP(6)")))
+                .to_owned(),
+            DbBackend::Postgres => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .col(ColumnDef::new(Column::Id).not_null().string().primary_key())
+                .col(ColumnDef::new(Column::EntityName).not_null().string())
+                .col(ColumnDef::new(Column::RecordId).not_null().string())
+                .col(ColumnDef::new(Column::Operation).not_null().string())
+                .col(ColumnDef::new(Column::Before).null().text())
+                .col(ColumnDef::new(Column::After).null().text())
+                .col(ColumnDef::new(Column::Owner).not_null().string())
+                .col(ColumnDef::new(Column::Ak).not_null().string())
+                .col(ColumnDef::new(Column::OwnPaths).not_null().string())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp_with_time_zone())
+                .to_owned(),
+            DbBackend::Sqlite => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .col(ColumnDef::new(Column::Id).not_null().string().primary_key())
+                .col(ColumnDef::new(Column::EntityName).not_null().string())
+                .col(ColumnDef::new(Column::RecordId).not_null().string())
+                .col(ColumnDef::new(Column::Operation).not_null().string())
+                .col(ColumnDef::new(Column::Before).null().text())
+                .col(ColumnDef::new(Column::After).null().text())
+                .col(ColumnDef::new(Column::Owner).not_null().string())
+                .col(ColumnDef::new(Column::Ak).not_null().string())
+                .col(ColumnDef::new(Column::OwnPaths).not_null().string())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
+                .to_owned(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: Set(TardisFuns::field.nanoid()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
@@ -1,13 +1,59 @@
+//! Data dictionary / 数据字典
+//!
+//! [`TardisDataDict`] stores the key/values in the `tardis_config` table, the values can be plain strings or JSON values,
+//! see [`TardisDataDict::get_as`] and [`TardisDataDict::add_as`] .
+//! The keys are namespaced by `own_paths` through [`TardisDataDict::scope`] , the root scope is the one without `own_paths` .
+//!
+//! [`TardisDataDict`] 将键值存储在 `tardis_config` 表中，值可以是普通字符串或JSON值，见 [`TardisDataDict::get_as`] 及 [`TardisDataDict::add_as`] .
+//! 键通过 [`TardisDataDict::scope`] 按 `own_paths` 划分命名空间，根空间为没有 `own_paths` 的空间.
+//!
+//! The keys and the `own_paths` must not contain `::` , and the keys of the root scope must not start with the reserved `__tardis_` prefix,
+//! so a scope never sees the values of the others, nor the internal ones like the migration lock.
+//!
+//! 键及 `own_paths` 不能包含 `::` ，根空间的键不能以保留的 `__tardis_` 前缀开头，因此各空间互相不可见，也看不到迁移锁等内部值.
+//!
+//! The values fetched by `get` and `get_as` are cached in process once [`TardisDataDict::enable_cache`] is called,
+//! the reads in a transaction always hit the database. With the `cache` feature, `TardisDataDict::enable_distributed_cache`
+//! fronts the database by a `TardisCacheClient` and broadcasts the invalidations to the other instances by its pub/sub,
+//! otherwise the cached values of the other instances are kept until their `ttl` expires.
+//! The values are only written to the distributed cache if absent and not evicted since they were read,
+//! and always expire, after `ttl` or 60 seconds if it's None, in case an invalidation is missed.
+//! The in-process cached values are kept per [`TardisRelDBClient`](crate::db::reldb_client::TardisRelDBClient) .
+//! The changes made by `add` , `update` and `delete` are published to [`TardisDataDict::subscribe`] of every instance.
+//!
+//! 调用 [`TardisDataDict::enable_cache`] 后 `get` 及 `get_as` 获取的值会缓存在进程内，事务中的读取总是访问数据库.
+//! 启用 `cache` 特性时， `TardisDataDict::enable_distributed_cache` 在数据库前增加一层 `TardisCacheClient` 缓存并通过其 pub/sub 将失效通知广播到其它实例，
+//! 否则其它实例的缓存值保留至其 `ttl` 过期.
+//! 仅当值不存在且读取后未被清除时才写入分布式缓存，且总会过期（ `ttl` 或其为 None 时的60秒），以防遗漏失效通知.
+//! 进程内的缓存值按 [`TardisRelDBClient`](crate::db::reldb_client::TardisRelDBClient) 分别保存.
+//! `add` 、 `update` 及 `delete` 的变更会发布到每个实例的 [`TardisDataDict::subscribe`] .
+//!
+//! The invalidations of the changes made in a transaction are broadcast before the commit,
+//! call [`TardisDataDictScope::invalidate`] after the commit if the values may be read by others in between.
+//!
+//! 事务中变更的失效通知在提交前广播，如果期间其他方可能读取该值，请在提交后调用 [`TardisDataDictScope::invalidate`] .
+use std::collections::HashMap;
+#[cfg(feature = "cache")]
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
 use chrono::Utc;
 use sea_orm::*;
-use tracing::trace;
+use tokio::sync::broadcast;
+use tracing::{trace, warn};
 
 use crate::basic::dto::TardisContext;
+use crate::basic::error::TardisError;
+#[cfg(feature = "cache")]
+use crate::cache::cache_client::TardisCacheClient;
 use crate::db::domain::tardis_db_config;
-use crate::db::reldb_client::{TardisActiveModel, TardisRelDBlConnection};
-use crate::db::sea_orm::sea_query::{ColumnDef, Table, TableCreateStatement};
+use crate::db::reldb_client::{escape_like, TardisActiveModel, TardisRelDBlConnection};
+use crate::db::sea_orm::sea_query::{ColumnDef, LikeExpr, Table, TableCreateStatement};
 use crate::db::sea_orm::ActiveValue::Set;
-use crate::TardisResult;
+use crate::serde::de::DeserializeOwned;
+use crate::serde::{Deserialize, Serialize};
+use crate::{TardisFuns, TardisResult};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "tardis_config")]
@@ -59,7 +105,7 @@ impl TardisActiveModel for ActiveModel {
                 .col(ColumnDef::new(Column::Creator).not_null().string())
                 .col(ColumnDef::new(Column::Updater).not_null().string())
                 .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
-                .col(ColumnDef::new(Column::UpdateTime).extra("DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP".to_string()).timestamp())
+                .col(ColumnDef::new(Column::UpdateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
                 .to_owned(),
         }
     }
@@ -70,35 +116,441 @@ impl ActiveModelBehavior for ActiveModel {}
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
+/// Separator between the `own_paths` and the key
+const NAMESPACE_SEPARATOR: &str = "::";
+/// Prefix of the internal keys of the root scope, e.g. the migration lock
+const INTERNAL_KEY_PREFIX: &str = "__tardis_";
+/// Capacity of the change events not yet received by a subscriber
+const EVENT_CAPACITY: usize = 256;
+#[cfg(feature = "cache")]
+const CACHE_KEY_PREFIX: &str = "tardis:dict:";
+#[cfg(feature = "cache")]
+const CACHE_CHANNEL: &str = "tardis:dict:changes";
+#[cfg(feature = "cache")]
+const CACHE_RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(1);
+/// Time to live of the distributed cached values without a `ttl`
+#[cfg(feature = "cache")]
+const DISTRIBUTED_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct DictCache {
+    enabled: bool,
+    ttl: Option<Duration>,
+    /// By the namespaced key and the client id, the missing keys are cached as `None`
+    entries: HashMap<String, HashMap<usize, (Option<TardisDictResp>, Instant)>>,
+    /// Number of the evictions, the values read before an eviction are not cached
+    evictions: u64,
+    #[cfg(feature = "cache")]
+    cache_client: Option<Arc<TardisCacheClient>>,
+    #[cfg(feature = "cache")]
+    listener: Option<tokio::task::JoinHandle<()>>,
+}
+
+crate::tardis_static! {
+    dict_cache: RwLock<DictCache>;
+    dict_events: broadcast::Sender<TardisDictChangeEvent> = broadcast::channel(EVENT_CAPACITY).0;
+    dict_instance: String = TardisFuns::field.nanoid();
+}
+
+/// Operation of a dict change / 字典变更的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TardisDictChangeOperation {
+    Add,
+    Update,
+    Delete,
+}
+
+/// Change of a dict value / 字典值的变更
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TardisDictChangeEvent {
+    /// `own_paths` of the scope, empty for the root scope / 所属空间的 `own_paths` ，根空间为空
+    pub own_paths: String,
+    /// Key without the namespace / 不含命名空间的键
+    pub key: String,
+    pub operation: TardisDictChangeOperation,
+}
+
+/// Change broadcast to the other instances, the invalidations have no operation
+#[cfg(feature = "cache")]
+#[derive(Serialize, Deserialize)]
+struct DictChangeMessage {
+    instance: String,
+    own_paths: String,
+    key: String,
+    operation: Option<TardisDictChangeOperation>,
+}
+
+/// Reject the keys aliasing the other scopes or the internal keys
+fn check_key(own_paths: &str, key: &str) -> TardisResult<()> {
+    if own_paths.contains(NAMESPACE_SEPARATOR) || key.contains(NAMESPACE_SEPARATOR) {
+        return Err(TardisError::bad_request(
+            &format!("[Tardis.RelDBClient] [db_config] The own paths and the key must not contain {NAMESPACE_SEPARATOR}"),
+            "400-tardis-reldb-dict-key-invalid",
+        ));
+    }
+    if own_paths.is_empty() && key.starts_with(INTERNAL_KEY_PREFIX) {
+        return Err(TardisError::bad_request(
+            &format!("[Tardis.RelDBClient] [db_config] The key must not start with the reserved prefix {INTERNAL_KEY_PREFIX}"),
+            "400-tardis-reldb-dict-key-invalid",
+        ));
+    }
+    Ok(())
+}
+
+fn namespaced_key(own_paths: &str, key: &str) -> String {
+    if own_paths.is_empty() {
+        key.to_string()
+    } else {
+        format!("{own_paths}{NAMESPACE_SEPARATOR}{key}")
+    }
+}
+
+fn cache_lock() -> std::sync::RwLockWriteGuard<'static, DictCache> {
+    dict_cache().write().expect("[Tardis.RelDBClient] Data dict cache lock poisoned")
+}
+
+/// Cached value of the key read by the client, `None` if not cached
+fn cached(client_id: usize, key: &str) -> Option<Option<TardisDictResp>> {
+    let cache = dict_cache().read().expect("[Tardis.RelDBClient] Data dict cache lock poisoned");
+    if !cache.enabled {
+        return None;
+    }
+    let (value, cached_at) = cache.entries.get(key)?.get(&client_id)?;
+    if cache.ttl.map(|ttl| cached_at.elapsed() >= ttl).unwrap_or(false) {
+        return None;
+    }
+    Some(value.clone())
+}
+
+fn evictions() -> u64 {
+    dict_cache().read().expect("[Tardis.RelDBClient] Data dict cache lock poisoned").evictions
+}
+
+/// Cache the value read by the client, unless evicted since `evictions` was taken
+fn cache(client_id: usize, key: &str, value: Option<TardisDictResp>, evictions: u64) {
+    let mut cache = cache_lock();
+    if cache.enabled && cache.evictions == evictions {
+        cache.entries.entry(key.to_string()).or_default().insert(client_id, (value, Instant::now()));
+    }
+}
+
+/// Evict the key of all the clients
+fn evict(key: &str) {
+    let mut cache = cache_lock();
+    cache.entries.remove(key);
+    cache.evictions += 1;
+}
+
+fn evict_all() {
+    let mut cache = cache_lock();
+    cache.entries.clear();
+    cache.evictions += 1;
+}
+
+#[cfg(feature = "cache")]
+fn cache_client() -> Option<Arc<TardisCacheClient>> {
+    dict_cache().read().expect("[Tardis.RelDBClient] Data dict cache lock poisoned").cache_client.clone()
+}
+
+/// Evict the key on every instance, and notify the subscribers of the change if any
+async fn publish(own_paths: &str, key: &str, operation: Option<TardisDictChangeOperation>) {
+    let namespaced_key = namespaced_key(own_paths, key);
+    evict(&namespaced_key);
+    #[cfg(feature = "cache")]
+    if let Some(cache_client) = cache_client() {
+        if let Err(error) = cache_client.del(&format!("{CACHE_KEY_PREFIX}{namespaced_key}")).await {
+            warn!("[Tardis.RelDBClient] [db_config] evict cached key {} error: {}", namespaced_key, error);
+        }
+        let message = DictChangeMessage {
+            instance: dict_instance().clone(),
+            own_paths: own_paths.to_string(),
+            key: key.to_string(),
+            operation,
+        };
+        match TardisFuns::json.obj_to_string(&message) {
+            Ok(message) => {
+                if let Err(error) = cache_client.publish(CACHE_CHANNEL, &message).await {
+                    warn!("[Tardis.RelDBClient] [db_config] publish change of key {} error: {}", namespaced_key, error);
+                }
+            }
+            Err(error) => warn!("[Tardis.RelDBClient] [db_config] serialize change of key {} error: {}", namespaced_key, error),
+        }
+    }
+    if let Some(operation) = operation {
+        // no receiver is not an error
+        let _ = dict_events().send(TardisDictChangeEvent {
+            own_paths: own_paths.to_string(),
+            key: key.to_string(),
+            operation,
+        });
+    }
+}
+
+/// Receive the changes of the other instances until the cache is disabled
+#[cfg(feature = "cache")]
+async fn listen(cache_client: Arc<TardisCacheClient>) {
+    use futures_util::StreamExt;
+    loop {
+        match cache_client.pubsub().await {
+            Ok(mut pubsub) => match pubsub.subscribe(CACHE_CHANNEL).await {
+                Ok(_) => {
+                    let mut messages = pubsub.into_on_message();
+                    while let Some(message) = messages.next().await {
+                        let message = match message
+                            .get_payload::<String>()
+                            .map_err(|error| error.to_string())
+                            .and_then(|payload| TardisFuns::json.str_to_obj::<DictChangeMessage>(&payload).map_err(|error| error.to_string()))
+                        {
+                            Ok(message) => message,
+                            Err(error) => {
+                                warn!("[Tardis.RelDBClient] [db_config] invalid change message: {}", error);
+                                continue;
+                            }
+                        };
+                        evict(&namespaced_key(&message.own_paths, &message.key));
+                        if let (Some(operation), false) = (message.operation, &message.instance == dict_instance()) {
+                            let _ = dict_events().send(TardisDictChangeEvent {
+                                own_paths: message.own_paths,
+                                key: message.key,
+                                operation,
+                            });
+                        }
+                    }
+                }
+                Err(error) => warn!("[Tardis.RelDBClient] [db_config] subscribe changes error: {}", error),
+            },
+            Err(error) => warn!("[Tardis.RelDBClient] [db_config] connect to the changes channel error: {}", error),
+        }
+        // the changes may be missed while resubscribing
+        evict_all();
+        tokio::time::sleep(CACHE_RESUBSCRIBE_INTERVAL).await;
+    }
+}
+
 pub struct TardisDataDict;
 
 impl TardisDataDict {
+    /// Scope of the `own_paths` / `own_paths` 的空间
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let enabled = TardisFuns::dict.scope(&ctx.own_paths).get_as::<bool>("feature.x", &conn).await?.unwrap_or(false);
+    /// ```
+    pub fn scope<'a>(&self, own_paths: &'a str) -> TardisDataDictScope<'a> {
+        TardisDataDictScope { own_paths }
+    }
+
+    /// Enable the in-process cache of `get` and `get_as` / 启用 `get` 及 `get_as` 的进程内缓存
+    ///
+    /// # Arguments
+    ///
+    ///  * `ttl` - time to live of the cached values, never expire if None / 缓存值的存活时间，None 表示永不过期
+    pub fn enable_cache(&self, ttl: Option<Duration>) {
+        let mut cache = cache_lock();
+        cache.enabled = true;
+        cache.ttl = ttl;
+        cache.entries.clear();
+        cache.evictions += 1;
+    }
+
+    /// Enable the in-process cache fronted by the distributed cache, the changes are broadcast by its pub/sub / 启用由分布式缓存支撑的进程内缓存，变更通过其 pub/sub 广播
+    ///
+    /// # Arguments
+    ///
+    ///  * `cache_client` - distributed cache client / 分布式缓存客户端
+    ///  * `ttl` - time to live of the cached values, the in-process ones never expire and the distributed ones expire after 60 seconds if None /
+    ///    缓存值的存活时间，None 表示进程内的缓存值永不过期，分布式缓存值60秒后过期
+    #[cfg(feature = "cache")]
+    pub fn enable_distributed_cache(&self, cache_client: Arc<TardisCacheClient>, ttl: Option<Duration>) {
+        let mut cache = cache_lock();
+        if let Some(listener) = cache.listener.take() {
+            listener.abort();
+        }
+        cache.enabled = true;
+        cache.ttl = ttl;
+        cache.entries.clear();
+        cache.evictions += 1;
+        cache.listener = Some(tokio::spawn(listen(cache_client.clone())));
+        cache.cache_client = Some(cache_client);
+    }
+
+    /// Disable the cache / 禁用缓存
+    pub fn disable_cache(&self) {
+        let mut cache = cache_lock();
+        #[cfg(feature = "cache")]
+        if let Some(listener) = cache.listener.take() {
+            listener.abort();
+        }
+        // the reads in progress must not cache their values once enabled again
+        *cache = DictCache {
+            evictions: cache.evictions + 1,
+            ..Default::default()
+        };
+    }
+
+    /// Subscribe the changes of the dict values / 订阅字典值的变更
+    ///
+    /// Including the changes of the other instances when the distributed cache is enabled.
+    ///
+    /// 启用分布式缓存时包含其它实例的变更.
+    pub fn subscribe(&self) -> broadcast::Receiver<TardisDictChangeEvent> {
+        dict_events().subscribe()
+    }
+
     /// Get the dict value of the key
     pub async fn get(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Option<TardisDictResp>> {
-        let model = tardis_db_config::Entity::find_by_id(key.to_string()).into_model::<TardisDictResp>();
-        let result = if db.has_tx() { model.one(db.raw_tx()?).await? } else { model.one(db.raw_conn()).await? };
-        Ok(result)
+        self.scope("").get(key, db).await
+    }
+
+    /// Get the JSON dict value of the key as `T` / 获取键的JSON字典值并转换为 `T`
+    pub async fn get_as<T: DeserializeOwned>(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Option<T>> {
+        self.scope("").get_as(key, db).await
     }
 
     /// Find the dict value of the key by like
+    ///
+    /// Only the keys of the root scope are matched.
     pub async fn find_like(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Vec<TardisDictResp>> {
-        let model = tardis_db_config::Entity::find().filter(tardis_db_config::Column::K.like(format!("{key}%").as_str())).into_model::<TardisDictResp>();
-        let result = if db.has_tx() { model.all(db.raw_tx()?).await? } else { model.all(db.raw_conn()).await? };
-        Ok(result)
+        self.scope("").find_like(key, db).await
     }
 
     /// Find all dict values
+    ///
+    /// Only the values of the root scope are returned.
     pub async fn find_all(&self, db: &TardisRelDBlConnection) -> TardisResult<Vec<TardisDictResp>> {
-        let model = tardis_db_config::Entity::find().into_model::<TardisDictResp>();
-        let result = if db.has_tx() { model.all(db.raw_tx()?).await? } else { model.all(db.raw_conn()).await? };
-        Ok(result)
+        self.scope("").find_all(db).await
     }
 
     /// Add a new dict value
+    pub async fn add(&self, key: &str, value: &str, creator: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.scope("").add(key, value, creator, db).await
+    }
+
+    /// Add a new JSON dict value / 添加新的JSON字典值
+    pub async fn add_as<T: Serialize>(&self, key: &str, value: &T, creator: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.scope("").add_as(key, value, creator, db).await
+    }
+
+    /// Update the dict value of the key
+    pub async fn update(&self, key: &str, value: &str, updater: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.scope("").update(key, value, updater, db).await
+    }
+
+    /// Update the JSON dict value of the key / 更新键的JSON字典值
+    pub async fn update_as<T: Serialize>(&self, key: &str, value: &T, updater: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.scope("").update_as(key, value, updater, db).await
+    }
+
+    /// Delete the dict value of the key
+    pub async fn delete(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.scope("").delete(key, db).await
+    }
+}
+
+/// Data dictionary of an `own_paths` / `own_paths` 的数据字典
+///
+/// The keys are stored with the `own_paths` as their namespace, and returned without it.
+///
+/// 键以 `own_paths` 作为命名空间存储，返回时不含命名空间.
+#[derive(Debug, Clone, Copy)]
+pub struct TardisDataDictScope<'a> {
+    own_paths: &'a str,
+}
+
+impl TardisDataDictScope<'_> {
+    fn key(&self, key: &str) -> String {
+        namespaced_key(self.own_paths, key)
+    }
+
+    /// Remove the namespace of the returned key
+    fn strip(&self, mut resp: TardisDictResp) -> TardisDictResp {
+        if let Some(key) = resp.k.strip_prefix(&self.key("")) {
+            resp.k = key.to_string();
+        }
+        resp
+    }
+
+    /// Get the dict value of the key / 获取键的字典值
+    pub async fn get(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Option<TardisDictResp>> {
+        check_key(self.own_paths, key)?;
+        let namespaced_key = self.key(key);
+        // the uncommitted values are not cached
+        if db.has_tx() {
+            let result = tardis_db_config::Entity::find_by_id(namespaced_key).into_model::<TardisDictResp>().one(db.raw_tx()?).await?;
+            return Ok(result.map(|resp| self.strip(resp)));
+        }
+        if let Some(result) = cached(db.client_id(), &namespaced_key) {
+            return Ok(result.map(|resp| self.strip(resp)));
+        }
+        // taken before reading, so a value changed in between is not cached
+        let evictions = evictions();
+        #[cfg(feature = "cache")]
+        let cache_client = cache_client();
+        #[cfg(feature = "cache")]
+        if let Some(cache_client) = &cache_client {
+            match cache_client.get(&format!("{CACHE_KEY_PREFIX}{namespaced_key}")).await {
+                Ok(Some(value)) => {
+                    if let Ok(result) = TardisFuns::json.str_to_obj::<TardisDictResp>(&value) {
+                        cache(db.client_id(), &namespaced_key, Some(result.clone()), evictions);
+                        return Ok(Some(self.strip(result)));
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => warn!("[Tardis.RelDBClient] [db_config] get cached key {} error: {}", namespaced_key, error),
+            }
+        }
+        let result = tardis_db_config::Entity::find_by_id(namespaced_key.clone()).into_model::<TardisDictResp>().one(db.raw_conn()).await?;
+        #[cfg(feature = "cache")]
+        if let (Some(cache_client), Some(result), true) = (&cache_client, &result, evictions() == evictions) {
+            let cache_key = format!("{CACHE_KEY_PREFIX}{namespaced_key}");
+            let value = TardisFuns::json.obj_to_string(result)?;
+            let ttl = dict_cache().read().expect("[Tardis.RelDBClient] Data dict cache lock poisoned").ttl.unwrap_or(DISTRIBUTED_CACHE_TTL);
+            // never overwrite a value cached by another instance, it may be fresher
+            if let Err(error) = cache_client.set_nx_ex(&cache_key, &value, ttl.as_secs().max(1)).await {
+                warn!("[Tardis.RelDBClient] [db_config] cache key {} error: {}", namespaced_key, error);
+            }
+        }
+        cache(db.client_id(), &namespaced_key, result.clone(), evictions);
+        Ok(result.map(|resp| self.strip(resp)))
+    }
+
+    /// Get the JSON dict value of the key as `T` / 获取键的JSON字典值并转换为 `T`
+    pub async fn get_as<T: DeserializeOwned>(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Option<T>> {
+        match self.get(key, db).await? {
+            Some(resp) => Ok(Some(TardisFuns::json.str_to_obj(&resp.v)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the dict values of the keys starting with `key` / 查找以 `key` 开头的键的字典值
+    pub async fn find_like(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<Vec<TardisDictResp>> {
+        check_key(self.own_paths, key)?;
+        let pattern = LikeExpr::new(format!("{}{}%", escape_like(&self.key("")), key)).escape('\\');
+        let mut select = tardis_db_config::Entity::find().filter(tardis_db_config::Column::K.like(pattern));
+        if self.own_paths.is_empty() {
+            // the keys of the other scopes and the internal keys
+            select = select
+                .filter(tardis_db_config::Column::K.not_like(LikeExpr::new(format!("%{}%", escape_like(NAMESPACE_SEPARATOR))).escape('\\')))
+                .filter(tardis_db_config::Column::K.not_like(LikeExpr::new(format!("{}%", escape_like(INTERNAL_KEY_PREFIX))).escape('\\')));
+        }
+        let model = select.into_model::<TardisDictResp>();
+        let result = if db.has_tx() { model.all(db.raw_tx()?).await? } else { model.all(db.raw_conn()).await? };
+        Ok(result.into_iter().map(|resp| self.strip(resp)).collect())
+    }
+
+    /// Find all dict values of the scope / 查找该空间的所有字典值
+    pub async fn find_all(&self, db: &TardisRelDBlConnection) -> TardisResult<Vec<TardisDictResp>> {
+        self.find_like("", db).await
+    }
+
+    /// Add a new dict value / 添加新的字典值
     pub async fn add(&self, key: &str, value: &str, creator: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
         trace!("[Tardis.RelDBClient] [db_config] add key: {}, value: {}", key, value);
+        check_key(self.own_paths, key)?;
         let model = tardis_db_config::ActiveModel {
-            k: Set(key.to_string()),
+            k: Set(self.key(key)),
             v: Set(value.to_string()),
             creator: Set(creator.to_string()),
             updater: Set(creator.to_string()),
@@ -110,14 +562,21 @@ impl TardisDataDict {
         } else {
             model.insert(db.raw_conn()).await?;
         }
+        publish(self.own_paths, key, Some(TardisDictChangeOperation::Add)).await;
         Ok(())
     }
 
-    /// Update the dict value of the key
+    /// Add a new JSON dict value / 添加新的JSON字典值
+    pub async fn add_as<T: Serialize>(&self, key: &str, value: &T, creator: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.add(key, &TardisFuns::json.obj_to_string(value)?, creator, db).await
+    }
+
+    /// Update the dict value of the key / 更新键的字典值
     pub async fn update(&self, key: &str, value: &str, updater: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
         trace!("[Tardis.RelDBClient] [db_config] update key: {}, value: {}", key, value);
+        check_key(self.own_paths, key)?;
         let model = tardis_db_config::ActiveModel {
-            k: Set(key.to_string()),
+            k: Set(self.key(key)),
             v: Set(value.to_string()),
             updater: Set(updater.to_string()),
             ..Default::default()
@@ -127,23 +586,36 @@ impl TardisDataDict {
         } else {
             model.update(db.raw_conn()).await?;
         }
+        publish(self.own_paths, key, Some(TardisDictChangeOperation::Update)).await;
         Ok(())
     }
 
-    /// Delete the dict value of the key
+    /// Update the JSON dict value of the key / 更新键的JSON字典值
+    pub async fn update_as<T: Serialize>(&self, key: &str, value: &T, updater: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
+        self.update(key, &TardisFuns::json.obj_to_string(value)?, updater, db).await
+    }
+
+    /// Delete the dict value of the key / 删除键的字典值
     pub async fn delete(&self, key: &str, db: &TardisRelDBlConnection) -> TardisResult<()> {
         trace!("[Tardis.RelDBClient] [db_config] delete key: {}", key);
-        let model = tardis_db_config::Entity::delete_many().filter(tardis_db_config::Column::K.eq(key.to_string()));
+        check_key(self.own_paths, key)?;
+        let model = tardis_db_config::Entity::delete_many().filter(tardis_db_config::Column::K.eq(self.key(key)));
         if db.has_tx() {
             model.exec(db.raw_tx()?).await?;
         } else {
             model.exec(db.raw_conn()).await?;
         }
+        publish(self.own_paths, key, Some(TardisDictChangeOperation::Delete)).await;
         Ok(())
     }
+
+    /// Evict the cached value of the key on every instance / 清除每个实例中键的缓存值
+    pub async fn invalidate(&self, key: &str) {
+        publish(self.own_paths, key, None).await;
+    }
 }
 
-#[derive(Debug, FromQueryResult)]
+#[derive(Debug, Clone, FromQueryResult, Serialize, Deserialize)]
 pub struct TardisDictResp {
     pub k: String,
     pub v: String,
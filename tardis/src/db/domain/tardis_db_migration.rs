@@ -0,0 +1,62 @@
+use chrono::Utc;
+
+use crate::basic::dto::TardisContext;
+use crate::db::reldb_client::TardisActiveModel;
+use crate::db::sea_orm::entity::prelude::*;
+use crate::db::sea_orm::sea_query::{ColumnDef, Table, TableCreateStatement};
+use crate::db::sea_orm::DbBackend;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "tardis_migration")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub version: i64,
+    pub description: String,
+    pub checksum: String,
+    pub execution_ms: i64,
+    pub create_time: chrono::DateTime<Utc>,
+}
+
+impl TardisActiveModel for ActiveModel {
+    fn fill_ctx(&mut self, _: &TardisContext, _: bool) {}
+
+    fn create_table_statement(db_type: DbBackend) -> TableCreateStatement {
+        match db_type {
+            DbBackend::MySql => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .engine("InnoDB")
+                .character_set("utf8mb4")
+                .collate("utf8mb4_0900_as_cs")
+                .col(ColumnDef::new(Column::Version).not_null().big_integer().primary_key())
+                .col(ColumnDef::new(Column::Description).not_null().string())
+                .col(ColumnDef::new(Column::Checksum).not_null().string())
+                .col(ColumnDef::new(Column::ExecutionMs).not_null().big_integer())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
+                .to_owned(),
+            DbBackend::Postgres => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .col(ColumnDef::new(Column::Version).not_null().big_integer().primary_key())
+                .col(ColumnDef::new(Column::Description).not_null().string())
+                .col(ColumnDef::new(Column::Checksum).not_null().string())
+                .col(ColumnDef::new(Column::ExecutionMs).not_null().big_integer())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp_with_time_zone())
+                .to_owned(),
+            DbBackend::Sqlite => Table::create()
+                .table(Entity.table_ref())
+                .if_not_exists()
+                .col(ColumnDef::new(Column::Version).not_null().big_integer().primary_key())
+                .col(ColumnDef::new(Column::Description).not_null().string())
+                .col(ColumnDef::new(Column::Checksum).not_null().string())
+                .col(ColumnDef::new(Column::ExecutionMs).not_null().big_integer())
+                .col(ColumnDef::new(Column::CreateTime).extra("DEFAULT CURRENT_TIMESTAMP".to_string()).timestamp())
+                .to_owned(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
@@ -0,0 +1,302 @@
+//! Audit trail of the entity changes / 实体变更的审计记录
+//!
+//! The changes of an audited table are recorded in the `tardis_audit_record` table by
+//! [`insert_one`](crate::db::reldb_client::TardisRelDBlConnection::insert_one) , [`update_one`](crate::db::reldb_client::TardisRelDBlConnection::update_one) ,
+//! [`update_many`](crate::db::reldb_client::TardisRelDBlConnection::update_many) , [`soft_delete`](crate::db::reldb_client::TardisRelDBlConnection::soft_delete)
+//! and [`soft_delete_custom`](crate::db::reldb_client::TardisRelDBlConnection::soft_delete_custom) ,
+//! each record holds the changed fields before and after the operation, the `owner` and `ak` of the `TardisContext` ,
+//! the own paths of the record if its table is tenant-scoped and the time.
+//! The records are written in the same transaction as the change, and fetched by
+//! [`find_audit_records`](crate::db::reldb_client::TardisRelDBlConnection::find_audit_records).
+//!
+//! 审计表的变更由 `insert_one` 、 `update_one` 、 `update_many` 、 `soft_delete` 及 `soft_delete_custom` 记录在 `tardis_audit_record` 表中，
+//! 每条记录包含操作前后的变更字段、 `TardisContext` 的 `owner` 及 `ak` 、记录的所属路径（如果其表是租户隔离表）以及时间.
+//! 记录与变更在同一事务中写入，通过 `find_audit_records` 查询.
+//!
+//! A table is audited when its entity is marked with `#[tardis_entity(audited)]` , or registered by [`TardisRelDBClient::register_audited_table`].
+//! `insert_one` and `update_one` follow [`TardisActiveModel::audited`] of the model, the other operations look up the table
+//! registered by the `TardisCreateEntity` derive itself or by `register_audited_table` ,
+//! and `update_many` only opens a transaction when the table to be updated is audited.
+//! `update_many` , `soft_delete` and `soft_delete_custom` take the `owner` and `ak` from
+//! [`with_ctx`](crate::db::reldb_client::TardisRelDBlConnection::with_ctx) , `soft_delete` defaults the `owner` to the delete user.
+//!
+//! 实体标记了 `#[tardis_entity(audited)]` 或通过 [`TardisRelDBClient::register_audited_table`] 注册的表为审计表.
+//! `insert_one` 及 `update_one` 依据模型的 [`TardisActiveModel::audited`] ，其它操作查找由 `TardisCreateEntity` 宏自身或 `register_audited_table` 注册的表，
+//! `update_many` 仅在要更新的表为审计表时开启事务.
+//! `update_many` 、 `soft_delete` 及 `soft_delete_custom` 从 `with_ctx` 中获取 `owner` 及 `ak` ， `soft_delete` 的 `owner` 默认为删除人.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{self, Alias, Asterisk, Expr, UpdateStatement};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DbBackend, EntityName, EntityTrait, FromQueryResult, Iterable, JsonValue, PrimaryKeyToColumn, Statement};
+use sqlparser::ast;
+use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+
+use crate::basic::dto::TardisContext;
+use crate::basic::result::TardisResult;
+use crate::db::domain::tardis_db_audit_record;
+use crate::db::reldb_client::{DeleteEntity, TardisActiveModel, TardisRelDBClient};
+use crate::db::reldb_tenant;
+use crate::TardisFuns;
+
+#[doc(hidden)]
+pub use inventory;
+
+crate::tardis_static! {
+    audited_tables: RwLock<HashMap<String, String>> = RwLock::new(
+        inventory::iter::<AuditedTable>
+            .into_iter()
+            .filter_map(|audited_table| Some(((audited_table.table_name)(), (audited_table.pk_column)()?)))
+            .collect(),
+    );
+}
+
+/// Audited table submitted by the `TardisCreateEntity` derive
+#[doc(hidden)]
+pub struct AuditedTable {
+    table_name: fn() -> String,
+    pk_column: fn() -> Option<String>,
+}
+
+impl AuditedTable {
+    pub const fn of<A: TardisActiveModel>() -> Self {
+        AuditedTable {
+            table_name: entity_table_name::<A>,
+            pk_column: entity_pk_column::<A>,
+        }
+    }
+}
+
+inventory::collect!(AuditedTable);
+
+fn entity_table_name<A: TardisActiveModel>() -> String {
+    <A as ActiveModelTrait>::Entity::default().table_name().to_string()
+}
+
+/// Primary key column of the model if it's audited
+pub(crate) fn entity_pk_column<A: TardisActiveModel>() -> Option<String> {
+    if !A::audited() {
+        return None;
+    }
+    <<A as ActiveModelTrait>::Entity as EntityTrait>::PrimaryKey::iter().next().map(|pk| pk.into_column().to_string())
+}
+
+impl TardisRelDBClient {
+    /// Register an audited table / 注册审计表
+    ///
+    /// # Arguments
+    ///
+    ///  * `table_name` - name of the table / 表名
+    ///  * `pk_column` - column of the primary key / 主键列
+    pub fn register_audited_table(table_name: &str, pk_column: &str) {
+        audited_tables().write().expect("[Tardis.RelDBClient] Audited table registry lock poisoned").insert(table_name.to_string(), pk_column.to_string());
+    }
+}
+
+/// Primary key column of the audited table
+pub(crate) fn pk_column(table_name: &str) -> Option<String> {
+    audited_tables().read().expect("[Tardis.RelDBClient] Audited table registry lock poisoned").get(table_name).cloned()
+}
+
+/// Table and primary key column to be updated by the statement if the table is audited
+pub(crate) fn audited_update_table(update_statement: &UpdateStatement, backend: DbBackend) -> TardisResult<Option<(String, String)>> {
+    if audited_tables().read().expect("[Tardis.RelDBClient] Audited table registry lock poisoned").is_empty() {
+        return Ok(None);
+    }
+    let relations = reldb_tenant::relations(&backend.build(update_statement).sql, backend)?;
+    Ok(relations.first().and_then(|(table_name, _, _)| pk_column(table_name).map(|pk_column| (table_name.clone(), pk_column))))
+}
+
+/// Operation of an audit record / 审计记录的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TardisAuditOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl TardisAuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TardisAuditOperation::Insert => "insert",
+            TardisAuditOperation::Update => "update",
+            TardisAuditOperation::Delete => "delete",
+        }
+    }
+}
+
+/// Who makes the changes
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditActor {
+    pub owner: String,
+    pub ak: String,
+}
+
+impl From<&TardisContext> for AuditActor {
+    fn from(ctx: &TardisContext) -> Self {
+        AuditActor {
+            owner: ctx.owner.clone(),
+            ak: ctx.ak.clone(),
+        }
+    }
+}
+
+/// Primary key of the row as the record id, the strings are not quoted
+pub(crate) fn record_id(row: &JsonValue, pk_column: &str) -> String {
+    match &row[pk_column] {
+        JsonValue::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+/// Keep the fields changed between the rows, the fields of a missing row are all changed
+fn diff(before: Option<&JsonValue>, after: Option<&JsonValue>) -> (Option<JsonValue>, Option<JsonValue>) {
+    match (before.and_then(JsonValue::as_object), after.and_then(JsonValue::as_object)) {
+        (Some(before), Some(after)) => {
+            let mut changed_before = serde_json::Map::new();
+            let mut changed_after = serde_json::Map::new();
+            for (field, after_value) in after {
+                let before_value = before.get(field).unwrap_or(&JsonValue::Null);
+                if before_value != after_value {
+                    changed_before.insert(field.clone(), before_value.clone());
+                    changed_after.insert(field.clone(), after_value.clone());
+                }
+            }
+            if changed_after.is_empty() {
+                (None, None)
+            } else {
+                (Some(changed_before.into()), Some(changed_after.into()))
+            }
+        }
+        _ => (before.cloned(), after.cloned()),
+    }
+}
+
+/// Time of a new record, strictly increasing within the process at the microsecond precision kept by the databases
+fn record_time() -> DateTime<Utc> {
+    static LAST_MICROS: AtomicI64 = AtomicI64::new(0);
+    let now = Utc::now().timestamp_micros();
+    let last = LAST_MICROS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1))).unwrap_or_else(|last| last);
+    DateTime::from_timestamp_micros(now.max(last + 1)).unwrap_or_else(Utc::now)
+}
+
+/// Record the change of a row, the unchanged updates are skipped
+pub(crate) async fn record<C>(
+    entity_name: &str,
+    record_id: String,
+    operation: TardisAuditOperation,
+    before: Option<&JsonValue>,
+    after: Option<&JsonValue>,
+    actor: &AuditActor,
+    db: &C,
+) -> TardisResult<()>
+where
+    C: ConnectionTrait,
+{
+    let own_paths = reldb_tenant::own_paths_column(entity_name)
+        .and_then(|own_paths_column| after.or(before).and_then(|row| row.get(&own_paths_column)).and_then(JsonValue::as_str).map(str::to_string))
+        .unwrap_or_default();
+    let (before, after) = diff(before, after);
+    if before.is_none() && after.is_none() {
+        return Ok(());
+    }
+    // without `RETURNING` , which is not supported by the Oracle compatible databases
+    tardis_db_audit_record::Entity::insert(tardis_db_audit_record::ActiveModel {
+        entity_name: Set(entity_name.to_string()),
+        record_id: Set(record_id),
+        operation: Set(operation.as_str().to_string()),
+        before: Set(before.map(|before| before.to_string())),
+        after: Set(after.map(|after| after.to_string())),
+        owner: Set(actor.owner.clone()),
+        ak: Set(actor.ak.clone()),
+        own_paths: Set(own_paths),
+        create_time: Set(record_time()),
+        ..Default::default()
+    })
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+/// Record the soft deleted rows of the audited tables
+pub(crate) async fn record_deleted<C>(delete_entities: &[DeleteEntity], actor: &AuditActor, db: &C) -> TardisResult<()>
+where
+    C: ConnectionTrait,
+{
+    for delete_entity in delete_entities {
+        let Some(pk_column) = pk_column(&delete_entity.entity_name) else {
+            continue;
+        };
+        let before = TardisFuns::json.str_to_obj::<JsonValue>(&delete_entity.content)?;
+        record(
+            &delete_entity.entity_name,
+            record_id(&before, &pk_column),
+            TardisAuditOperation::Delete,
+            Some(&before),
+            None,
+            actor,
+            db,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Rows to be updated by the statement
+pub(crate) async fn rows_to_update<C>(update_statement: &UpdateStatement, db: &C) -> TardisResult<Vec<JsonValue>>
+where
+    C: ConnectionTrait,
+{
+    let backend = db.get_database_backend();
+    // the values are inlined to reuse the where clause in the select statement
+    let (sql, dialect): (_, &dyn sqlparser::dialect::Dialect) = match backend {
+        DbBackend::MySql => (update_statement.to_string(sea_query::MysqlQueryBuilder), &MySqlDialect {}),
+        DbBackend::Postgres => (update_statement.to_string(sea_query::PostgresQueryBuilder), &PostgreSqlDialect {}),
+        DbBackend::Sqlite => (update_statement.to_string(sea_query::SqliteQueryBuilder), &SQLiteDialect {}),
+    };
+    let Some(ast::Statement::Update { table, selection, .. }) = Parser::parse_sql(dialect, &sql)?.pop() else {
+        return Ok(Vec::new());
+    };
+    let select_sql = match selection {
+        Some(selection) => format!("SELECT * FROM {} WHERE {selection}", table.relation),
+        None => format!("SELECT * FROM {}", table.relation),
+    };
+    Ok(JsonValue::find_by_statement(Statement::from_string(backend, select_sql)).all(db).await?)
+}
+
+/// Rows of the table by the primary keys
+pub(crate) async fn rows_by_ids<C>(table_name: &str, pk_column: &str, ids: Vec<JsonValue>, db: &C) -> TardisResult<Vec<JsonValue>>
+where
+    C: ConnectionTrait,
+{
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids = ids
+        .into_iter()
+        .map(|id| match id {
+            JsonValue::String(id) => sea_query::Value::from(id),
+            JsonValue::Number(id) => match (id.as_i64(), id.as_u64()) {
+                (Some(id), _) => id.into(),
+                (None, Some(id)) => id.into(),
+                (None, None) => id.as_f64().unwrap_or_default().into(),
+            },
+            id => id.to_string().into(),
+        })
+        .collect::<Vec<_>>();
+    let mut select = sea_query::Query::select();
+    select.column(Asterisk).from(Alias::new(table_name)).and_where(Expr::col(Alias::new(pk_column)).is_in(ids));
+    Ok(JsonValue::find_by_statement(db.get_database_backend().build(&select)).all(db).await?)
+}
+
+/// Whether the table of the entity is audited
+pub(crate) fn is_audited_entity<E>() -> bool
+where
+    E: EntityTrait,
+{
+    pk_column(E::default().table_name()).is_some()
+}
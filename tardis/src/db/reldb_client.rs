@@ -1,10 +1,17 @@
+use std::borrow::Cow;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use rand::Rng;
 use sea_orm::sea_query::TableCreateStatement;
-use sea_orm::sea_query::{IndexCreateStatement, SelectStatement, UpdateStatement};
+use sea_orm::sea_query::{Expr, IndexCreateStatement, LikeExpr, SelectStatement, UpdateStatement};
+use sea_orm::sea_query::{FromValueTuple, IntoValueTuple};
 use sea_orm::ActiveValue::Set;
 use sea_orm::*;
 use sqlparser::ast;
@@ -12,15 +19,21 @@ use sqlparser::ast::{SetExpr, TableFactor};
 use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
 use sqlparser::parser::{Parser, ParserError};
 use sqlx::Executor;
-use tracing::{error, info, instrument, trace};
+use tracing::{error, field, info, instrument, trace, warn};
+use typed_builder::TypedBuilder;
 use url::Url;
 
 use crate::basic::dto::TardisContext;
 use crate::basic::error::TardisError;
+use crate::basic::health::HealthCheck;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::db::CompatibleType;
-use crate::config::config_dto::component::db::DBModuleConfig;
-use crate::db::domain::{tardis_db_config, tardis_db_del_record};
+use crate::config::config_dto::component::db::{DBModuleConfig, DBReplicaPolicy};
+use crate::db::domain::{tardis_db_audit_record, tardis_db_config, tardis_db_del_record, tardis_db_migration};
+use crate::db::reldb_audit::{self, AuditActor, TardisAuditOperation};
+use crate::db::reldb_dialect::TardisRelDBDialect;
+use crate::db::reldb_tenant;
+use crate::db::reldb_trace;
 use crate::serde::{Deserialize, Serialize};
 use crate::utils::initializer::InitBy;
 use crate::TardisFuns;
@@ -127,8 +140,24 @@ use crate::TardisFuns;
 ///     1,10
 /// ).await.unwrap();
 /// ```
+///
+/// # Read replicas / 只读副本
+///
+/// When [`DBModuleConfig::replica_urls`] is set, the multi-row queries outside a transaction
+/// ( `find_dtos` , `paginate_dtos` , `paginate_dtos_by_keyset` , `stream_dtos` , `count` , `query_all` and their `_by_sql` variants) go to the replicas,
+/// use [`TardisRelDBlConnection::primary`] to read from the primary database.
+/// The single-row queries ( `get_dto` , `get_dto_by_sql` and `query_one` ), usually following a write, and the reads of the bookkeeping records
+/// ( `find_audit_records` and `paginate_soft_deleted` ) always go to the primary database.
+///
+/// 设置 [`DBModuleConfig::replica_urls`] 后，事务外的多行查询（ `find_dtos` 、 `paginate_dtos` 、 `paginate_dtos_by_keyset` 、 `stream_dtos` 、 `count` 、
+/// `query_all` 及其 `_by_sql` 版本）使用只读副本，使用 [`TardisRelDBlConnection::primary`] 从主库读取.
+/// 通常紧随写入的单行查询（ `get_dto` 、 `get_dto_by_sql` 及 `query_one` ）及记录类数据的查询（ `find_audit_records` 及 `paginate_soft_deleted` ）总是使用主库.
 pub struct TardisRelDBClient {
+    id: usize,
     con: Arc<DatabaseConnection>,
+    replicas: Vec<Arc<DatabaseConnection>>,
+    replica_policy: DBReplicaPolicy,
+    replica_cursor: AtomicUsize,
     compatible_type: CompatibleType,
 }
 
@@ -139,18 +168,50 @@ impl InitBy<DBModuleConfig> for TardisRelDBClient {
     }
 }
 
+#[async_trait::async_trait]
+impl HealthCheck for TardisRelDBClient {
+    /// Ping the database / 检查数据库连接
+    async fn health_check(&self) -> TardisResult<()> {
+        self.con.ping().await?;
+        for replica in &self.replicas {
+            replica.ping().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Process unique id of the next client / 下一个客户端的进程内唯一标识
+static NEXT_CLIENT_ID: AtomicUsize = AtomicUsize::new(0);
+
 impl TardisRelDBClient {
     /// Initialize configuration / 初始化配置
-    pub async fn init(
+    pub async fn init(config: &DBModuleConfig) -> TardisResult<TardisRelDBClient> {
+        let con = Self::connect(&config.url, config).await?;
+        let mut replicas = Vec::with_capacity(config.replica_urls.len());
+        for replica_url in &config.replica_urls {
+            replicas.push(Arc::new(Self::connect(replica_url, config).await?));
+        }
+        Ok(TardisRelDBClient {
+            id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+            con: Arc::new(con),
+            replicas,
+            replica_policy: config.replica_policy,
+            replica_cursor: AtomicUsize::new(0),
+            compatible_type: config.compatible_type,
+        })
+    }
+
+    async fn connect(
+        str_url: &str,
         DBModuleConfig {
-            url: str_url,
             max_connections,
             min_connections,
             connect_timeout_sec,
             idle_timeout_sec,
-            compatible_type,
+            slow_query,
+            ..
         }: &DBModuleConfig,
-    ) -> TardisResult<TardisRelDBClient> {
+    ) -> TardisResult<DatabaseConnection> {
         use crate::utils::redact::Redact;
         let url = Url::parse(str_url).map_err(|_| TardisError::format_error(&format!("[Tardis.RelDBClient] Invalid url {str_url}"), "406-tardis-reldb-url-error"))?;
         info!(
@@ -167,7 +228,7 @@ impl TardisRelDBClient {
         if let Some(idle_timeout_sec) = idle_timeout_sec {
             opt.idle_timeout(Duration::from_secs(*idle_timeout_sec));
         }
-        let con = if let Some(timezone) = url.query_pairs().find(|x| x.0.to_lowercase() == "timezone").map(|x| x.1.to_string()) {
+        let mut con = if let Some(timezone) = url.query_pairs().find(|x| x.0.to_lowercase() == "timezone").map(|x| x.1.to_string()) {
             match url.scheme().to_lowercase().as_str() {
                 #[cfg(feature = "reldb-mysql")]
                 "mysql" => {
@@ -238,16 +299,14 @@ impl TardisRelDBClient {
                 )
             })
         }?;
+        reldb_trace::trace(&mut con, slow_query);
         info!(
             "[Tardis.RelDBClient] Initialized, host:{}, port:{}, max_connections:{}",
             url.host_str().unwrap_or(""),
             url.port().unwrap_or(0),
             min_connections
         );
-        Ok(TardisRelDBClient {
-            con: Arc::new(con),
-            compatible_type: *compatible_type,
-        })
+        Ok(con)
     }
 
     /// Get database instance implementation / 获取数据库实例的实现
@@ -261,11 +320,52 @@ impl TardisRelDBClient {
         self.compatible_type
     }
 
+    /// Get the size and the idle connections of the connection pool / 获取连接池的大小及空闲连接数
+    pub fn pool_status(&self) -> Option<(u32, usize)> {
+        match self.con.as_ref() {
+            #[cfg(feature = "reldb-postgres")]
+            DatabaseConnection::SqlxPostgresPoolConnection(_) => {
+                let pool = self.con.get_postgres_connection_pool();
+                Some((pool.size(), pool.num_idle()))
+            }
+            #[cfg(feature = "reldb-mysql")]
+            DatabaseConnection::SqlxMySqlPoolConnection(_) => {
+                let pool = self.con.get_mysql_connection_pool();
+                Some((pool.size(), pool.num_idle()))
+            }
+            #[cfg(feature = "reldb-sqlite")]
+            DatabaseConnection::SqlxSqlitePoolConnection(_) => {
+                let pool = self.con.get_sqlite_connection_pool();
+                Some((pool.size(), pool.num_idle()))
+            }
+            _ => None,
+        }
+    }
+
     /// Get database connection
     ///
     /// 获取数据库操作连接
     pub fn conn(&self) -> TardisRelDBlConnection {
-        TardisRelDBlConnection { conn: self.con.clone(), tx: None }
+        TardisRelDBlConnection {
+            client_id: self.id,
+            conn: self.con.clone(),
+            replica: self.pick_replica(),
+            compatible_type: self.compatible_type,
+            own_paths: None,
+            actor: None,
+            tx: None,
+        }
+    }
+
+    fn pick_replica(&self) -> Option<Arc<DatabaseConnection>> {
+        if self.replicas.is_empty() {
+            return None;
+        }
+        let index = match self.replica_policy {
+            DBReplicaPolicy::RoundRobin => self.replica_cursor.fetch_add(1, Ordering::Relaxed),
+            DBReplicaPolicy::Random => rand::random::<usize>(),
+        } % self.replicas.len();
+        Some(self.replicas[index].clone())
     }
 
     /// Initialize basic tables / 初始化基础表
@@ -281,6 +381,12 @@ impl TardisRelDBClient {
         let create_all = tardis_db_del_record::ActiveModel::init(self.con.get_database_backend(), None, self.compatible_type);
         TardisRelDBClient::create_table_inner(&create_all.0, &tx).await?;
         TardisRelDBClient::create_index_inner(&create_all.1, &tx).await?;
+        let create_all = tardis_db_migration::ActiveModel::init(self.con.get_database_backend(), None, self.compatible_type);
+        TardisRelDBClient::create_table_inner(&create_all.0, &tx).await?;
+        TardisRelDBClient::create_index_inner(&create_all.1, &tx).await?;
+        let create_all = tardis_db_audit_record::ActiveModel::init(self.con.get_database_backend(), None, self.compatible_type);
+        TardisRelDBClient::create_table_inner(&create_all.0, &tx).await?;
+        TardisRelDBClient::create_index_inner(&create_all.1, &tx).await?;
         tx.commit().await?;
         Ok(())
     }
@@ -363,7 +469,10 @@ impl TardisRelDBClient {
         let query_stmt = Statement::from_sql_and_values(db.get_database_backend(), sql, params);
         let result = db.query_one(query_stmt).await;
         match result {
-            Ok(ok) => TardisResult::Ok(ok),
+            Ok(ok) => {
+                reldb_trace::record_returned_rows(usize::from(ok.is_some()));
+                TardisResult::Ok(ok)
+            }
             Err(error) => TardisResult::Err(TardisError::from(error)),
         }
     }
@@ -376,7 +485,10 @@ impl TardisRelDBClient {
         let query_stmt = Statement::from_sql_and_values(db.get_database_backend(), sql, params);
         let result = db.query_all(query_stmt).await;
         match result {
-            Ok(ok) => TardisResult::Ok(ok),
+            Ok(ok) => {
+                reldb_trace::record_returned_rows(ok.len());
+                TardisResult::Ok(ok)
+            }
             Err(error) => TardisResult::Err(TardisError::from(error)),
         }
     }
@@ -404,7 +516,10 @@ impl TardisRelDBClient {
     {
         let result = D::find_by_statement(select_statement).one(db).await;
         match result {
-            Ok(r) => TardisResult::Ok(r),
+            Ok(r) => {
+                reldb_trace::record_returned_rows(usize::from(r.is_some()));
+                TardisResult::Ok(r)
+            }
             Err(error) => TardisResult::Err(TardisError::from(error)),
         }
     }
@@ -432,71 +547,75 @@ impl TardisRelDBClient {
     {
         let result = D::find_by_statement(select_statement).all(db).await;
         match result {
-            Ok(r) => TardisResult::Ok(r),
+            Ok(r) => {
+                reldb_trace::record_returned_rows(r.len());
+                TardisResult::Ok(r)
+            }
             Err(error) => TardisResult::Err(TardisError::from(error)),
         }
     }
 
-    pub(self) async fn paginate_dtos_inner<C, D>(select_statement: &SelectStatement, page_number: u64, page_size: u64, db: &C) -> TardisResult<(Vec<D>, u64)>
+    pub(self) async fn paginate_dtos_inner<C, D>(
+        select_statement: &SelectStatement,
+        page_number: u64,
+        page_size: u64,
+        dialect: TardisRelDBDialect,
+        db: &C,
+    ) -> TardisResult<(Vec<D>, u64)>
     where
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        Self::do_paginate_dtos_inner(db.get_database_backend().build(select_statement), page_number, page_size, db).await
+        Self::do_paginate_dtos_inner(db.get_database_backend().build(select_statement), page_number, page_size, dialect, db).await
     }
 
-    pub(self) async fn paginate_dtos_by_sql_inner<C, D>(sql: &str, params: Vec<Value>, page_number: u64, page_size: u64, db: &C) -> TardisResult<(Vec<D>, u64)>
+    pub(self) async fn paginate_dtos_by_sql_inner<C, D>(
+        sql: &str,
+        params: Vec<Value>,
+        page_number: u64,
+        page_size: u64,
+        dialect: TardisRelDBDialect,
+        db: &C,
+    ) -> TardisResult<(Vec<D>, u64)>
     where
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        Self::do_paginate_dtos_inner(Statement::from_sql_and_values(db.get_database_backend(), sql, params), page_number, page_size, db).await
+        Self::do_paginate_dtos_inner(Statement::from_sql_and_values(db.get_database_backend(), sql, params), page_number, page_size, dialect, db).await
     }
 
-    async fn do_paginate_dtos_inner<C, D>(select_statement: Statement, page_number: u64, page_size: u64, db: &C) -> TardisResult<(Vec<D>, u64)>
+    async fn do_paginate_dtos_inner<C, D>(select_statement: Statement, page_number: u64, page_size: u64, dialect: TardisRelDBDialect, db: &C) -> TardisResult<(Vec<D>, u64)>
     where
         C: ConnectionTrait,
         D: FromQueryResult,
     {
-        let select_sql = format!("{} LIMIT {} OFFSET {}", select_statement.sql, page_size, (page_number - 1) * page_size);
-        let query_statement = Statement {
-            sql: select_sql,
-            values: select_statement.values.clone(),
-            db_backend: select_statement.db_backend,
-        };
+        let query_statement = dialect.paginate(select_statement.clone(), page_number, page_size);
         let query_result = D::find_by_statement(query_statement).all(db).await?;
-        let count_result = TardisRelDBClient::do_count_inner(select_statement, db).await?;
+        let count_result = TardisRelDBClient::do_count_inner(select_statement, dialect, db).await?;
+        reldb_trace::record_returned_rows(query_result.len());
         Ok((query_result, count_result))
     }
 
-    pub(self) async fn count_inner<C>(select_statement: &SelectStatement, db: &C) -> TardisResult<u64>
+    pub(self) async fn count_inner<C>(select_statement: &SelectStatement, dialect: TardisRelDBDialect, db: &C) -> TardisResult<u64>
     where
         C: ConnectionTrait,
     {
-        Self::do_count_inner(db.get_database_backend().build(select_statement), db).await
+        Self::do_count_inner(db.get_database_backend().build(select_statement), dialect, db).await
     }
 
-    pub(self) async fn count_by_sql_inner<C>(sql: &str, params: Vec<Value>, db: &C) -> TardisResult<u64>
+    pub(self) async fn count_by_sql_inner<C>(sql: &str, params: Vec<Value>, dialect: TardisRelDBDialect, db: &C) -> TardisResult<u64>
     where
         C: ConnectionTrait,
     {
-        Self::do_count_inner(Statement::from_sql_and_values(db.get_database_backend(), sql, params), db).await
+        Self::do_count_inner(Statement::from_sql_and_values(db.get_database_backend(), sql, params), dialect, db).await
     }
 
-    async fn do_count_inner<C>(select_statement: Statement, db: &C) -> TardisResult<u64>
+    async fn do_count_inner<C>(select_statement: Statement, dialect: TardisRelDBDialect, db: &C) -> TardisResult<u64>
     where
         C: ConnectionTrait,
     {
-        let count_sql = format!(
-            "SELECT COUNT(1) AS count FROM ( {} ) _{}",
-            select_statement.sql,
-            SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
-        );
-        let count_statement = Statement {
-            sql: count_sql.clone(),
-            values: select_statement.values,
-            db_backend: select_statement.db_backend,
-        };
+        let count_statement = dialect.count(select_statement);
+        let count_sql = count_statement.sql.clone();
         let count_result = CountResp::find_by_statement(count_statement).one(db).await?;
         match count_result {
             Some(r) => TardisResult::Ok(r.count as u64),
@@ -515,7 +634,26 @@ impl TardisRelDBClient {
         trace!("[Tardis.RelDBClient] Inserting one model");
         model.fill_ctx(ctx, true);
         let result = EntityTrait::insert(model).exec(db).await?;
-        Ok(result)
+        let Some(pk_column) = reldb_audit::entity_pk_column::<T>() else {
+            return Ok(result);
+        };
+        let last_insert_id = result.last_insert_id.into_value_tuple();
+        let after = T::Entity::find_by_id(PrimaryKeyValue::<T>::from_value_tuple(last_insert_id.clone())).into_json().one(db).await?;
+        if let Some(after) = after {
+            reldb_audit::record(
+                T::Entity::default().table_name(),
+                reldb_audit::record_id(&after, &pk_column),
+                TardisAuditOperation::Insert,
+                None,
+                Some(&after),
+                &ctx.into(),
+                db,
+            )
+            .await?;
+        }
+        Ok(InsertResult {
+            last_insert_id: PrimaryKeyValue::<T>::from_value_tuple(last_insert_id),
+        })
     }
 
     pub(self) async fn insert_many_inner<T, C>(mut models: Vec<T>, db: &C, ctx: &TardisContext) -> TardisResult<()>
@@ -529,49 +667,274 @@ impl TardisRelDBClient {
         Ok(())
     }
 
-    pub(self) async fn update_one_inner<T, C>(mut model: T, db: &C, ctx: &TardisContext) -> TardisResult<()>
+    pub(self) async fn upsert_many_inner<T, C>(mut models: Vec<T>, dialect: TardisRelDBDialect, db: &C, ctx: &TardisContext) -> TardisResult<TardisUpsertResult<PrimaryKeyValue<T>>>
+    where
+        C: ConnectionTrait,
+        T: TardisActiveModel,
+    {
+        trace!("[Tardis.RelDBClient] Upserting many models");
+        let mut result = TardisUpsertResult {
+            rows_affected: 0,
+            ids: dialect.support_returning().then(Vec::new),
+        };
+        let Some(first) = models.first() else {
+            return Ok(result);
+        };
+        let is_pk = |column: &<T::Entity as EntityTrait>::Column| <<T::Entity as EntityTrait>::PrimaryKey as PrimaryKeyToColumn>::from_column(*column).is_some();
+        let pk_columns = <T::Entity as EntityTrait>::PrimaryKey::iter().map(|pk| pk.into_column()).collect::<Vec<_>>();
+        // the columns filled on update and set by the caller are updated on conflict, the insert only ones are kept
+        let mut update_model = first.clone();
+        update_model.fill_ctx(ctx, false);
+        let mut update_columns = <T::Entity as EntityTrait>::Column::iter().filter(|column| !is_pk(column) && !update_model.get(*column).is_not_set()).collect::<Vec<_>>();
+        if update_columns.is_empty() {
+            update_columns.clone_from(&pk_columns);
+        }
+
+        models.iter_mut().for_each(|m| m.fill_ctx(ctx, true));
+        let set_columns = |model: &T| <T::Entity as EntityTrait>::Column::iter().map(|column| !model.get(column).is_not_set()).collect::<Vec<_>>();
+        let first_set_columns = set_columns(&models[0]);
+        if models.iter().any(|model| set_columns(model) != first_set_columns) {
+            return Err(TardisError::bad_request(
+                "[Tardis.RelDBClient] The models to be upserted must set the same columns",
+                "400-tardis-reldb-upsert-columns-mismatch",
+            ));
+        }
+        let max_params = match db.get_database_backend() {
+            DatabaseBackend::Sqlite => UPSERT_MAX_PARAMS_SQLITE,
+            _ => UPSERT_MAX_PARAMS,
+        };
+        let chunk_size = (max_params / first_set_columns.iter().filter(|set| **set).count().max(1)).max(1);
+        let pk_names = pk_columns.iter().map(|column| column.to_string()).collect::<Vec<_>>();
+        while !models.is_empty() {
+            let chunk = models.drain(..chunk_size.min(models.len())).collect::<Vec<_>>();
+            let upsert = dialect.upsert(chunk, &pk_columns, &update_columns, result.ids.is_some())?;
+            if let Some(ids) = &mut result.ids {
+                let rows = db.query_all(upsert).await?;
+                result.rows_affected += rows.len() as u64;
+                for row in rows {
+                    ids.push(row.try_get_many("", &pk_names)?);
+                }
+            } else {
+                result.rows_affected += TardisRelDBClient::execute_inner(upsert, db).await?.rows_affected();
+            }
+        }
+        Ok(result)
+    }
+
+    pub(self) async fn update_one_inner<T, C>(mut model: T, condition: Condition, db: &C, ctx: &TardisContext) -> TardisResult<u64>
     where
         C: ConnectionTrait,
         T: TardisActiveModel,
     {
         trace!("[Tardis.RelDBClient] Updating one model");
         model.fill_ctx(ctx, false);
-        let update = EntityTrait::update(model);
-        TardisRelDBClient::execute_inner(db.get_database_backend().build(update.as_query()), db).await?;
-        Ok(())
+        let audit = match (reldb_audit::entity_pk_column::<T>(), pk_select(&model)) {
+            (Some(pk_column), Some(select)) => {
+                let before = select.clone().into_json().one(db).await?;
+                Some((pk_column, select, before))
+            }
+            _ => None,
+        };
+        let version = match T::version_column() {
+            Some(version_column) => match model.get(version_column) {
+                ActiveValue::Set(version) | ActiveValue::Unchanged(version) => {
+                    model.not_set(version_column);
+                    Some((version_column, version))
+                }
+                ActiveValue::NotSet => {
+                    return Err(TardisError::bad_request(
+                        &format!("[Tardis.RelDBClient] The version [{}] is required for optimistic locking", version_column.to_string()),
+                        "400-tardis-reldb-version-not-set",
+                    ))
+                }
+            },
+            None => None,
+        };
+        let mut update = EntityTrait::update(model).filter(condition);
+        let optimistic_locking = version.is_some();
+        if let Some((version_column, version)) = version {
+            update = update.filter(version_column.eq(version));
+            QueryTrait::query(&mut update).value(version_column, Expr::col(version_column).add(1));
+        }
+        let result = TardisRelDBClient::execute_inner(db.get_database_backend().build(update.as_query()), db).await?;
+        if optimistic_locking && result.rows_affected() == 0 {
+            return Err(TardisError::conflict(
+                "[Tardis.RelDBClient] The record has been modified or deleted by others",
+                "409-tardis-reldb-conflict",
+            ));
+        }
+        if let Some((pk_column, select, Some(before))) = audit {
+            let after = select.into_json().one(db).await?;
+            reldb_audit::record(
+                T::Entity::default().table_name(),
+                reldb_audit::record_id(&before, &pk_column),
+                TardisAuditOperation::Update,
+                Some(&before),
+                after.as_ref(),
+                &ctx.into(),
+                db,
+            )
+            .await?;
+        }
+        Ok(result.rows_affected())
     }
 
-    pub(self) async fn update_many_inner<C>(update_statement: &UpdateStatement, db: &C) -> TardisResult<()>
+    pub(self) async fn update_many_inner<C>(update_statement: &UpdateStatement, audited_table: Option<(String, String)>, actor: &AuditActor, db: &C) -> TardisResult<()>
     where
         C: ConnectionTrait,
     {
         trace!("[Tardis.RelDBClient] Updating many models");
+        let audit = match audited_table {
+            Some((table_name, pk_column)) => Some((table_name, pk_column, reldb_audit::rows_to_update(update_statement, db).await?)),
+            None => None,
+        };
         TardisRelDBClient::execute_inner(db.get_database_backend().build(update_statement), db).await?;
+        if let Some((table_name, pk_column, before_rows)) = audit {
+            let ids = before_rows.iter().map(|row| row[&pk_column].clone()).collect();
+            let after_rows = reldb_audit::rows_by_ids(&table_name, &pk_column, ids, db).await?;
+            for before in &before_rows {
+                let after = after_rows.iter().find(|after| after[&pk_column] == before[&pk_column]);
+                reldb_audit::record(
+                    &table_name,
+                    reldb_audit::record_id(before, &pk_column),
+                    TardisAuditOperation::Update,
+                    Some(before),
+                    after,
+                    actor,
+                    db,
+                )
+                .await?;
+            }
+        }
         Ok(())
     }
 
-    pub(self) async fn soft_delete_inner<E, C>(select: Select<E>, delete_user: &str, db: &C) -> TardisResult<u64>
+    pub(self) async fn hard_delete_inner<E, C>(condition: Condition, actor: &AuditActor, db: &C) -> TardisResult<u64>
     where
         C: ConnectionTrait,
         E: EntityTrait,
     {
-        trace!("[Tardis.RelDBClient] Soft deleting");
-        select.soft_delete(delete_user, db).await
+        trace!("[Tardis.RelDBClient] Hard deleting");
+        let entity = E::default();
+        let table_name = entity.table_name();
+        let audit = match reldb_audit::pk_column(table_name) {
+            Some(pk_column) => Some((pk_column, E::find().filter(condition.clone()).into_json().all(db).await?)),
+            None => None,
+        };
+        let result = TardisRelDBClient::execute_inner(db.get_database_backend().build(E::delete_many().filter(condition).as_query()), db).await?;
+        if let Some((pk_column, before_rows)) = audit {
+            for before in &before_rows {
+                reldb_audit::record(
+                    table_name,
+                    reldb_audit::record_id(before, &pk_column),
+                    TardisAuditOperation::Delete,
+                    Some(before),
+                    None,
+                    actor,
+                    db,
+                )
+                .await?;
+            }
+        }
+        Ok(result.rows_affected())
     }
 
-    pub(self) async fn soft_delete_custom_inner<E, C>(select: Select<E>, custom_pk_field: &str, db: &C) -> TardisResult<Vec<DeleteEntity>>
+    pub(self) async fn soft_delete_inner<E, C>(select: Select<E>, delete_user: &str, actor: &AuditActor, dialect: TardisRelDBDialect, db: &C) -> TardisResult<u64>
+    where
+        C: ConnectionTrait,
+        E: EntityTrait,
+    {
+        trace!("[Tardis.RelDBClient] Soft deleting");
+        let delete_entities = do_soft_delete(select, "id", delete_user, dialect, db).await?;
+        reldb_audit::record_deleted(&delete_entities, actor, db).await?;
+        Ok(delete_entities.len() as u64)
+    }
+
+    pub(self) async fn soft_delete_custom_inner<E, C>(
+        select: Select<E>,
+        custom_pk_field: &str,
+        actor: &AuditActor,
+        dialect: TardisRelDBDialect,
+        db: &C,
+    ) -> TardisResult<Vec<DeleteEntity>>
     where
         C: ConnectionTrait,
         E: EntityTrait,
     {
         trace!("[Tardis.RelDBClient] Soft deleting custom");
-        select.soft_delete_custom(custom_pk_field, db).await
+        let delete_entities = do_soft_delete_custom(select, custom_pk_field, dialect, db).await?;
+        reldb_audit::record_deleted(&delete_entities, actor, db).await?;
+        Ok(delete_entities)
+    }
+
+    pub(self) async fn restore_soft_deleted_inner<C>(entity_name: &str, record_id: &str, own_paths: Option<&str>, db: &C) -> TardisResult<()>
+    where
+        C: ConnectionTrait,
+    {
+        trace!("[Tardis.RelDBClient] Restoring soft deleted record [{}] of [{}]", record_id, entity_name);
+        // the string ids are stored as json strings, e.g. `"xxx"`
+        let del_record = tardis_db_del_record::Entity::find()
+            .filter(tardis_db_del_record::Column::EntityName.eq(entity_name))
+            .filter(tardis_db_del_record::Column::RecordId.is_in([record_id.to_string(), TardisFuns::json.obj_to_string(&record_id)?]))
+            .order_by_desc(tardis_db_del_record::Column::CreateTime)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                TardisError::not_found(
+                    &format!("[Tardis.RelDBClient] The soft deleted record [{record_id}] of [{entity_name}] does not exist"),
+                    "404-tardis-reldb-del-record-not-exist",
+                )
+            })?;
+        let content = TardisFuns::json.str_to_obj::<serde_json::Map<String, JsonValue>>(&del_record.content)?;
+        if let Some(own_paths) = own_paths {
+            if let Some(own_paths_column) = reldb_tenant::own_paths_column(entity_name) {
+                if !content.get(&own_paths_column).and_then(JsonValue::as_str).is_some_and(|record_own_paths| record_own_paths.starts_with(own_paths)) {
+                    return Err(TardisError::not_found(
+                        &format!("[Tardis.RelDBClient] The soft deleted record [{record_id}] of [{entity_name}] does not exist"),
+                        "404-tardis-reldb-del-record-not-exist",
+                    ));
+                }
+            }
+        }
+        let mut columns = Vec::with_capacity(content.len());
+        let mut values = Vec::with_capacity(content.len());
+        for (column, value) in content {
+            columns.push(sea_query::Alias::new(column));
+            values.push(match value {
+                JsonValue::Null => Expr::cust("NULL"),
+                JsonValue::Bool(value) => Expr::val(value).into(),
+                JsonValue::Number(value) => match (value.as_i64(), value.as_u64()) {
+                    (Some(value), _) => Expr::val(value).into(),
+                    (None, Some(value)) => Expr::val(value).into(),
+                    (None, None) => Expr::val(value.as_f64().unwrap_or_default()).into(),
+                },
+                JsonValue::String(value) => Expr::val(value).into(),
+                value => Expr::val(value.to_string()).into(),
+            });
+        }
+        let mut insert = sea_query::Query::insert();
+        insert.into_table(sea_query::Alias::new(entity_name)).columns(columns).values_panic(values);
+        // the column types are unknown, so the values are inlined as literals to be converted by the database
+        let db_backend = db.get_database_backend();
+        let sql = match db_backend {
+            DatabaseBackend::MySql => insert.to_string(sea_query::MysqlQueryBuilder),
+            DatabaseBackend::Postgres => insert.to_string(sea_query::PostgresQueryBuilder),
+            DatabaseBackend::Sqlite => insert.to_string(sea_query::SqliteQueryBuilder),
+        };
+        TardisRelDBClient::execute_inner(Statement::from_string(db_backend, sql), db).await?;
+        tardis_db_del_record::Entity::delete_by_id(del_record.id).exec(db).await?;
+        Ok(())
     }
 }
 
 /// Database operation connection object / 数据库操作连接对象
 pub struct TardisRelDBlConnection {
+    client_id: usize,
     conn: Arc<DatabaseConnection>,
+    replica: Option<Arc<DatabaseConnection>>,
+    compatible_type: CompatibleType,
+    own_paths: Option<String>,
+    actor: Option<AuditActor>,
     tx: Option<DatabaseTransaction>,
 }
 
@@ -605,10 +968,118 @@ impl TardisRelDBlConnection {
         }
     }
 
+    /// Get the process unique id of the client this connection belongs to / 获取此连接所属客户端的进程内唯一标识
+    pub(crate) fn client_id(&self) -> usize {
+        self.client_id
+    }
+
     pub fn has_tx(&self) -> bool {
         self.tx.is_some()
     }
 
+    /// Use the primary database for the queries / 查询使用主库
+    ///
+    /// The queries outside a transaction go to the read replicas if configured, use it to read your own writes.
+    ///
+    /// 如果配置了只读副本，事务外的查询会使用只读副本，使用该方法以读取刚写入的数据.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn().primary();
+    /// ```
+    pub fn primary(mut self) -> Self {
+        self.replica = None;
+        self
+    }
+
+    /// Whether the queries outside a transaction go to a read replica / 事务外的查询是否使用只读副本
+    pub fn is_replica(&self) -> bool {
+        self.replica.is_some()
+    }
+
+    /// SQL dialect of the database / 数据库的SQL方言
+    ///
+    /// See [`reldb_dialect`](crate::db::reldb_dialect).
+    pub fn dialect(&self) -> TardisRelDBDialect {
+        TardisRelDBDialect::new(self.conn.get_database_backend(), self.compatible_type)
+    }
+
+    /// Connection of the read-only queries outside a transaction
+    fn read_conn(&self) -> &DatabaseConnection {
+        self.replica.as_deref().unwrap_or(self.conn.as_ref())
+    }
+
+    /// Isolate the rows of the tenant-scoped tables by the own paths of the context / 按上下文的所属路径隔离租户隔离表的行
+    ///
+    /// `get_dto` , `find_dtos` , `paginate_dtos` , `paginate_dtos_by_keyset` , `stream_dtos` , `count` , `update_one` , `update_one_with_condition` , `update_many` , `hard_delete` , `soft_delete` and `soft_delete_custom`
+    /// only operate the rows whose own paths start with `ctx.own_paths` , see [`reldb_tenant`](crate::db::reldb_tenant).
+    /// The `owner` and `ak` of the context are recorded in the audit records of `update_many` , `soft_delete` and `soft_delete_custom` ,
+    /// see [`reldb_audit`](crate::db::reldb_audit).
+    ///
+    /// `get_dto` 、 `find_dtos` 、 `paginate_dtos` 、 `paginate_dtos_by_keyset` 、 `stream_dtos` 、 `count` 、 `update_one` 、 `update_one_with_condition` 、 `update_many` 、 `hard_delete` 、 `soft_delete` 及 `soft_delete_custom`
+    /// 只操作所属路径以 `ctx.own_paths` 开头的行，见 [`reldb_tenant`](crate::db::reldb_tenant).
+    /// 上下文的 `owner` 及 `ak` 会记录在 `update_many` 、 `soft_delete` 及 `soft_delete_custom` 的审计记录中，见 [`reldb_audit`](crate::db::reldb_audit).
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn().with_ctx(&ctx);
+    /// ```
+    pub fn with_ctx(mut self, ctx: &TardisContext) -> Self {
+        self.own_paths = Some(ctx.own_paths.clone());
+        self.actor = Some(ctx.into());
+        self
+    }
+
+    /// Bypass the tenant isolation, e.g. for the administration / 绕过租户隔离，如用于管理操作
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn().with_ctx(&ctx).bypass_tenant();
+    /// ```
+    pub fn bypass_tenant(mut self) -> Self {
+        self.own_paths = None;
+        self
+    }
+
+    /// Own paths of the tenant isolation / 租户隔离的所属路径
+    pub fn tenant_own_paths(&self) -> Option<&str> {
+        self.own_paths.as_deref()
+    }
+
+    fn scope_select<'a>(&self, select_statement: &'a SelectStatement) -> TardisResult<Cow<'a, SelectStatement>> {
+        match &self.own_paths {
+            Some(own_paths) => reldb_tenant::scope_select(select_statement, own_paths, self.conn.get_database_backend()),
+            None => Ok(Cow::Borrowed(select_statement)),
+        }
+    }
+
+    fn scope_table_select<E>(&self, select: Select<E>) -> Select<E>
+    where
+        E: EntityTrait,
+    {
+        match &self.own_paths {
+            Some(own_paths) => reldb_tenant::scope_table_select(select, own_paths),
+            None => select,
+        }
+    }
+
+    /// Record the latency of an operation when the `metrics` feature is enabled
+    async fn observe<T>(&self, op: &str, operation: impl std::future::Future<Output = TardisResult<T>>) -> TardisResult<T> {
+        #[cfg(feature = "metrics")]
+        {
+            let metrics = TardisFuns::metrics();
+            crate::basic::metrics::TardisMetrics::observe(&metrics.reldb_query_duration, &metrics.reldb_query_errors, op, operation).await
+        }
+        #[cfg(not(feature = "metrics"))]
+        {
+            let _ = op;
+            operation.await
+        }
+    }
+
     /// Open a transaction / 开启一个事务
     ///
     /// # Examples
@@ -654,7 +1125,103 @@ impl TardisRelDBlConnection {
         Ok(())
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    /// Run the function in a transaction / 在事务中执行函数
+    ///
+    /// The transaction is committed when the function returns `Ok` , and rolled back when it returns `Err` or panics.
+    /// If the connection is already in a transaction, a nested transaction is created by a savepoint,
+    /// so that only the changes of the function are rolled back.
+    ///
+    /// 函数返回 `Ok` 时提交事务，返回 `Err` 或panic时回滚事务.
+    /// 如果连接已在事务中，会通过保存点创建嵌套事务，只回滚该函数的变更.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let id = conn.transaction(|tx| Box::pin(async move {
+    ///     tx.insert_one(model, &ctx).await?;
+    ///     tx.transaction(|nested| Box::pin(async move { nested.update_one(other_model, &ctx).await })).await?;
+    ///     Ok(id)
+    /// })).await?;
+    /// ```
+    pub async fn transaction<F, T>(&self, f: F) -> TardisResult<T>
+    where
+        F: for<'c> FnOnce(&'c TardisRelDBlConnection) -> BoxFuture<'c, TardisResult<T>> + Send,
+        T: Send,
+    {
+        let tx = match &self.tx {
+            Some(tx) => tx.begin().await?,
+            None => self.conn.begin().await?,
+        };
+        let tx_conn = TardisRelDBlConnection {
+            client_id: self.client_id,
+            conn: self.conn.clone(),
+            replica: None,
+            compatible_type: self.compatible_type,
+            own_paths: self.own_paths.clone(),
+            actor: self.actor.clone(),
+            tx: Some(tx),
+        };
+        match AssertUnwindSafe(f(&tx_conn)).catch_unwind().await {
+            Ok(Ok(result)) => {
+                tx_conn.commit().await?;
+                Ok(result)
+            }
+            Ok(Err(error)) => {
+                if let Err(rollback_error) = tx_conn.rollback().await {
+                    error!("[Tardis.RelDBClient] Rollback error: {}", rollback_error);
+                }
+                Err(error)
+            }
+            Err(panic) => {
+                if let Err(rollback_error) = tx_conn.rollback().await {
+                    error!("[Tardis.RelDBClient] Rollback error: {}", rollback_error);
+                }
+                std::panic::resume_unwind(panic)
+            }
+        }
+    }
+
+    /// Run the function in a transaction, and retry it on serialization failures and deadlocks / 在事务中执行函数，并在序列化失败及死锁时重试
+    ///
+    /// See [`transaction`](Self::transaction) and [`TardisTxRetryPolicy`].
+    /// The nested transactions are not retried, since the whole outer transaction is aborted by the database.
+    ///
+    /// 见 [`transaction`](Self::transaction) 及 [`TardisTxRetryPolicy`]. 嵌套事务不会重试，因为数据库会中止整个外层事务.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// use tardis::db::reldb_client::TardisTxRetryPolicy;
+    /// let conn = TardisFuns::reldb().conn();
+    /// conn.transaction_with_retry(&TardisTxRetryPolicy::default(), |tx| Box::pin(async move {
+    ///     tx.execute_one("UPDATE account SET balance = balance - 1 WHERE id = ?", vec!["a1".into()]).await?;
+    ///     Ok(())
+    /// })).await?;
+    /// ```
+    pub async fn transaction_with_retry<F, T>(&self, policy: &TardisTxRetryPolicy, f: F) -> TardisResult<T>
+    where
+        F: for<'c> Fn(&'c TardisRelDBlConnection) -> BoxFuture<'c, TardisResult<T>> + Send + Sync,
+        T: Send,
+    {
+        if self.tx.is_some() {
+            return self.transaction(f).await;
+        }
+        let mut retries = 0;
+        loop {
+            match self.transaction(&f).await {
+                Err(error) if retries < policy.max_retries && TardisTxRetryPolicy::is_retryable(&error) => {
+                    let backoff = policy.backoff(retries);
+                    retries += 1;
+                    warn!("[Tardis.RelDBClient] Transaction failed, retry {} after {:?}: {}", retries, backoff, error);
+                    tokio::time::sleep(backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Create a table from an entity / 从实体中创建表
     ///
     /// # Arguments
@@ -672,11 +1239,14 @@ impl TardisRelDBlConnection {
     where
         E: EntityTrait,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::create_table_from_entity_inner(entity, tx).await
-        } else {
-            TardisRelDBClient::create_table_from_entity_inner(entity, self.conn.as_ref()).await
-        }
+        self.observe("create_table_from_entity", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::create_table_from_entity_inner(entity, tx).await
+            } else {
+                TardisRelDBClient::create_table_from_entity_inner(entity, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
     /// Create table index and functions / 创建表、索引和函数
@@ -704,7 +1274,7 @@ impl TardisRelDBlConnection {
         Ok(())
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Create table  / 创建表
     ///
     /// # Arguments
@@ -720,14 +1290,17 @@ impl TardisRelDBlConnection {
     /// conn.create_table(&tardis_db_config::ActiveModel::create_table_statement(TardisFuns::reldb().backend())).await.unwrap();
     /// ```
     pub async fn create_table(&self, statement: &TableCreateStatement) -> TardisResult<()> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::create_table_inner(statement, tx).await
-        } else {
-            TardisRelDBClient::create_table_inner(statement, self.conn.as_ref()).await
-        }
+        self.observe("create_table", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::create_table_inner(statement, tx).await
+            } else {
+                TardisRelDBClient::create_table_inner(statement, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Create index / 创建索引
     ///
     /// # Arguments
@@ -743,14 +1316,17 @@ impl TardisRelDBlConnection {
     /// conn.create_index(&tardis_db_config::ActiveModel::create_index_statement()).await.unwrap();
     /// ```
     pub async fn create_index(&self, statements: &[IndexCreateStatement]) -> TardisResult<()> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::create_index_inner(statements, tx).await
-        } else {
-            TardisRelDBClient::create_index_inner(statements, self.conn.as_ref()).await
-        }
+        self.observe("create_index", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::create_index_inner(statements, tx).await
+            } else {
+                TardisRelDBClient::create_index_inner(statements, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Get a record, return a custom structure / 获取一条记录，返回自定义结构体
     ///
     /// # Arguments
@@ -775,11 +1351,15 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::get_dto_inner(select_statement, tx).await
-        } else {
-            TardisRelDBClient::get_dto_inner(select_statement, self.conn.as_ref()).await
-        }
+        self.observe("get_dto", async {
+            let select_statement = self.scope_select(select_statement)?;
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::get_dto_inner(&select_statement, tx).await
+            } else {
+                TardisRelDBClient::get_dto_inner(&select_statement, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
     /// Get a record, return a custom structure / 获取一条记录，返回自定义结构体
@@ -793,14 +1373,17 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::get_dto_by_sql_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::get_dto_by_sql_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("get_dto_by_sql", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::get_dto_by_sql_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::get_dto_by_sql_inner(sql, params, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Get multiple rows and return a custom structure / 获取多条记录，返回自定义结构体
     ///
     /// # Arguments
@@ -824,14 +1407,18 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::find_dtos_inner(select_statement, tx).await
-        } else {
-            TardisRelDBClient::find_dtos_inner(select_statement, self.conn.as_ref()).await
-        }
+        self.observe("find_dtos", async {
+            let select_statement = self.scope_select(select_statement)?;
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::find_dtos_inner(&select_statement, tx).await
+            } else {
+                TardisRelDBClient::find_dtos_inner(&select_statement, self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Get multiple rows and return a custom structure / 获取多条记录，返回自定义结构体
     ///
     /// # Arguments
@@ -843,14 +1430,17 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::find_dtos_by_sql_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::find_dtos_by_sql_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("find_dtos_by_sql", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::find_dtos_by_sql_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::find_dtos_by_sql_inner(sql, params, self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Paging to get multiple records and the total number of records, returning a custom structure / 分页获取多条记录及总记录数，返回自定义结构体
     ///
     /// # Arguments
@@ -877,14 +1467,18 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::paginate_dtos_inner(select_statement, page_number, page_size, tx).await
-        } else {
-            TardisRelDBClient::paginate_dtos_inner(select_statement, page_number, page_size, self.conn.as_ref()).await
-        }
+        self.observe("paginate_dtos", async {
+            let select_statement = self.scope_select(select_statement)?;
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::paginate_dtos_inner(&select_statement, page_number, page_size, self.dialect(), tx).await
+            } else {
+                TardisRelDBClient::paginate_dtos_inner(&select_statement, page_number, page_size, self.dialect(), self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Paging to get multiple records and the total number of records, returning a custom structure / 分页获取多条记录及总记录数，返回自定义结构体
     ///
     /// # Arguments
@@ -898,42 +1492,184 @@ impl TardisRelDBlConnection {
     where
         D: FromQueryResult,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::paginate_dtos_by_sql_inner(sql, params, page_number, page_size, tx).await
-        } else {
-            TardisRelDBClient::paginate_dtos_by_sql_inner(sql, params, page_number, page_size, self.conn.as_ref()).await
-        }
+        self.observe("paginate_dtos_by_sql", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::paginate_dtos_by_sql_inner(sql, params, page_number, page_size, self.dialect(), tx).await
+            } else {
+                TardisRelDBClient::paginate_dtos_by_sql_inner(sql, params, page_number, page_size, self.dialect(), self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
-    /// Get number of records / 获取记录数量
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Paging by the key of the last record, returning a custom structure / 按上一条记录的键分页，返回自定义结构体
+    ///
+    /// Unlike [`paginate_dtos`](Self::paginate_dtos), the records are located by `key_column > after` ( `<` in the descending order) instead of the offset,
+    /// so the deep pages are as fast as the first one. `key_column` should be unique and indexed, the `ORDER BY` of the statement is replaced by it.
+    ///
+    /// 与 [`paginate_dtos`](Self::paginate_dtos) 不同，通过 `key_column > after` （降序时为 `<` ）而不是偏移量定位记录，
+    /// 因此深分页与首页一样快. `key_column` 应唯一且有索引，Statement的 `ORDER BY` 会被替换为该列.
     ///
     /// # Arguments
     ///
     ///  * `select_statement` - Statement of the query / 查询的Statement
+    ///  * `key_column` - Column of the key / 键列
+    ///  * `order` - Order of the key / 键的排序
+    ///  * `after` - Key of the last record of the previous page, None for the first page / 上一页最后一条记录的键，首页为None
+    ///  * `page_size` -  Number of records per page / 每页记录数
     ///
     /// # Examples
     /// ```ignore
     /// use tardis::db::sea_orm::sea_query::*;
     /// use tardis::db::domain::tardis_db_config;
-    /// use tardis::db::reldb_client::TardisActiveModel;
     /// use tardis::TardisFuns;
-    /// let mut conn = TardisFuns::reldb().conn();
-    /// let resp = conn.count(&Query::select()
-    ///     .column(tardis_db_config::Column::Id)
-    ///     .column(tardis_db_config::Column::Name)
-    ///     .from(tardis_db_config::Entity)
+    /// let conn = TardisFuns::reldb().conn();
+    /// let select = Query::select().columns(tardis_db_config::Column::iter()).from(tardis_db_config::Entity).to_owned();
+    /// let mut after = None;
+    /// loop {
+    ///     let configs = conn.paginate_dtos_by_keyset::<tardis_db_config::Model, _>(&select, tardis_db_config::Column::Id, Order::Asc, after, 100).await.unwrap();
+    ///     // ...
+    ///     match configs.last() {
+    ///         Some(config) if configs.len() == 100 => after = Some(config.id.clone()),
+    ///         _ => break,
+    ///     }
+    /// }
+    /// ```
+    pub async fn paginate_dtos_by_keyset<D, K>(
+        &self,
+        select_statement: &SelectStatement,
+        key_column: impl sea_query::IntoColumnRef,
+        order: sea_query::Order,
+        after: Option<K>,
+        page_size: u64,
+    ) -> TardisResult<Vec<D>>
+    where
+        D: FromQueryResult,
+        K: Into<Value>,
+    {
+        let key_column = key_column.into_column_ref();
+        let mut select_statement = select_statement.clone();
+        if let Some(after) = after {
+            select_statement.and_where(match order {
+                sea_query::Order::Desc => Expr::col(key_column.clone()).lt(after),
+                _ => Expr::col(key_column.clone()).gt(after),
+            });
+        }
+        select_statement.clear_order_by().order_by(key_column, order);
+        self.observe("paginate_dtos_by_keyset", async {
+            let select_statement = self.scope_select(&select_statement)?;
+            let select_statement = self.dialect().limit(self.conn.get_database_backend().build(select_statement.as_ref()), page_size);
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::do_find_dtos_inner(select_statement, tx).await
+            } else {
+                TardisRelDBClient::do_find_dtos_inner(select_statement, self.read_conn()).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Stream multiple records, returning a custom structure / 流式获取多条记录，返回自定义结构体
+    ///
+    /// The records are fetched while the stream is consumed, so that huge result sets are not loaded into memory.
+    /// Inside a transaction the stream holds the transaction, drop it before other operations of the connection.
+    ///
+    /// 记录在消费流时才获取，大结果集不会一次加载到内存中. 在事务中流会占用事务，在连接的其它操作之前需先释放流.
+    ///
+    /// # Arguments
+    ///
+    ///  * `select_statement` - Statement of the query / 查询的Statement
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::futures::TryStreamExt;
+    /// use tardis::db::sea_orm::sea_query::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let mut configs = conn.stream_dtos::<tardis_db_config::Model>(&Query::select().columns(tardis_db_config::Column::iter()).from(tardis_db_config::Entity)).await.unwrap();
+    /// while let Some(config) = configs.try_next().await.unwrap() {
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn stream_dtos<'a, D>(&'a self, select_statement: &SelectStatement) -> TardisResult<BoxStream<'a, TardisResult<D>>>
+    where
+        D: FromQueryResult + Send + 'a,
+    {
+        self.observe("stream_dtos", async {
+            let select_statement = self.scope_select(select_statement)?;
+            self.do_stream(self.conn.get_database_backend().build(select_statement.as_ref())).await
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Stream multiple records, returning a custom structure / 流式获取多条记录，返回自定义结构体
+    ///
+    /// See [`stream_dtos`](Self::stream_dtos).
+    ///
+    /// # Arguments
+    ///
+    ///  * `sql` - sql of the query / 查询SQL
+    ///  * `params` - params of the query / 查询参数
+    ///
+    pub async fn stream_by_sql<'a, D>(&'a self, sql: &str, params: Vec<Value>) -> TardisResult<BoxStream<'a, TardisResult<D>>>
+    where
+        D: FromQueryResult + Send + 'a,
+    {
+        self.observe("stream_by_sql", async {
+            self.do_stream(Statement::from_sql_and_values(self.conn.get_database_backend(), sql, params)).await
+        })
+        .await
+    }
+
+    async fn do_stream<'a, D>(&'a self, statement: Statement) -> TardisResult<BoxStream<'a, TardisResult<D>>>
+    where
+        D: FromQueryResult + Send + 'a,
+    {
+        trace!("[Tardis.RelDBClient] Streaming sql: {}, params:{:?}", statement.sql, statement.values);
+        let stream = if let Some(tx) = &self.tx {
+            D::find_by_statement(statement).stream(tx).await?
+        } else {
+            D::find_by_statement(statement).stream(self.read_conn()).await?
+        };
+        Ok(stream.map_err(TardisError::from).boxed())
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Get number of records / 获取记录数量
+    ///
+    /// # Arguments
+    ///
+    ///  * `select_statement` - Statement of the query / 查询的Statement
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::db::sea_orm::sea_query::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::db::reldb_client::TardisActiveModel;
+    /// use tardis::TardisFuns;
+    /// let mut conn = TardisFuns::reldb().conn();
+    /// let resp = conn.count(&Query::select()
+    ///     .column(tardis_db_config::Column::Id)
+    ///     .column(tardis_db_config::Column::Name)
+    ///     .from(tardis_db_config::Entity)
     /// ).await.unwrap();
     /// ```
     pub async fn count(&self, select_statement: &SelectStatement) -> TardisResult<u64> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::count_inner(select_statement, tx).await
-        } else {
-            TardisRelDBClient::count_inner(select_statement, self.conn.as_ref()).await
-        }
+        self.observe("count", async {
+            let select_statement = self.scope_select(select_statement)?;
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::count_inner(&select_statement, self.dialect(), tx).await
+            } else {
+                TardisRelDBClient::count_inner(&select_statement, self.dialect(), self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Get number of records / 获取记录数量
     ///
     /// # Arguments
@@ -943,14 +1679,17 @@ impl TardisRelDBlConnection {
     ///
     /// ```
     pub async fn count_by_sql(&self, sql: &str, params: Vec<Value>) -> TardisResult<u64> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::count_by_sql_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::count_by_sql_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("count_by_sql", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::count_by_sql_inner(sql, params, self.dialect(), tx).await
+            } else {
+                TardisRelDBClient::count_by_sql_inner(sql, params, self.dialect(), self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(skip_all)]
+    #[instrument(skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Execute SQL operations (provide custom SQL processing capabilities) / 执行SQL操作（提供自定义SQL处理能力）
     ///
     /// # Arguments
@@ -962,52 +1701,67 @@ impl TardisRelDBlConnection {
         S: StatementBuilder,
     {
         let statement = self.conn.get_database_backend().build(statement);
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::execute_inner(statement, tx).await
-        } else {
-            TardisRelDBClient::execute_inner(statement, self.conn.as_ref()).await
-        }
+        self.observe("execute", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::execute_inner(statement, tx).await
+            } else {
+                TardisRelDBClient::execute_inner(statement, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Execute SQL operations (provide custom SQL processing capabilities) / 执行SQL操作（提供自定义SQL处理能力）
     pub async fn execute_one(&self, sql: &str, params: Vec<Value>) -> TardisResult<ExecResult> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::execute_one_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::execute_one_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("execute_one", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::execute_one_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::execute_one_inner(sql, params, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     // Execute SQL operations (provide custom SQL processing capabilities) / 执行SQL操作（提供自定义SQL处理能力）
     pub async fn execute_many(&self, sql: &str, params: Vec<Vec<Value>>) -> TardisResult<()> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::execute_many_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::execute_many_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("execute_many", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::execute_many_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::execute_many_inner(sql, params, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     pub async fn query_one(&self, sql: &str, params: Vec<Value>) -> TardisResult<Option<QueryResult>> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::query_one_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::query_one_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("query_one", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::query_one_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::query_one_inner(sql, params, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     pub async fn query_all(&self, sql: &str, params: Vec<Value>) -> TardisResult<Vec<QueryResult>> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::query_all_inner(sql, params, tx).await
-        } else {
-            TardisRelDBClient::query_all_inner(sql, params, self.conn.as_ref()).await
-        }
+        self.observe("query_all", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::query_all_inner(sql, params, tx).await
+            } else {
+                TardisRelDBClient::query_all_inner(sql, params, self.read_conn()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Insert a record and return primary key value / 插入一条记录，返回主键值
     ///
     /// # Arguments
@@ -1033,14 +1787,22 @@ impl TardisRelDBlConnection {
     where
         T: TardisActiveModel,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::insert_one_inner(model, tx, ctx).await
-        } else {
-            TardisRelDBClient::insert_one_inner(model, self.conn.as_ref(), ctx).await
-        }
+        self.observe("insert_one", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::insert_one_inner(model, tx, ctx).await
+            } else if T::audited() {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::insert_one_inner(model, &tx, ctx).await?;
+                tx.commit().await?;
+                Ok(result)
+            } else {
+                TardisRelDBClient::insert_one_inner(model, self.conn.as_ref(), ctx).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Insert multiple records / 插入多条记录
     ///
     /// # Arguments
@@ -1068,16 +1830,103 @@ impl TardisRelDBlConnection {
     where
         T: TardisActiveModel,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::insert_many_inner(models, tx, ctx).await
-        } else {
-            TardisRelDBClient::insert_many_inner(models, self.conn.as_ref(), ctx).await
-        }
+        self.observe("insert_many", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::insert_many_inner(models, tx, ctx).await
+            } else {
+                TardisRelDBClient::insert_many_inner(models, self.conn.as_ref(), ctx).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Insert a record, or update it if the primary key exists / 插入一条记录，如果主键已存在则更新
+    ///
+    /// See [`upsert_many`](Self::upsert_many).
+    ///
+    /// # Arguments
+    ///
+    ///  * `model` -  Record to be upserted / 要插入或更新的记录
+    ///  * `ctx` -  TardisContext
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::db::sea_orm::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let resp = conn.upsert_one(tardis_db_config::ActiveModel {
+    ///     k: Set("ke".to_string()),
+    ///     v: Set("ve".to_string()),
+    ///     ..Default::default()
+    /// },&ctx).await.unwrap();
+    /// ```
+    pub async fn upsert_one<T>(&self, model: T, ctx: &TardisContext) -> TardisResult<TardisUpsertResult<PrimaryKeyValue<T>>>
+    where
+        T: TardisActiveModel,
+    {
+        self.upsert_many(vec![model], ctx).await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Insert multiple records, or update them if the primary keys exist / 插入多条记录，如果主键已存在则更新
+    ///
+    /// It maps to `ON CONFLICT ... DO UPDATE` on Postgres and SQLite, and `ON DUPLICATE KEY UPDATE` on MySQL.
+    /// The inserted rows are filled by `fill_ctx(ctx, true)` , and only the columns set by the caller or by `fill_ctx(ctx, false)` are updated on conflict.
+    /// All models must set the same columns, they are split into chunks by the bind parameter limit of the database and upserted in a transaction,
+    /// the current transaction is used if exists.
+    ///
+    /// 在Postgres及SQLite上对应 `ON CONFLICT ... DO UPDATE` ，在MySQL上对应 `ON DUPLICATE KEY UPDATE` .
+    /// 插入的行由 `fill_ctx(ctx, true)` 填充，冲突时只更新调用方设置的列及 `fill_ctx(ctx, false)` 填充的列.
+    /// 所有记录必须设置相同的列，记录会按数据库的绑定参数上限分批并在事务中执行，如果存在当前事务则使用当前事务.
+    ///
+    /// # Arguments
+    ///
+    ///  * `models` -  Set of records to be upserted / 要插入或更新的记录集
+    ///  * `ctx` -  TardisContext
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::db::sea_orm::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let resp = conn.upsert_many(vec![
+    ///     tardis_db_config::ActiveModel {
+    ///          k: Set("ke".to_string()),
+    ///          v: Set("ve".to_string()),
+    ///          ..Default::default()
+    ///     }
+    ///  ],&ctx).await.unwrap();
+    /// ```
+    pub async fn upsert_many<T>(&self, models: Vec<T>, ctx: &TardisContext) -> TardisResult<TardisUpsertResult<PrimaryKeyValue<T>>>
+    where
+        T: TardisActiveModel,
+    {
+        self.observe("upsert_many", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::upsert_many_inner(models, self.dialect(), tx, ctx).await
+            } else {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::upsert_many_inner(models, self.dialect(), &tx, ctx).await?;
+                tx.commit().await?;
+                Ok(result)
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Update a record / 更新一条记录
     ///
+    /// If the entity has a [version column](TardisActiveModel::version_column), the record is updated only if the version of the model is unchanged,
+    /// and a `409-tardis-reldb-conflict` error is returned otherwise.
+    /// With [`with_ctx`](Self::with_ctx), the record out of the own paths of the context is not updated.
+    ///
+    /// 如果实体有[版本列](TardisActiveModel::version_column)，仅在模型的版本未变化时更新记录，否则返回 `409-tardis-reldb-conflict` 错误.
+    /// 使用 [`with_ctx`](Self::with_ctx) 时，不属于上下文所属路径的记录不会被更新.
+    ///
     /// # Arguments
     ///
     ///  * `model` -  Records to be inserted / 要插入的记录
@@ -1102,14 +1951,55 @@ impl TardisRelDBlConnection {
     where
         T: TardisActiveModel,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::update_one_inner(model, tx, ctx).await
-        } else {
-            TardisRelDBClient::update_one_inner(model, self.conn.as_ref(), ctx).await
-        }
+        self.update_one_scoped("update_one", model, Condition::all(), ctx).await?;
+        Ok(())
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Update a record if it also matches the condition, returns the number of updated records /
+    /// 如果记录同时满足条件则更新该记录，返回更新的记录数
+    ///
+    /// Same as [`update_one`](Self::update_one) , e.g. the record out of the own paths of the context is not updated and `0` is returned.
+    ///
+    /// 与 [`update_one`](Self::update_one) 相同，如不属于上下文所属路径的记录不会被更新并返回 `0` .
+    ///
+    /// # Arguments
+    ///
+    ///  * `model` -  Record to be updated / 要更新的记录
+    ///  * `condition` -  Condition of the record / 记录的条件
+    ///  * `ctx` -  TardisContext
+    pub async fn update_one_with_condition<T>(&self, model: T, condition: Condition, ctx: &TardisContext) -> TardisResult<u64>
+    where
+        T: TardisActiveModel,
+    {
+        self.update_one_scoped("update_one_with_condition", model, condition, ctx).await
+    }
+
+    /// Update a record within the own paths of the connection if it also matches the condition
+    async fn update_one_scoped<T>(&self, operation: &str, model: T, condition: Condition, ctx: &TardisContext) -> TardisResult<u64>
+    where
+        T: TardisActiveModel,
+    {
+        self.observe(operation, async {
+            let condition = match &self.own_paths {
+                Some(own_paths) => reldb_tenant::scope_table_condition::<T::Entity>(condition, own_paths),
+                None => condition,
+            };
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::update_one_inner(model, condition, tx, ctx).await
+            } else if T::audited() {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::update_one_inner(model, condition, &tx, ctx).await?;
+                tx.commit().await?;
+                Ok(result)
+            } else {
+                TardisRelDBClient::update_one_inner(model, condition, self.conn.as_ref(), ctx).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Update multiple records / 更新多条记录
     ///
     /// # Arguments
@@ -1132,14 +2022,74 @@ impl TardisRelDBlConnection {
     ///     .and_where(Expr::col(tardis_db_config::Column::id).eq("111"))).await.unwrap();
     /// ```
     pub async fn update_many(&self, update_statement: &UpdateStatement) -> TardisResult<()> {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::update_many_inner(update_statement, tx).await
-        } else {
-            TardisRelDBClient::update_many_inner(update_statement, self.conn.as_ref()).await
-        }
+        self.observe("update_many", async {
+            let update_statement = match &self.own_paths {
+                Some(own_paths) => reldb_tenant::scope_update(update_statement, own_paths, self.conn.get_database_backend())?,
+                None => Cow::Borrowed(update_statement),
+            };
+            let actor = self.actor.clone().unwrap_or_default();
+            let audited_table = reldb_audit::audited_update_table(&update_statement, self.conn.get_database_backend())?;
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::update_many_inner(&update_statement, audited_table, &actor, tx).await
+            } else if audited_table.is_some() {
+                let tx = self.conn.begin().await?;
+                TardisRelDBClient::update_many_inner(&update_statement, audited_table, &actor, &tx).await?;
+                tx.commit().await?;
+                Ok(())
+            } else {
+                TardisRelDBClient::update_many_inner(&update_statement, None, &actor, self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Delete the records matching the condition, returns the number of deleted records / 删除满足条件的记录，返回删除的记录数
+    ///
+    /// The records are deleted by a single statement, and the deletions of the audited tables are recorded in the same transaction.
+    /// With [`with_ctx`](Self::with_ctx), the records of the tenant-scoped tables out of the own paths are not deleted.
+    ///
+    /// 记录通过单条语句删除，审计表的删除记录在同一事务中记录.
+    /// 使用 [`with_ctx`](Self::with_ctx) 时，租户隔离表中不属于该所属路径的记录不会被删除.
+    ///
+    /// # Arguments
+    ///
+    ///  * `condition` -  Condition of the records / 记录的条件
+    ///  * `ctx` -  TardisContext, recorded in the audit records / 记录在审计记录中
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::db::sea_orm::*;
+    /// use tardis::db::domain::tardis_db_config;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let deleted = conn.hard_delete::<tardis_db_config::Entity>(Condition::all().add(tardis_db_config::Column::K.eq("111")), &ctx).await.unwrap();
+    /// ```
+    pub async fn hard_delete<E>(&self, condition: Condition, ctx: &TardisContext) -> TardisResult<u64>
+    where
+        E: EntityTrait,
+    {
+        self.observe("hard_delete", async {
+            let condition = match &self.own_paths {
+                Some(own_paths) => reldb_tenant::scope_table_condition::<E>(condition, own_paths),
+                None => condition,
+            };
+            let actor: AuditActor = ctx.into();
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::hard_delete_inner::<E, _>(condition, &actor, tx).await
+            } else if reldb_audit::is_audited_entity::<E>() {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::hard_delete_inner::<E, _>(condition, &actor, &tx).await?;
+                tx.commit().await?;
+                Ok(result)
+            } else {
+                TardisRelDBClient::hard_delete_inner::<E, _>(condition, &actor, self.conn.as_ref()).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Soft delete record(s) (primary key is Id) / 软删除记录(主键为Id)
     ///
     /// # Arguments
@@ -1161,14 +2111,27 @@ impl TardisRelDBlConnection {
     where
         E: EntityTrait,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::soft_delete_inner(select, delete_user, tx).await
-        } else {
-            TardisRelDBClient::soft_delete_inner(select, delete_user, self.conn.as_ref()).await
-        }
+        self.observe("soft_delete", async {
+            let select = self.scope_table_select(select);
+            let actor = self.actor.clone().unwrap_or_else(|| AuditActor {
+                owner: delete_user.to_string(),
+                ak: String::new(),
+            });
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::soft_delete_inner(select, delete_user, &actor, self.dialect(), tx).await
+            } else if reldb_audit::is_audited_entity::<E>() {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::soft_delete_inner(select, delete_user, &actor, self.dialect(), &tx).await?;
+                tx.commit().await?;
+                Ok(result)
+            } else {
+                TardisRelDBClient::soft_delete_inner(select, delete_user, &actor, self.dialect(), self.conn.as_ref()).await
+            }
+        })
+        .await
     }
 
-    #[instrument(name = "reldb_query", skip_all)]
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
     /// Soft delete record(s) (custom primary key) / 软删除记录(自定义主键)
     ///
     /// # Arguments
@@ -1191,11 +2154,193 @@ impl TardisRelDBlConnection {
     where
         E: EntityTrait,
     {
-        if let Some(tx) = &self.tx {
-            TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, tx).await
-        } else {
-            TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, self.conn.as_ref()).await
+        self.observe("soft_delete_custom", async {
+            let select = self.scope_table_select(select);
+            let actor = self.actor.clone().unwrap_or_default();
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, &actor, self.dialect(), tx).await
+            } else if reldb_audit::is_audited_entity::<E>() {
+                let tx = self.conn.begin().await?;
+                let result = TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, &actor, self.dialect(), &tx).await?;
+                tx.commit().await?;
+                Ok(result)
+            } else {
+                TardisRelDBClient::soft_delete_custom_inner(select, custom_pk_field, &actor, self.dialect(), self.conn.as_ref()).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Restore a soft deleted record / 恢复软删除的记录
+    ///
+    /// The record is inserted back into its table and removed from `tardis_del_record` in a transaction,
+    /// the current transaction is used if exists.
+    /// With [`with_ctx`](Self::with_ctx), the records of the tenant-scoped tables out of the own paths are not found.
+    ///
+    /// 记录会在事务中重新插入到原表并从 `tardis_del_record` 中删除，如果存在当前事务则使用当前事务.
+    /// 使用 [`with_ctx`](Self::with_ctx) 时，租户隔离表中不属于该所属路径的记录视为不存在.
+    ///
+    /// # Arguments
+    ///
+    ///  * `entity_name` -  Table name of the record / 记录的表名
+    ///  * `record_id` -  Primary key of the record / 记录的主键
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// conn.restore_soft_deleted("tardis_config", "111").await.unwrap();
+    /// ```
+    pub async fn restore_soft_deleted(&self, entity_name: &str, record_id: &str) -> TardisResult<()> {
+        self.observe("restore_soft_deleted", async {
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::restore_soft_deleted_inner(entity_name, record_id, self.own_paths.as_deref(), tx).await
+            } else {
+                let tx = self.conn.begin().await?;
+                TardisRelDBClient::restore_soft_deleted_inner(entity_name, record_id, self.own_paths.as_deref(), &tx).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Paginate the soft deleted records, the latest first / 分页查询软删除的记录，最新的在前
+    ///
+    /// With [`with_ctx`](Self::with_ctx), the records of the tenant-scoped tables are filtered by the own paths stored in their content.
+    ///
+    /// 使用 [`with_ctx`](Self::with_ctx) 时，租户隔离表的记录按其内容中保存的所属路径过滤.
+    ///
+    /// # Arguments
+    ///
+    ///  * `entity_name` -  Table name of the records / 记录的表名
+    ///  * `creator` -  Delete user / 删除人
+    ///  * `page_number` -  Current page number, starting from 1 / 当前页码，从1开始
+    ///  * `page_size` -  Number of records per page / 每页记录数
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let (records, total_size) = conn.paginate_soft_deleted(Some("tardis_config"), Some("admin"), 1, 10).await.unwrap();
+    /// ```
+    pub async fn paginate_soft_deleted(
+        &self,
+        entity_name: Option<&str>,
+        creator: Option<&str>,
+        page_number: u64,
+        page_size: u64,
+    ) -> TardisResult<(Vec<tardis_db_del_record::Model>, u64)> {
+        let mut select = sea_query::Query::select();
+        select.columns(tardis_db_del_record::Column::iter()).from(tardis_db_del_record::Entity);
+        if let Some(entity_name) = entity_name {
+            select.and_where(Expr::col(tardis_db_del_record::Column::EntityName).eq(entity_name));
+        }
+        if let Some(creator) = creator {
+            select.and_where(Expr::col(tardis_db_del_record::Column::Creator).eq(creator));
+        }
+        if let Some(own_paths) = &self.own_paths {
+            reldb_tenant::scope_del_records(&mut select, own_paths, self.conn.get_database_backend());
+        }
+        select.order_by(tardis_db_del_record::Column::CreateTime, sea_query::Order::Desc).order_by(tardis_db_del_record::Column::Id, sea_query::Order::Desc);
+        self.observe("paginate_soft_deleted", async {
+            // the records are written by the deletes, read them from the primary database
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::paginate_dtos_inner(&select, page_number, page_size, self.dialect(), tx).await
+            } else {
+                TardisRelDBClient::paginate_dtos_inner(&select, page_number, page_size, self.dialect(), self.conn.as_ref()).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Find the audit records of a record, the earliest first / 查询记录的审计记录，最早的在前
+    ///
+    /// With [`with_ctx`](Self::with_ctx), the records of the tenant-scoped tables are filtered by the own paths of the record.
+    ///
+    /// 使用 [`with_ctx`](Self::with_ctx) 时，租户隔离表的记录按记录的所属路径过滤.
+    ///
+    /// See [`reldb_audit`](crate::db::reldb_audit).
+    ///
+    /// # Arguments
+    ///
+    ///  * `entity_name` -  Table name of the record / 记录的表名
+    ///  * `record_id` -  Primary key of the record / 记录的主键
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let records = conn.find_audit_records("tardis_config", "111").await.unwrap();
+    /// ```
+    pub async fn find_audit_records(&self, entity_name: &str, record_id: &str) -> TardisResult<Vec<tardis_db_audit_record::Model>> {
+        let mut select = sea_query::Query::select();
+        select
+            .columns(tardis_db_audit_record::Column::iter())
+            .from(tardis_db_audit_record::Entity)
+            .and_where(Expr::col(tardis_db_audit_record::Column::EntityName).eq(entity_name))
+            .and_where(Expr::col(tardis_db_audit_record::Column::RecordId).eq(record_id))
+            .order_by(tardis_db_audit_record::Column::CreateTime, sea_query::Order::Asc);
+        if let Some(own_paths) = &self.own_paths {
+            if reldb_tenant::own_paths_column(entity_name).is_some() {
+                select.and_where(reldb_tenant::own_paths_predicate(tardis_db_audit_record::Column::OwnPaths, own_paths));
+            }
         }
+        self.observe("find_audit_records", async {
+            // the records are written by the changes, read them from the primary database
+            if let Some(tx) = &self.tx {
+                TardisRelDBClient::find_dtos_inner(&select, tx).await
+            } else {
+                TardisRelDBClient::find_dtos_inner(&select, self.conn.as_ref()).await
+            }
+        })
+        .await
+    }
+
+    #[instrument(name = "reldb_query", skip_all, fields(db.system = field::Empty, db.statement = field::Empty, db.response.returned_rows = field::Empty))]
+    /// Purge the soft deleted records older than the retention / 清除早于保留时长的软删除记录
+    ///
+    /// Nothing is purged if the retention goes beyond the earliest representable time.
+    ///
+    /// 如果保留时长超出可表示的最早时间，则不清除任何记录.
+    ///
+    /// It runs periodically when `retention_sec` of [`DBDelRecordConfig`](crate::config::config_dto::component::db::DBDelRecordConfig) is set.
+    ///
+    /// 设置了 [`DBDelRecordConfig`](crate::config::config_dto::component::db::DBDelRecordConfig) 的 `retention_sec` 时会定期执行.
+    ///
+    /// # Arguments
+    ///
+    ///  * `retention` -  Retention of the records / 记录的保留时长
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use std::time::Duration;
+    /// use tardis::TardisFuns;
+    /// let conn = TardisFuns::reldb().conn();
+    /// let purged = conn.purge_soft_deleted(Duration::from_secs(30 * 24 * 3600)).await.unwrap();
+    /// ```
+    pub async fn purge_soft_deleted(&self, retention: Duration) -> TardisResult<u64> {
+        self.observe("purge_soft_deleted", async {
+            let Some(before) = chrono::Duration::from_std(retention).ok().and_then(|retention| chrono::Utc::now().checked_sub_signed(retention)) else {
+                return Ok(0);
+            };
+            // sqlite compares the text of `CURRENT_TIMESTAMP`
+            let before: Value = match self.conn.get_database_backend() {
+                DatabaseBackend::Sqlite => before.format("%Y-%m-%d %H:%M:%S").to_string().into(),
+                _ => before.into(),
+            };
+            let delete = tardis_db_del_record::Entity::delete_many().filter(tardis_db_del_record::Column::CreateTime.lt(before));
+            let result = if let Some(tx) = &self.tx {
+                delete.exec(tx).await?
+            } else {
+                delete.exec(self.conn.as_ref()).await?
+            };
+            Ok(result.rows_affected)
+        })
+        .await
     }
 }
 
@@ -1230,129 +2375,154 @@ where
     where
         C: ConnectionTrait,
     {
-        let delete_entities = self.soft_delete_custom(custom_pk_field, db).await?;
-        let count = delete_entities.len() as u64;
-        for delete_entity in delete_entities {
-            tardis_db_del_record::ActiveModel {
-                entity_name: Set(delete_entity.entity_name.to_string()),
-                record_id: Set(delete_entity.record_id.to_string()),
-                content: Set(delete_entity.content),
-                creator: Set(delete_user.to_string()),
-                ..Default::default()
-            }
-            .insert(db)
-            .await?;
-        }
-        Ok(count)
+        let dialect = TardisRelDBDialect::new(db.get_database_backend(), CompatibleType::None);
+        Ok(do_soft_delete(self, custom_pk_field, delete_user, dialect, db).await?.len() as u64)
     }
 
     async fn soft_delete_custom<C>(self, custom_pk_field: &str, db: &C) -> TardisResult<Vec<DeleteEntity>>
     where
         C: ConnectionTrait,
     {
-        let db_backend: DbBackend = db.get_database_backend();
-
-        let sql = self.build(db_backend).sql.replace('?', "''");
-        let ast = match Parser::parse_sql(
-            match db.get_database_backend() {
-                DatabaseBackend::MySql => &MySqlDialect {},
-                DatabaseBackend::Postgres => &PostgreSqlDialect {},
-                DatabaseBackend::Sqlite => &SQLiteDialect {},
-            },
-            &sql,
-        )?
-        .pop()
-        {
-            Some(ast) => ast,
-            None => {
-                return Err(TardisError::format_error(
-                    "[Tardis.RelDBClient] Sql parsing error, no valid Statement found",
-                    "406-tardis-reldb-sql-error",
-                ))
-            }
-        };
-        let mut table_name = String::new();
-        if let ast::Statement::Query(query) = ast {
-            if let SetExpr::Select(select) = query.body.as_ref() {
-                if let TableFactor::Table { name, .. } = &select.from[0].relation {
-                    if let Some(table_ident) = name.0.first().and_then(ast::ObjectNamePart::as_ident) {
-                        table_name.clone_from(&table_ident.value);
-                    }
+        do_soft_delete_custom(self, custom_pk_field, TardisRelDBDialect::new(db.get_database_backend(), CompatibleType::None), db).await
+    }
+}
+
+/// Select the record by the primary keys of the model, `None` if any of them is not set
+fn pk_select<T>(model: &T) -> Option<Select<T::Entity>>
+where
+    T: ActiveModelTrait,
+{
+    let mut select = T::Entity::find();
+    for pk in <T::Entity as EntityTrait>::PrimaryKey::iter() {
+        let column = pk.into_column();
+        select = select.filter(column.eq(model.get(column).into_value()?));
+    }
+    Some(select)
+}
+
+async fn do_soft_delete<E, C>(select: Select<E>, custom_pk_field: &str, delete_user: &str, dialect: TardisRelDBDialect, db: &C) -> TardisResult<Vec<DeleteEntity>>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let delete_entities = do_soft_delete_custom(select, custom_pk_field, dialect, db).await?;
+    for delete_entity in &delete_entities {
+        // without `RETURNING` , which is not supported by the Oracle compatible databases
+        tardis_db_del_record::Entity::insert(tardis_db_del_record::ActiveModel {
+            entity_name: Set(delete_entity.entity_name.to_string()),
+            record_id: Set(delete_entity.record_id.to_string()),
+            content: Set(delete_entity.content.clone()),
+            creator: Set(delete_user.to_string()),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await?;
+    }
+    Ok(delete_entities)
+}
+
+async fn do_soft_delete_custom<E, C>(select: Select<E>, custom_pk_field: &str, dialect: TardisRelDBDialect, db: &C) -> TardisResult<Vec<DeleteEntity>>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let db_backend: DbBackend = db.get_database_backend();
+
+    let ast = parse_sql(db_backend, &select.build(db_backend).sql)?;
+    let mut table_name = String::new();
+    if let ast::Statement::Query(query) = ast {
+        if let SetExpr::Select(select) = query.body.as_ref() {
+            if let TableFactor::Table { name, .. } = &select.from[0].relation {
+                if let Some(table_ident) = name.0.first().and_then(ast::ObjectNamePart::as_ident) {
+                    table_name.clone_from(&table_ident.value);
                 }
             }
         }
-        if table_name.is_empty() {
-            return TardisResult::Err(TardisError::not_found(
-                "[Tardis.RelDBClient] Sql parsing error, the name of the table to be soft deleted was not found",
-                "404-tardis-reldb-soft-delete-table-not-exit",
-            ));
-        }
-
-        let rows = self.into_json().all(db).await?;
-        if rows.is_empty() {
-            return Ok(Vec::new());
-        }
-        let mut ids: Vec<Value> = Vec::new();
-        let mut delete_entities = Vec::with_capacity(ids.len());
-        for row in rows {
-            let id = row[custom_pk_field].clone();
-            let json = TardisFuns::json.obj_to_string(&row)?;
-            if id.is_string() {
-                ids.push(
-                    (*id.as_str().as_ref().ok_or_else(|| {
+    }
+    if table_name.is_empty() {
+        return TardisResult::Err(TardisError::not_found(
+            "[Tardis.RelDBClient] Sql parsing error, the name of the table to be soft deleted was not found",
+            "404-tardis-reldb-soft-delete-table-not-exit",
+        ));
+    }
+
+    let rows = select.into_json().all(db).await?;
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut ids: Vec<Value> = Vec::new();
+    let mut delete_entities = Vec::with_capacity(ids.len());
+    for row in rows {
+        let id = row[custom_pk_field].clone();
+        let json = TardisFuns::json.obj_to_string(&row)?;
+        if id.is_string() {
+            ids.push(
+                (*id.as_str().as_ref().ok_or_else(|| {
+                    TardisError::internal_error(
+                        &format!("[Tardis.RelDBClient] The primary key [{id}] in a soft delete operation is not a character type"),
+                        "500-tardis-reldb-id-not-char",
+                    )
+                })?)
+                .to_string()
+                .into(),
+            );
+        } else {
+            ids.push(
+                id.as_u64()
+                    .ok_or_else(|| {
                         TardisError::internal_error(
-                            &format!("[Tardis.RelDBClient] The primary key [{id}] in a soft delete operation is not a character type"),
-                            "500-tardis-reldb-id-not-char",
+                            &format!("[Tardis.RelDBClient] The primary key [{id}] in a soft delete operation is not a number type"),
+                            "500-tardis-reldb-id-not-num",
                         )
-                    })?)
-                    .to_string()
+                    })?
                     .into(),
-                );
-            } else {
-                ids.push(
-                    id.as_u64()
-                        .ok_or_else(|| {
-                            TardisError::internal_error(
-                                &format!("[Tardis.RelDBClient] The primary key [{id}] in a soft delete operation is not a number type"),
-                                "500-tardis-reldb-id-not-num",
-                            )
-                        })?
-                        .into(),
-                );
-            }
-            delete_entities.push(DeleteEntity {
-                entity_name: table_name.to_string(),
-                record_id: id.to_string(),
-                content: json,
-            });
-        }
-        let statement = Statement::from_sql_and_values(
-            db_backend,
-            match db_backend {
-                DatabaseBackend::Postgres => format!(
-                    "DELETE FROM {} WHERE {} IN ({})",
-                    table_name,
-                    custom_pk_field,
-                    ids.iter().enumerate().map(|(idx, _)| format!("${}", idx + 1)).collect::<Vec<String>>().join(",")
-                ),
-                _ => format!(
-                    "DELETE FROM {} WHERE {} IN ({})",
-                    table_name,
-                    custom_pk_field,
-                    ids.iter().map(|_| "?").collect::<Vec<&str>>().join(",")
-                ),
-            }
-            .as_str(),
-            ids,
-        );
-        let result = db.execute(statement).await;
-        match result {
-            Ok(_) => Ok(delete_entities),
-            Err(error) => Err(TardisError::from(error)),
+            );
         }
+        delete_entities.push(DeleteEntity {
+            entity_name: table_name.to_string(),
+            record_id: id.to_string(),
+            content: json,
+        });
+    }
+    let statement = dialect.delete_by_ids(&table_name, custom_pk_field, ids);
+    let result = db.execute(statement).await;
+    match result {
+        Ok(_) => Ok(delete_entities),
+        Err(error) => Err(TardisError::from(error)),
+    }
+}
+
+/// Parse the generated sql, the placeholders are replaced by empty strings
+pub(crate) fn parse_sql(db_backend: DbBackend, sql: &str) -> TardisResult<ast::Statement> {
+    let sql = sql.replace('?', "''");
+    match Parser::parse_sql(
+        match db_backend {
+            DatabaseBackend::MySql => &MySqlDialect {},
+            DatabaseBackend::Postgres => &PostgreSqlDialect {},
+            DatabaseBackend::Sqlite => &SQLiteDialect {},
+        },
+        &sql,
+    )?
+    .pop()
+    {
+        Some(ast) => Ok(ast),
+        None => Err(TardisError::format_error(
+            "[Tardis.RelDBClient] Sql parsing error, no valid Statement found",
+            "406-tardis-reldb-sql-error",
+        )),
     }
 }
 
+/// Escape the wildcards of the `LIKE` pattern by `\` / 使用 `\` 转义 `LIKE` 模式中的通配符
+pub fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// `LIKE` pattern of the values containing the value, the wildcards of which are escaped / 包含该值的 `LIKE` 模式，其中的通配符会被转义
+pub fn contains_like(value: &str) -> LikeExpr {
+    LikeExpr::new(format!("%{}%", escape_like(value))).escape('\\')
+}
+
 /// 对 `ActiveModelBehavior` 的扩展操作
 #[async_trait]
 pub trait TardisActiveModel: ActiveModelBehavior {
@@ -1384,7 +2554,7 @@ pub trait TardisActiveModel: ActiveModelBehavior {
     ///  * `db` -  database instance type / 数据库实例类型
     ///  * `update_time_field` -  update time field / 更新字段
     fn init(db: DbBackend, update_time_field: Option<&str>, compatible_type: CompatibleType) -> (TableCreateStatement, Vec<IndexCreateStatement>, Vec<String>) {
-        let create_table_statement = Self::create_table_statement(db);
+        let create_table_statement = Self::create_compatible_table_statement(db, compatible_type);
         let create_index_statement = Self::create_index_statement();
         if let Some(table_name) = create_table_statement.get_table_name() {
             let table_name = match table_name {
@@ -1453,6 +2623,20 @@ pub trait TardisActiveModel: ActiveModelBehavior {
         TableCreateStatement::new()
     }
 
+    /// Create table of the compatible database / 创建兼容数据库的表
+    ///
+    /// Generated by the `TardisCreateEntity` macro to map the column types by [`TardisRelDBDialect::column_type`], defaults to [`create_table_statement`](Self::create_table_statement).
+    ///
+    /// 由 `TardisCreateEntity` 宏生成，通过 [`TardisRelDBDialect::column_type`] 映射列类型，默认为 [`create_table_statement`](Self::create_table_statement).
+    ///
+    /// # Arguments
+    ///
+    ///  * `db` -  database instance type / 数据库实例类型
+    ///  * `compatible_type` -  compatible database type / 兼容数据库类型
+    fn create_compatible_table_statement(db: DbBackend, _: CompatibleType) -> TableCreateStatement {
+        Self::create_table_statement(db)
+    }
+
     /// Create index / 创建索引
     ///
     /// # Examples
@@ -1474,6 +2658,38 @@ pub trait TardisActiveModel: ActiveModelBehavior {
         vec![]
     }
 
+    /// Version column of the optimistic locking / 乐观锁的版本列
+    ///
+    /// Generated by `#[tardis_entity(version = "...")]` of the `TardisCreateEntity` macro.
+    /// When present, [`TardisRelDBlConnection::update_one`] requires the version read before, updates the record only if the version is unchanged
+    /// and increments it, otherwise returns a `409-tardis-reldb-conflict` error.
+    ///
+    /// 由 `TardisCreateEntity` 宏的 `#[tardis_entity(version = "...")]` 生成.
+    /// 存在时 [`TardisRelDBlConnection::update_one`] 需要传入之前读取的版本，仅在版本未变化时更新记录并递增版本，否则返回 `409-tardis-reldb-conflict` 错误.
+    fn version_column() -> Option<<Self::Entity as EntityTrait>::Column> {
+        None
+    }
+
+    /// Column of the own paths if the rows are isolated by tenant / 按租户隔离行时的所属路径列
+    ///
+    /// Generated by `#[tardis_entity(tenant_scoped)]` of the `TardisCreateEntity` macro, which also registers the table,
+    /// see [`reldb_tenant`](crate::db::reldb_tenant).
+    ///
+    /// 由 `TardisCreateEntity` 宏的 `#[tardis_entity(tenant_scoped)]` 生成，该宏同时注册该表，见 [`reldb_tenant`](crate::db::reldb_tenant).
+    fn own_paths_column() -> Option<&'static str> {
+        None
+    }
+
+    /// Whether the changes are audited / 是否审计变更
+    ///
+    /// Generated by `#[tardis_entity(audited)]` of the `TardisCreateEntity` macro, which also registers the table,
+    /// see [`reldb_audit`](crate::db::reldb_audit).
+    ///
+    /// 由 `TardisCreateEntity` 宏的 `#[tardis_entity(audited)]` 生成，该宏同时注册该表，见 [`reldb_audit`](crate::db::reldb_audit).
+    fn audited() -> bool {
+        false
+    }
+
     /// Create functions / 创建函数
     fn create_function_sqls(db: DbBackend, table_name: &str, update_time_field: Option<&str>, compatible_type: CompatibleType) -> Vec<String> {
         if db == DbBackend::Postgres {
@@ -1532,6 +2748,82 @@ struct CountResp {
     count: i64,
 }
 
+/// Retry policy of the transactions / 事务的重试策略
+///
+/// The serialization failures and deadlocks are retried, i.e. Postgres `40001` / `40P01` and MySQL `1213` / `40001` ,
+/// with an exponential backoff and a random jitter.
+/// They are classified by the SQLSTATE of the database errors and converted to the `409` errors with the [`RETRYABLE_CODE`](Self::RETRYABLE_CODE) locale code.
+///
+/// 序列化失败及死锁会被重试，即Postgres的 `40001` / `40P01` 及MySQL的 `1213` / `40001` ，重试间隔指数增长并带有随机抖动.
+/// 它们按数据库错误的SQLSTATE识别，并转换为编码为 `409` 、语言编码为 [`RETRYABLE_CODE`](Self::RETRYABLE_CODE) 的错误.
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TardisTxRetryPolicy {
+    /// Max number of the retries, default 3 / 最大重试次数，默认 3
+    #[builder(default = 3)]
+    pub max_retries: u32,
+    /// Backoff of the first retry, doubled for each further retry, default 50ms / 首次重试的间隔，之后每次翻倍，默认 50ms
+    #[builder(default = Duration::from_millis(50))]
+    pub initial_backoff: Duration,
+    /// Max backoff of the retries, default 2s / 重试的最大间隔，默认 2s
+    #[builder(default = Duration::from_secs(2))]
+    pub max_backoff: Duration,
+}
+
+impl Default for TardisTxRetryPolicy {
+    fn default() -> Self {
+        TardisTxRetryPolicy::builder().build()
+    }
+}
+
+/// SQLSTATE of the serialization failures and deadlocks, MySQL reports `1213` as `40001`
+#[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
+const RETRYABLE_SQLSTATES: &[&str] = &["40001", "40P01"];
+
+impl TardisTxRetryPolicy {
+    /// Locale code of the serialization failures and deadlocks, the code of which is `409` / 序列化失败及死锁错误的语言编码，其编码为 `409`
+    pub const RETRYABLE_CODE: &'static str = "409-tardis-reldb-retryable";
+
+    /// Whether the error is a serialization failure or deadlock / 是否为序列化失败或死锁错误
+    pub fn is_retryable(error: &TardisError) -> bool {
+        error.locale_code == Self::RETRYABLE_CODE
+    }
+
+    /// Whether the database error is a serialization failure or deadlock
+    fn is_retryable_db_err(error: &DbErr) -> bool {
+        #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
+        if let DbErr::Conn(RuntimeErr::SqlxError(sqlx::Error::Database(error)))
+        | DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(error)))
+        | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(error))) = error
+        {
+            return error.code().is_some_and(|code| RETRYABLE_SQLSTATES.contains(&code.as_ref()));
+        }
+        false
+    }
+
+    fn backoff(&self, retries: u32) -> Duration {
+        let backoff = self.initial_backoff.saturating_mul(2_u32.saturating_pow(retries)).min(self.max_backoff);
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// Max bind parameters of an upsert statement
+const UPSERT_MAX_PARAMS: usize = 65535;
+const UPSERT_MAX_PARAMS_SQLITE: usize = 32766;
+
+/// Primary key value of an active model / 实体模型的主键值
+pub type PrimaryKeyValue<T> = <<<T as ActiveModelTrait>::Entity as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::ValueType;
+
+/// Result of the upsert operations / 插入或更新操作的结果
+#[derive(Clone, Debug)]
+pub struct TardisUpsertResult<K> {
+    /// Number of the affected rows, note that MySQL counts an updated row as 2 / 影响的行数，注意MySQL中更新的行计为2
+    pub rows_affected: u64,
+    /// Primary keys of the inserted or updated rows, only available on the backends supporting `RETURNING` , e.g. Postgres
+    ///
+    /// 插入或更新的行的主键，仅在支持 `RETURNING` 的数据库（如Postgres）中可用
+    pub ids: Option<Vec<K>>,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct DeleteEntity {
     pub entity_name: String,
@@ -1547,6 +2839,9 @@ pub struct IdResp {
 impl From<DbErr> for TardisError {
     fn from(error: DbErr) -> Self {
         error!("[Tardis.RelDBClient] DbErr: {}", error.to_string());
+        if TardisTxRetryPolicy::is_retryable_db_err(&error) {
+            return TardisError::conflict(&format!("[Tardis.RelDBClient] {error:?}"), TardisTxRetryPolicy::RETRYABLE_CODE);
+        }
         TardisError::wrap(&format!("[Tardis.RelDBClient] {error:?}"), "-1-tardis-reldb-error")
     }
 }
@@ -0,0 +1,62 @@
+//! Purge job of the soft deleted records / 软删除记录的清除任务
+//!
+//! For the modules with `del_record.retention_sec` configured, the records of `tardis_del_record` older than the retention
+//! are purged every `del_record.purge_interval_sec` , see [`TardisRelDBlConnection::purge_soft_deleted`](crate::db::reldb_client::TardisRelDBlConnection::purge_soft_deleted).
+//!
+//! 对配置了 `del_record.retention_sec` 的模块，每隔 `del_record.purge_interval_sec` 清除 `tardis_del_record` 中早于保留时长的记录，
+//! 见 [`TardisRelDBlConnection::purge_soft_deleted`](crate::db::reldb_client::TardisRelDBlConnection::purge_soft_deleted).
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::DBConfig;
+
+crate::tardis_static! {
+    purge_jobs: Mutex<Vec<JoinHandle<()>>>;
+}
+
+/// Start the purge jobs, the jobs of the previous config are stopped
+///
+/// The client of each module is resolved before its job is started, the failures of the job are logged.
+pub(crate) fn start_purge_jobs(config: &DBConfig) -> TardisResult<()> {
+    stop_purge_jobs();
+    let mut jobs = purge_jobs().lock().expect("[Tardis.RelDBClient] Purge jobs lock poisoned");
+    let default_code = String::new();
+    for (code, module_config) in std::iter::once((&default_code, &config.default)).chain(config.modules.iter()) {
+        let Some(retention_sec) = module_config.del_record.retention_sec else {
+            continue;
+        };
+        let client = crate::tardis_instance().reldb.get(&code.to_lowercase()).ok_or_else(|| {
+            TardisError::not_found(
+                &format!("[Tardis.RelDBClient] RelDB {code} instance doesn't exist, the soft deleted records can't be purged"),
+                "404-tardis-reldb-module-not-exist",
+            )
+        })?;
+        let code = code.clone();
+        let retention = Duration::from_secs(retention_sec);
+        let interval = Duration::from_secs(module_config.del_record.purge_interval_sec.max(1));
+        jobs.push(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match client.conn().purge_soft_deleted(retention).await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("[Tardis.RelDBClient] Module [{code}] purged {purged} soft deleted records"),
+                    Err(e) => warn!("[Tardis.RelDBClient] Module [{code}] failed to purge soft deleted records: {e}"),
+                }
+            }
+        }));
+    }
+    Ok(())
+}
+
+pub(crate) fn stop_purge_jobs() {
+    for job in purge_jobs().lock().expect("[Tardis.RelDBClient] Purge jobs lock poisoned").drain(..) {
+        job.abort();
+    }
+}
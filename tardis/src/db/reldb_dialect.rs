@@ -0,0 +1,248 @@
+//! SQL dialect of the compatible databases / 兼容数据库的SQL方言
+//!
+//! Databases such as PolarDB for Oracle speak the protocol of a [`DbBackend`] but the SQL of Oracle,
+//! when `compatible_type` of [`DBModuleConfig`](crate::config::config_dto::DBModuleConfig) is `Oracle` :
+//!
+//! * the pagination uses `OFFSET n ROWS FETCH NEXT n ROWS ONLY` instead of `LIMIT n OFFSET n`
+//! * the count quotes its `"count"` column
+//! * the upsert uses `MERGE INTO` instead of `ON CONFLICT` , and the primary keys are not returned
+//! * the soft delete splits the `IN` lists into at most 1000 items
+//! * the tables created by `TardisCreateTable` / `TardisCreateEntity` use the Oracle column types ( `VARCHAR2` , `NUMBER` , `CLOB` , etc.)
+//!
+//! 诸如PolarDB for Oracle的数据库使用 [`DbBackend`] 的协议但使用Oracle的SQL，
+//! 当 [`DBModuleConfig`](crate::config::config_dto::DBModuleConfig) 的 `compatible_type` 为 `Oracle` 时:
+//!
+//! * 分页使用 `OFFSET n ROWS FETCH NEXT n ROWS ONLY` 而不是 `LIMIT n OFFSET n`
+//! * 计数时为 `"count"` 列加上引号
+//! * upsert使用 `MERGE INTO` 而不是 `ON CONFLICT` ，且不返回主键
+//! * 软删除将 `IN` 列表拆分为最多1000项
+//! * 由 `TardisCreateTable` / `TardisCreateEntity` 创建的表使用Oracle的列类型（ `VARCHAR2` 、 `NUMBER` 、 `CLOB` 等）
+use sea_orm::sea_query::{ColumnType, OnConflict, Query, StringLen};
+use sea_orm::{ActiveModelTrait, DbBackend, EntityName, EntityTrait, Iterable, QueryTrait, Statement, Value};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::db::CompatibleType;
+
+/// Max number of the items of an Oracle `IN` list
+const ORACLE_MAX_IN_ITEMS: usize = 1000;
+
+/// Alias of the sub query of the count
+const COUNT_ALIAS: &str = "tardis_count";
+
+/// SQL dialect of a backend and a compatible type / 数据库类型及兼容类型的SQL方言
+///
+/// # Examples
+/// ```ignore
+/// use tardis::config::config_dto::CompatibleType;
+/// use tardis::db::reldb_dialect::TardisRelDBDialect;
+/// use tardis::db::sea_orm::*;
+/// let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::Oracle);
+/// let statement = dialect.paginate(Statement::from_string(DbBackend::Postgres, "SELECT id FROM doc"), 2, 10);
+/// assert_eq!(statement.sql, "SELECT id FROM doc OFFSET 10 ROWS FETCH NEXT 10 ROWS ONLY");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TardisRelDBDialect {
+    backend: DbBackend,
+    compatible_type: CompatibleType,
+}
+
+impl TardisRelDBDialect {
+    pub fn new(backend: DbBackend, compatible_type: CompatibleType) -> Self {
+        TardisRelDBDialect { backend, compatible_type }
+    }
+
+    pub fn backend(&self) -> DbBackend {
+        self.backend
+    }
+
+    pub fn compatible_type(&self) -> CompatibleType {
+        self.compatible_type
+    }
+
+    fn is_oracle(&self) -> bool {
+        self.compatible_type == CompatibleType::Oracle
+    }
+
+    fn placeholder(&self, index: usize) -> String {
+        match self.backend {
+            DbBackend::Postgres => format!("${index}"),
+            _ => "?".to_string(),
+        }
+    }
+
+    /// Whether the upsert returns the primary keys / upsert是否返回主键
+    pub fn support_returning(&self) -> bool {
+        !self.is_oracle() && self.backend.support_returning()
+    }
+
+    /// Add the pagination to the query / 为查询添加分页
+    ///
+    /// # Arguments
+    ///
+    ///  * `statement` - Statement of the query / 查询的Statement
+    ///  * `page_number` -  Current page number, starting from 1 / 当前页码，从1开始
+    ///  * `page_size` -  Number of records per page / 每页记录数
+    pub fn paginate(&self, statement: Statement, page_number: u64, page_size: u64) -> Statement {
+        let offset = page_number.saturating_sub(1) * page_size;
+        let sql = if self.is_oracle() {
+            format!("{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", statement.sql, offset, page_size)
+        } else {
+            format!("{} LIMIT {} OFFSET {}", statement.sql, page_size, offset)
+        };
+        Statement { sql, ..statement }
+    }
+
+    /// Limit the number of the records of the query / 限制查询的记录数
+    pub fn limit(&self, statement: Statement, limit: u64) -> Statement {
+        let sql = if self.is_oracle() {
+            format!("{} FETCH FIRST {} ROWS ONLY", statement.sql, limit)
+        } else {
+            format!("{} LIMIT {}", statement.sql, limit)
+        };
+        Statement { sql, ..statement }
+    }
+
+    /// Count the records of the query, the result column is `count` / 统计查询的记录数，结果列为 `count`
+    pub fn count(&self, statement: Statement) -> Statement {
+        let sql = if self.is_oracle() {
+            format!("SELECT COUNT(1) AS \"count\" FROM ( {} ) {COUNT_ALIAS}", statement.sql)
+        } else {
+            format!("SELECT COUNT(1) AS count FROM ( {} ) {COUNT_ALIAS}", statement.sql)
+        };
+        Statement { sql, ..statement }
+    }
+
+    /// Insert the models, or update the `update_columns` of the existing records with the same `conflict_columns` / 插入模型，或更新 `conflict_columns` 相同的已有记录的 `update_columns`
+    ///
+    /// The models must set the same columns. The `conflict_columns` are returned if `returning` is true and [`support_returning`](Self::support_returning).
+    ///
+    /// 模型必须设置相同的列. 如果 `returning` 为true且 [`support_returning`](Self::support_returning) 则返回 `conflict_columns` .
+    pub fn upsert<A>(
+        &self,
+        models: Vec<A>,
+        conflict_columns: &[<A::Entity as EntityTrait>::Column],
+        update_columns: &[<A::Entity as EntityTrait>::Column],
+        returning: bool,
+    ) -> TardisResult<Statement>
+    where
+        A: ActiveModelTrait,
+    {
+        if self.is_oracle() {
+            return self.merge(models, conflict_columns, update_columns);
+        }
+        let on_conflict = OnConflict::columns(conflict_columns.to_vec()).update_columns(update_columns.to_vec()).to_owned();
+        let mut insert = QueryTrait::into_query(A::Entity::insert_many(models).on_conflict(on_conflict));
+        if returning && self.support_returning() {
+            insert.returning(Query::returning().columns(conflict_columns.to_vec()));
+        }
+        Ok(self.backend.build(&insert))
+    }
+
+    fn merge<A>(&self, models: Vec<A>, conflict_columns: &[<A::Entity as EntityTrait>::Column], update_columns: &[<A::Entity as EntityTrait>::Column]) -> TardisResult<Statement>
+    where
+        A: ActiveModelTrait,
+    {
+        let Some(first) = models.first() else {
+            return Err(TardisError::bad_request(
+                "[Tardis.RelDBClient] The models to be upserted are empty",
+                "400-tardis-reldb-upsert-empty",
+            ));
+        };
+        let columns = <A::Entity as EntityTrait>::Column::iter().filter(|column| !first.get(*column).is_not_set()).collect::<Vec<_>>();
+        let quote = |column: &<A::Entity as EntityTrait>::Column| format!("\"{}\"", column.to_string());
+        let mut values = Vec::with_capacity(models.len() * columns.len());
+        let mut rows = Vec::with_capacity(models.len());
+        for model in models {
+            let mut row = Vec::with_capacity(columns.len());
+            for column in &columns {
+                let Some(value) = model.get(*column).into_value() else {
+                    return Err(TardisError::bad_request(
+                        "[Tardis.RelDBClient] The models to be upserted must set the same columns",
+                        "400-tardis-reldb-upsert-columns-mismatch",
+                    ));
+                };
+                values.push(value);
+                row.push(format!("{} AS {}", self.placeholder(values.len()), quote(column)));
+            }
+            rows.push(format!("SELECT {} FROM DUAL", row.join(", ")));
+        }
+        let on = conflict_columns.iter().map(|column| format!("\"target\".{} = \"source\".{}", quote(column), quote(column))).collect::<Vec<_>>().join(" AND ");
+        // the columns of the ON clause can't be updated
+        let update = update_columns
+            .iter()
+            .filter(|column| !conflict_columns.contains(column))
+            .map(|column| format!("\"target\".{} = \"source\".{}", quote(column), quote(column)))
+            .collect::<Vec<_>>();
+        let mut sql = format!(
+            "MERGE INTO \"{}\" \"target\" USING ({}) \"source\" ON ({})",
+            A::Entity::default().table_name(),
+            rows.join(" UNION ALL "),
+            on
+        );
+        if !update.is_empty() {
+            sql.push_str(&format!(" WHEN MATCHED THEN UPDATE SET {}", update.join(", ")));
+        }
+        sql.push_str(&format!(
+            " WHEN NOT MATCHED THEN INSERT ({}) VALUES ({})",
+            columns.iter().map(quote).collect::<Vec<_>>().join(", "),
+            columns.iter().map(|column| format!("\"source\".{}", quote(column))).collect::<Vec<_>>().join(", ")
+        ));
+        Ok(Statement::from_sql_and_values(self.backend, sql, values))
+    }
+
+    /// Delete the records by the primary keys / 按主键删除记录
+    ///
+    /// # Arguments
+    ///
+    ///  * `table_name` - Name of the table / 表名
+    ///  * `pk_field` - Primary key field / 主键字段
+    ///  * `ids` - Primary keys of the records / 记录的主键
+    pub fn delete_by_ids(&self, table_name: &str, pk_field: &str, ids: Vec<Value>) -> Statement {
+        let placeholders = (1..=ids.len()).map(|idx| self.placeholder(idx)).collect::<Vec<_>>();
+        let condition = if self.is_oracle() && placeholders.len() > ORACLE_MAX_IN_ITEMS {
+            format!(
+                "({})",
+                placeholders.chunks(ORACLE_MAX_IN_ITEMS).map(|chunk| format!("{} IN ({})", pk_field, chunk.join(","))).collect::<Vec<_>>().join(" OR ")
+            )
+        } else {
+            format!("{} IN ({})", pk_field, placeholders.join(","))
+        };
+        Statement::from_sql_and_values(self.backend, format!("DELETE FROM {table_name} WHERE {condition}"), ids)
+    }
+
+    /// Map the column type to the compatible database / 将列类型映射为兼容数据库的类型
+    ///
+    /// The types without an equivalent, e.g. the arrays of Postgres, are kept.
+    ///
+    /// 没有对应类型的列类型（如Postgres的数组）保持不变.
+    pub fn column_type(&self, column_type: ColumnType) -> ColumnType {
+        if !self.is_oracle() {
+            return column_type;
+        }
+        let oracle_type = match &column_type {
+            ColumnType::Char(len) => format!("CHAR({})", len.unwrap_or(1)),
+            ColumnType::String(StringLen::N(len)) => format!("VARCHAR2({len})"),
+            ColumnType::String(_) => "VARCHAR2(4000)".to_string(),
+            ColumnType::Text | ColumnType::Json | ColumnType::JsonBinary => "CLOB".to_string(),
+            ColumnType::TinyInteger | ColumnType::TinyUnsigned => "NUMBER(3)".to_string(),
+            ColumnType::SmallInteger | ColumnType::SmallUnsigned => "NUMBER(5)".to_string(),
+            ColumnType::Integer | ColumnType::Unsigned => "NUMBER(10)".to_string(),
+            ColumnType::BigInteger => "NUMBER(19)".to_string(),
+            ColumnType::BigUnsigned => "NUMBER(20)".to_string(),
+            ColumnType::Boolean => "NUMBER(1)".to_string(),
+            ColumnType::Decimal(Some((precision, scale))) | ColumnType::Money(Some((precision, scale))) => format!("NUMBER({precision}, {scale})"),
+            ColumnType::Decimal(None) | ColumnType::Money(None) => "NUMBER".to_string(),
+            ColumnType::Float => "BINARY_FLOAT".to_string(),
+            ColumnType::Double => "BINARY_DOUBLE".to_string(),
+            ColumnType::Date => "DATE".to_string(),
+            ColumnType::Time => "INTERVAL DAY(0) TO SECOND".to_string(),
+            ColumnType::DateTime | ColumnType::Timestamp => "TIMESTAMP".to_string(),
+            ColumnType::TimestampWithTimeZone => "TIMESTAMP WITH TIME ZONE".to_string(),
+            ColumnType::Blob | ColumnType::Binary(_) | ColumnType::VarBinary(_) => "BLOB".to_string(),
+            ColumnType::Uuid => "VARCHAR2(36)".to_string(),
+            _ => return column_type,
+        };
+        ColumnType::custom(oracle_type)
+    }
+}
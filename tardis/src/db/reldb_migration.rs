@@ -0,0 +1,592 @@
+//! Schema migration / 数据库结构迁移
+//!
+//! `create_table_from_entity` and [`TardisActiveModel::init`](crate::db::reldb_client::TardisActiveModel::init) never alter an existing table,
+//! [`TardisMigrator`] applies ordered, checksummed migrations instead and tracks them in the `tardis_migration` table.
+//!
+//! `create_table_from_entity` 及 [`TardisActiveModel::init`](crate::db::reldb_client::TardisActiveModel::init) 不会修改已存在的表，
+//! [`TardisMigrator`] 按顺序执行带校验和的迁移，并在 `tardis_migration` 表中记录.
+//!
+//! # Migration files / 迁移文件
+//!
+//! ```text
+//! migrations/
+//! ├── V1__create_todo.sql            # up script of version 1 / 版本1的升级脚本
+//! ├── V2__add_todo_owner.sql
+//! ├── V2__add_todo_owner.mysql.sql   # takes precedence on MySQL / 在MySQL上优先使用
+//! ├── U2__add_todo_owner.sql         # rollback script of version 2 / 版本2的回滚脚本
+//! └── U2__add_todo_owner.sqlite.sql  # takes precedence on SQLite / 在SQLite上优先使用
+//! ```
+//!
+//! The backend suffix is one of `mysql` , `postgres` and `sqlite` , the `_` in the description are replaced by spaces.
+//!
+//! 数据库后缀为 `mysql` 、 `postgres` 及 `sqlite` 之一，描述中的 `_` 会被替换为空格.
+//!
+//! # Startup / 启动时迁移
+//!
+//! When [`DBMigrationConfig::enabled`] is set, the migrations of the `dir` and the ones registered by [`TardisMigrator::register`]
+//! are applied to each DB module on startup.
+//!
+//! 设置 [`DBMigrationConfig::enabled`] 后，启动时会对各数据库模块执行 `dir` 中的迁移及通过 [`TardisMigrator::register`] 注册的迁移.
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use regex::Regex;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, QueryFilter, QueryOrder, Set};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::basic::error::TardisError;
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::db::DBMigrationConfig;
+use crate::config::config_dto::component::DBConfig;
+use crate::db::domain::tardis_db_config;
+use crate::db::domain::tardis_db_migration;
+use crate::db::reldb_client::{TardisRelDBClient, TardisRelDBlConnection};
+use crate::TardisFuns;
+
+const LOCK_KEY: &str = "__tardis_migration_lock__";
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+type MigrationFn = Arc<dyn for<'a> Fn(&'a TardisRelDBlConnection) -> BoxFuture<'a, TardisResult<()>> + Send + Sync>;
+
+crate::tardis_static! {
+    registered_migrations: RwLock<HashMap<String, Vec<TardisMigration>>>;
+    migration_file_regex: Regex = Regex::new(r"^(?P<kind>[VU])(?P<version>\d+)__(?P<description>.+?)(\.(?P<backend>mysql|postgres|sqlite))?\.sql$").expect("[Tardis.RelDBClient] Invalid migration file regex");
+}
+
+#[derive(Clone)]
+enum MigrationAction {
+    Sql { default: Option<String>, backends: Vec<(DbBackend, String)> },
+    Func(MigrationFn),
+}
+
+impl MigrationAction {
+    fn sql(&self, backend: DbBackend) -> Option<&str> {
+        match self {
+            MigrationAction::Sql { default, backends } => backends.iter().find(|(b, _)| *b == backend).map(|(_, sql)| sql.as_str()).or(default.as_deref()),
+            MigrationAction::Func(_) => None,
+        }
+    }
+
+    fn set_sql(&mut self, backend: Option<DbBackend>, sql: String) {
+        if let MigrationAction::Func(_) = self {
+            *self = MigrationAction::Sql {
+                default: None,
+                backends: Vec::new(),
+            };
+        }
+        if let MigrationAction::Sql { default, backends } = self {
+            match backend {
+                Some(backend) => {
+                    backends.retain(|(b, _)| *b != backend);
+                    backends.push((backend, sql));
+                }
+                None => *default = Some(sql),
+            }
+        }
+    }
+
+    async fn run(&self, backend: DbBackend, conn: &TardisRelDBlConnection) -> TardisResult<()> {
+        match self {
+            MigrationAction::Sql { .. } => {
+                let sql = self.sql(backend).ok_or_else(|| {
+                    TardisError::not_found(
+                        &format!("[Tardis.RelDBClient] Migration script for {backend:?} doesn't exist"),
+                        "404-tardis-reldb-migration-script-not-exist",
+                    )
+                })?;
+                conn.raw_tx()?.execute_unprepared(sql).await?;
+                Ok(())
+            }
+            MigrationAction::Func(func) => func(conn).await,
+        }
+    }
+}
+
+/// A versioned migration / 版本化的迁移
+///
+/// # Examples
+/// ```ignore
+/// use tardis::db::reldb_migration::TardisMigration;
+/// use tardis::db::sea_orm::DbBackend;
+///
+/// TardisMigration::sql(1, "create todo", "CREATE TABLE todo (id INT PRIMARY KEY, title VARCHAR(255) NOT NULL)")
+///     .rollback_sql("DROP TABLE todo");
+/// TardisMigration::sql(2, "add todo owner", "ALTER TABLE todo ADD COLUMN owner VARCHAR(255) NOT NULL DEFAULT ''")
+///     .rollback_sql("ALTER TABLE todo DROP COLUMN owner")
+///     .rollback_sql_for(DbBackend::Sqlite, "CREATE TABLE todo_bak AS SELECT id, title FROM todo; DROP TABLE todo; ALTER TABLE todo_bak RENAME TO todo");
+/// TardisMigration::func(3, "seed todo", |conn| {
+///     Box::pin(async move {
+///         conn.execute_one("INSERT INTO todo (id, title) VALUES (1, 'first')", vec![]).await?;
+///         Ok(())
+///     })
+/// });
+/// ```
+#[derive(Clone)]
+pub struct TardisMigration {
+    /// Version, the migrations are applied in ascending order / 版本，迁移按升序执行
+    pub version: i64,
+    /// Description / 描述
+    pub description: String,
+    up: MigrationAction,
+    down: Option<MigrationAction>,
+}
+
+impl std::fmt::Debug for TardisMigration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TardisMigration").field("version", &self.version).field("description", &self.description).field("rollback", &self.down.is_some()).finish()
+    }
+}
+
+impl TardisMigration {
+    /// Create a migration from a SQL script / 从SQL脚本创建迁移
+    ///
+    /// The script may contain several statements separated by `;` .
+    ///
+    /// 脚本可以包含以 `;` 分隔的多条语句.
+    pub fn sql(version: i64, description: impl Into<String>, sql: impl Into<String>) -> Self {
+        TardisMigration {
+            version,
+            description: description.into(),
+            up: MigrationAction::Sql {
+                default: Some(sql.into()),
+                backends: Vec::new(),
+            },
+            down: None,
+        }
+    }
+
+    /// Create a migration from a closure / 从闭包创建迁移
+    ///
+    /// The checksum of a closure only covers the version and the description.
+    ///
+    /// 闭包的校验和只包含版本及描述.
+    pub fn func<F>(version: i64, description: impl Into<String>, func: F) -> Self
+    where
+        F: for<'a> Fn(&'a TardisRelDBlConnection) -> BoxFuture<'a, TardisResult<()>> + Send + Sync + 'static,
+    {
+        TardisMigration {
+            version,
+            description: description.into(),
+            up: MigrationAction::Func(Arc::new(func)),
+            down: None,
+        }
+    }
+
+    /// Set the up script of a backend, which takes precedence over the default one / 设置某个数据库的升级脚本，优先于默认脚本
+    pub fn sql_for(mut self, backend: DbBackend, sql: impl Into<String>) -> Self {
+        self.up.set_sql(Some(backend), sql.into());
+        self
+    }
+
+    /// Set the rollback script / 设置回滚脚本
+    pub fn rollback_sql(mut self, sql: impl Into<String>) -> Self {
+        self.down
+            .get_or_insert_with(|| MigrationAction::Sql {
+                default: None,
+                backends: Vec::new(),
+            })
+            .set_sql(None, sql.into());
+        self
+    }
+
+    /// Set the rollback script of a backend, which takes precedence over the default one / 设置某个数据库的回滚脚本，优先于默认脚本
+    pub fn rollback_sql_for(mut self, backend: DbBackend, sql: impl Into<String>) -> Self {
+        self.down
+            .get_or_insert_with(|| MigrationAction::Sql {
+                default: None,
+                backends: Vec::new(),
+            })
+            .set_sql(Some(backend), sql.into());
+        self
+    }
+
+    /// Set the rollback closure / 设置回滚闭包
+    pub fn rollback_func<F>(mut self, func: F) -> Self
+    where
+        F: for<'a> Fn(&'a TardisRelDBlConnection) -> BoxFuture<'a, TardisResult<()>> + Send + Sync + 'static,
+    {
+        self.down = Some(MigrationAction::Func(Arc::new(func)));
+        self
+    }
+
+    /// Checksum of the up script on the backend / 在该数据库上升级脚本的校验和
+    pub fn checksum(&self, backend: DbBackend) -> String {
+        let content = match &self.up {
+            MigrationAction::Sql { .. } => self.up.sql(backend).unwrap_or_default().replace("\r\n", "\n"),
+            MigrationAction::Func(_) => format!("{}:{}", self.version, self.description),
+        };
+        hex::encode(Sha256::digest(content.trim().as_bytes()))
+    }
+
+    /// Load the migrations from the SQL files of the directory / 从目录的SQL文件中加载迁移
+    pub fn load_dir(dir: impl AsRef<Path>) -> TardisResult<Vec<TardisMigration>> {
+        let dir = dir.as_ref();
+        let mut entries = std::fs::read_dir(dir)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<_>>();
+        entries.sort();
+        let mut migrations: Vec<TardisMigration> = Vec::new();
+        let mut rollbacks = Vec::new();
+        for path in entries {
+            let Some(captures) = path.file_name().and_then(|name| name.to_str()).and_then(|name| migration_file_regex().captures(name)) else {
+                continue;
+            };
+            let version = captures["version"].parse::<i64>().map_err(|_| {
+                TardisError::format_error(
+                    &format!("[Tardis.RelDBClient] Invalid migration version of {}", path.display()),
+                    "406-tardis-reldb-migration-invalid",
+                )
+            })?;
+            let description = captures["description"].replace('_', " ");
+            let backend = captures.name("backend").map(|backend| match backend.as_str() {
+                "mysql" => DbBackend::MySql,
+                "postgres" => DbBackend::Postgres,
+                _ => DbBackend::Sqlite,
+            });
+            let sql = std::fs::read_to_string(&path)?;
+            if &captures["kind"] == "U" {
+                rollbacks.push((version, backend, sql, path));
+                continue;
+            }
+            match migrations.iter_mut().find(|migration| migration.version == version) {
+                Some(migration) => migration.up.set_sql(backend, sql),
+                None => {
+                    let mut up = MigrationAction::Sql {
+                        default: None,
+                        backends: Vec::new(),
+                    };
+                    up.set_sql(backend, sql);
+                    migrations.push(TardisMigration {
+                        version,
+                        description,
+                        up,
+                        down: None,
+                    });
+                }
+            }
+        }
+        for (version, backend, sql, path) in rollbacks {
+            let migration = migrations.iter_mut().find(|migration| migration.version == version).ok_or_else(|| {
+                TardisError::not_found(
+                    &format!("[Tardis.RelDBClient] Migration of the rollback script {} doesn't exist", path.display()),
+                    "404-tardis-reldb-migration-not-exist",
+                )
+            })?;
+            migration
+                .down
+                .get_or_insert_with(|| MigrationAction::Sql {
+                    default: None,
+                    backends: Vec::new(),
+                })
+                .set_sql(backend, sql);
+        }
+        Ok(migrations)
+    }
+}
+
+/// Schema migration runner / 数据库结构迁移执行器
+///
+/// The migrations are applied in ascending order of the versions, each in a transaction
+/// (MySQL commits the DDL statements implicitly, so a failed migration may be partially applied there).
+/// An applied migration whose checksum has changed, or a pending migration older than the applied ones, fails the migration.
+///
+/// 迁移按版本升序执行，每个迁移在一个事务中执行（MySQL会隐式提交DDL语句，失败的迁移可能被部分执行）.
+/// 已执行迁移的校验和发生变化，或待执行的迁移早于已执行的迁移时，迁移失败.
+///
+/// Only one instance migrates at a time, the lock is kept in the `tardis_config` table and refreshed while the migrations run.
+///
+/// 同一时间只有一个实例执行迁移，锁保存在 `tardis_config` 表中，执行迁移期间会持续刷新.
+///
+/// # Examples
+/// ```ignore
+/// use tardis::db::reldb_migration::{TardisMigration, TardisMigrator};
+/// use tardis::TardisFuns;
+///
+/// let migrator = TardisMigrator::new(TardisMigration::load_dir("migrations")?);
+/// println!("{}", migrator.dry_run(&TardisFuns::reldb()).await?);
+/// migrator.migrate(&TardisFuns::reldb()).await?;
+/// migrator.rollback(&TardisFuns::reldb(), 1).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TardisMigrator {
+    migrations: Vec<TardisMigration>,
+    lock_timeout: Duration,
+}
+
+impl TardisMigrator {
+    pub fn new(mut migrations: Vec<TardisMigration>) -> Self {
+        migrations.sort_by_key(|migration| migration.version);
+        TardisMigrator {
+            migrations,
+            lock_timeout: Duration::from_secs(DBMigrationConfig::default().lock_timeout_sec),
+        }
+    }
+
+    /// Set the lock timeout, a lock not refreshed within it is taken over / 设置锁超时时间，超过该时间未刷新的锁会被接管
+    pub fn lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
+    /// Register the migrations of a DB module applied on startup / 注册启动时对数据库模块执行的迁移
+    ///
+    /// The default module code is `""` .
+    ///
+    /// 默认模块的编码为 `""` .
+    pub fn register(module_code: &str, migrations: impl IntoIterator<Item = TardisMigration>) {
+        registered_migrations().write().expect("[Tardis.RelDBClient] Migration registry lock poisoned").entry(module_code.to_string()).or_default().extend(migrations);
+    }
+
+    /// Create the migrator of a DB module from the registered migrations and the migration files / 从注册的迁移及迁移文件创建数据库模块的迁移执行器
+    pub fn by_module(module_code: &str, config: &DBMigrationConfig) -> TardisResult<Self> {
+        let mut migrations = registered_migrations().read().expect("[Tardis.RelDBClient] Migration registry lock poisoned").get(module_code).cloned().unwrap_or_default();
+        if let Some(dir) = &config.dir {
+            migrations.extend(TardisMigration::load_dir(dir)?);
+        }
+        Ok(TardisMigrator::new(migrations).lock_timeout(Duration::from_secs(config.lock_timeout_sec)))
+    }
+
+    /// Get the applied migrations / 获取已执行的迁移
+    pub async fn applied(&self, client: &TardisRelDBClient) -> TardisResult<Vec<tardis_db_migration::Model>> {
+        Ok(tardis_db_migration::Entity::find().order_by_asc(tardis_db_migration::Column::Version).all(client.conn().raw_conn()).await?)
+    }
+
+    /// Get the pending migrations and the plan, nothing is applied / 获取待执行的迁移及执行计划，不执行任何迁移
+    ///
+    /// The basic tables are created if missing.
+    ///
+    /// 如果基础表不存在，会创建基础表.
+    pub async fn dry_run(&self, client: &TardisRelDBClient) -> TardisResult<String> {
+        client.init_basic_tables().await?;
+        let backend = client.backend();
+        let pending = self.pending(backend, &self.applied(client).await?)?;
+        let mut plan = format!("-- {} pending migration(s) on {backend:?}\n", pending.len());
+        for migration in pending {
+            plan.push_str(&format!(
+                "-- V{} {} (checksum: {})\n",
+                migration.version,
+                migration.description,
+                migration.checksum(backend)
+            ));
+            match migration.up.sql(backend) {
+                Some(sql) => {
+                    plan.push_str(sql.trim());
+                    plan.push('\n');
+                }
+                None => plan.push_str("-- <rust closure>\n"),
+            }
+        }
+        info!("[Tardis.RelDBClient] Migration plan:\n{plan}");
+        Ok(plan)
+    }
+
+    /// Apply the pending migrations, return the applied versions / 执行待执行的迁移，返回执行的版本
+    pub async fn migrate(&self, client: &TardisRelDBClient) -> TardisResult<Vec<i64>> {
+        client.init_basic_tables().await?;
+        self.locked(client, self.migrate_locked(client)).await
+    }
+
+    async fn migrate_locked(&self, client: &TardisRelDBClient) -> TardisResult<Vec<i64>> {
+        let backend = client.backend();
+        let pending = self.pending(backend, &self.applied(client).await?)?;
+        let mut versions = Vec::with_capacity(pending.len());
+        for migration in pending {
+            info!("[Tardis.RelDBClient] Migrating to version {} : {}", migration.version, migration.description);
+            let started = Instant::now();
+            let mut conn = client.conn();
+            conn.begin().await?;
+            migration.up.run(backend, &conn).await?;
+            tardis_db_migration::ActiveModel {
+                version: Set(migration.version),
+                description: Set(migration.description.clone()),
+                checksum: Set(migration.checksum(backend)),
+                execution_ms: Set(started.elapsed().as_millis() as i64),
+                create_time: Set(Utc::now()),
+            }
+            .insert(conn.raw_tx()?)
+            .await?;
+            conn.commit().await?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    /// Roll back the applied migrations newer than the target version, return the rolled back versions / 回滚新于目标版本的已执行迁移，返回回滚的版本
+    pub async fn rollback(&self, client: &TardisRelDBClient, target_version: i64) -> TardisResult<Vec<i64>> {
+        client.init_basic_tables().await?;
+        self.locked(client, self.rollback_locked(client, target_version)).await
+    }
+
+    async fn rollback_locked(&self, client: &TardisRelDBClient, target_version: i64) -> TardisResult<Vec<i64>> {
+        let backend = client.backend();
+        let mut versions = Vec::new();
+        for applied in self.applied(client).await?.into_iter().rev().filter(|applied| applied.version > target_version) {
+            let migration = self.migrations.iter().find(|migration| migration.version == applied.version).ok_or_else(|| {
+                TardisError::not_found(
+                    &format!("[Tardis.RelDBClient] Migration of version {} doesn't exist", applied.version),
+                    "404-tardis-reldb-migration-not-exist",
+                )
+            })?;
+            let down = migration.down.as_ref().ok_or_else(|| {
+                TardisError::not_found(
+                    &format!("[Tardis.RelDBClient] Rollback of version {} doesn't exist", applied.version),
+                    "404-tardis-reldb-migration-rollback-not-exist",
+                )
+            })?;
+            info!("[Tardis.RelDBClient] Rolling back version {} : {}", migration.version, migration.description);
+            let mut conn = client.conn();
+            conn.begin().await?;
+            down.run(backend, &conn).await?;
+            tardis_db_migration::Entity::delete_by_id(migration.version).exec(conn.raw_tx()?).await?;
+            conn.commit().await?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    /// Verify the applied migrations and return the pending ones
+    fn pending(&self, backend: DbBackend, applied: &[tardis_db_migration::Model]) -> TardisResult<Vec<&TardisMigration>> {
+        if let Some(migration) = self.migrations.windows(2).find(|pair| pair[0].version == pair[1].version) {
+            return Err(TardisError::format_error(
+                &format!("[Tardis.RelDBClient] Duplicate migration version {}", migration[0].version),
+                "406-tardis-reldb-migration-invalid",
+            ));
+        }
+        for applied in applied {
+            match self.migrations.iter().find(|migration| migration.version == applied.version) {
+                Some(migration) if migration.checksum(backend) != applied.checksum => {
+                    return Err(TardisError::conflict(
+                        &format!("[Tardis.RelDBClient] Checksum of the applied migration {} has changed", applied.version),
+                        "409-tardis-reldb-migration-checksum-mismatch",
+                    ));
+                }
+                Some(_) => {}
+                None => warn!("[Tardis.RelDBClient] Applied migration {} : {} doesn't exist locally", applied.version, applied.description),
+            }
+        }
+        let latest = applied.iter().map(|applied| applied.version).max();
+        let pending = self.migrations.iter().filter(|migration| applied.iter().all(|applied| applied.version != migration.version)).collect::<Vec<_>>();
+        if let (Some(latest), Some(migration)) = (latest, pending.first()) {
+            if migration.version < latest {
+                return Err(TardisError::format_error(
+                    &format!("[Tardis.RelDBClient] Pending migration {} is older than the applied version {latest}", migration.version),
+                    "406-tardis-reldb-migration-out-of-order",
+                ));
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Run the work holding the migration lock
+    ///
+    /// The lock is refreshed while the work runs, the work is cancelled (and its transaction rolled back) if the lock is taken over.
+    /// A failure to release the lock is only logged, it's taken over once stale.
+    async fn locked<T>(&self, client: &TardisRelDBClient, work: impl Future<Output = TardisResult<T>>) -> TardisResult<T> {
+        let owner = self.lock(client).await?;
+        let result = tokio::select! {
+            result = work => result,
+            error = self.heartbeat(client, &owner) => Err(error),
+        };
+        if let Err(error) = self.unlock(client, &owner).await {
+            warn!("[Tardis.RelDBClient] Failed to release the migration lock {owner} : {error:?}");
+        }
+        result
+    }
+
+    /// Acquire the migration lock, wait until it's released or stale
+    ///
+    /// The lock row is written directly instead of through the data dictionary, so no change events or cache invalidations are published for it.
+    async fn lock(&self, client: &TardisRelDBClient) -> TardisResult<String> {
+        let owner = TardisFuns::field.nanoid();
+        loop {
+            let conn = client.conn();
+            let now = Utc::now();
+            let error = match tardis_db_config::Entity::insert(tardis_db_config::ActiveModel {
+                k: Set(LOCK_KEY.to_string()),
+                v: Set(owner.clone()),
+                creator: Set("tardis".to_string()),
+                updater: Set("tardis".to_string()),
+                create_time: Set(now),
+                update_time: Set(now),
+            })
+            .exec(conn.raw_conn())
+            .await
+            {
+                Ok(_) => return Ok(owner),
+                Err(error) => error,
+            };
+            let Some(lock) = tardis_db_config::Entity::find_by_id(LOCK_KEY).one(conn.raw_conn()).await? else {
+                // released in between
+                if error.sql_err().is_some() {
+                    continue;
+                }
+                return Err(error.into());
+            };
+            if now.signed_duration_since(lock.update_time).to_std().is_ok_and(|idle| idle > self.lock_timeout) {
+                warn!("[Tardis.RelDBClient] Taking over the stale migration lock held by {}", lock.v);
+                self.unlock(client, &lock.v).await?;
+                continue;
+            }
+            info!("[Tardis.RelDBClient] Waiting for the migration lock held by {}", lock.v);
+            tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Refresh the migration lock until it's lost, a failed refresh is retried on the next tick
+    async fn heartbeat(&self, client: &TardisRelDBClient, owner: &str) -> TardisError {
+        let mut interval = tokio::time::interval((self.lock_timeout / 3).max(LOCK_RETRY_INTERVAL));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let refreshed = tardis_db_config::Entity::update_many()
+                .col_expr(tardis_db_config::Column::UpdateTime, Expr::value(Utc::now()))
+                .filter(tardis_db_config::Column::K.eq(LOCK_KEY))
+                .filter(tardis_db_config::Column::V.eq(owner))
+                .exec(client.conn().raw_conn())
+                .await;
+            match refreshed {
+                Ok(result) if result.rows_affected == 0 => {
+                    return TardisError::conflict(
+                        &format!("[Tardis.RelDBClient] Migration lock {owner} has been taken over"),
+                        "409-tardis-reldb-migration-lock-lost",
+                    );
+                }
+                Ok(_) => {}
+                Err(error) => warn!("[Tardis.RelDBClient] Failed to refresh the migration lock {owner} : {error:?}"),
+            }
+        }
+    }
+
+    async fn unlock(&self, client: &TardisRelDBClient, owner: &str) -> TardisResult<()> {
+        tardis_db_config::Entity::delete_many()
+            .filter(tardis_db_config::Column::K.eq(LOCK_KEY))
+            .filter(tardis_db_config::Column::V.eq(owner))
+            .exec(client.conn().raw_conn())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Apply the migrations of the DB modules whose migration is enabled
+pub(crate) async fn migrate_on_startup(config: &DBConfig) -> TardisResult<()> {
+    let default_code = String::new();
+    for (code, module_config) in std::iter::once((&default_code, &config.default)).chain(config.modules.iter()) {
+        if !module_config.migration.enabled {
+            continue;
+        }
+        let migrator = TardisMigrator::by_module(code, &module_config.migration)?;
+        let client = TardisFuns::reldb_by_module(code);
+        if module_config.migration.dry_run {
+            migrator.dry_run(&client).await?;
+        } else {
+            let versions = migrator.migrate(&client).await?;
+            info!("[Tardis.RelDBClient] Module [{code}] migrated versions {versions:?}");
+        }
+    }
+    Ok(())
+}
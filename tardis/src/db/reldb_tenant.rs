@@ -0,0 +1,240 @@
+//! Multi-tenant row isolation / 多租户行隔离
+//!
+//! The rows of a tenant-scoped table belong to the tenant whose `own_paths` is a prefix of the row's,
+//! after [`TardisRelDBlConnection::with_ctx`](crate::db::reldb_client::TardisRelDBlConnection::with_ctx) the connection appends
+//! `own_paths LIKE 'ctx.own_paths%'` predicates of the tenant-scoped tables to the `SelectStatement` , `UpdateStatement` and soft delete operations.
+//!
+//! 租户隔离表的行属于 `own_paths` 为该行前缀的租户，
+//! 调用 [`TardisRelDBlConnection::with_ctx`](crate::db::reldb_client::TardisRelDBlConnection::with_ctx) 后，连接会对 `SelectStatement` 、 `UpdateStatement` 及软删除操作
+//! 追加租户隔离表的 `own_paths LIKE 'ctx.own_paths%'` 条件.
+//!
+//! The table of an entity marked with `#[tardis_entity(tenant_scoped)]` is registered by the `TardisCreateEntity` derive itself,
+//! no matter whether the table is created by [`TardisActiveModel::init`] , `create_table_from_entity` or a migration.
+//! Other tables are registered by [`TardisRelDBClient::register_tenant_table`] .
+//! Only the tables of the top level `FROM` and `JOIN` clauses are scoped, the raw SQL operations ( `*_by_sql` , `execute_one` , etc.) are not.
+//!
+//! 标记了 `#[tardis_entity(tenant_scoped)]` 的实体的表由 `TardisCreateEntity` 宏自身注册，
+//! 无论该表是通过 [`TardisActiveModel::init`] 、 `create_table_from_entity` 还是迁移创建的. 其它表通过 [`TardisRelDBClient::register_tenant_table`] 注册.
+//! 只有顶层 `FROM` 及 `JOIN` 子句中的表会被隔离，原生SQL操作（ `*_by_sql` 、 `execute_one` 等）不会被隔离.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use sea_orm::sea_query::{Alias, Expr, LikeExpr, SelectStatement, SimpleExpr, UpdateStatement};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DbBackend, EntityName, EntityTrait, QueryFilter, Select};
+use sqlparser::ast::{self, SetExpr, TableFactor};
+
+use crate::basic::result::TardisResult;
+use crate::db::domain::tardis_db_del_record;
+use crate::db::reldb_client::{escape_like, parse_sql, TardisActiveModel, TardisRelDBClient};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Capacity of the parsed statement cache, the cache is cleared when it's full
+const PARSED_RELATIONS_CAPACITY: usize = 1024;
+
+/// Tables of the top level `FROM` and `JOIN` clauses: the table, its qualifier and whether it's joined
+type Relations = Arc<Vec<(String, String, bool)>>;
+
+crate::tardis_static! {
+    tenant_tables: RwLock<HashMap<String, String>> = RwLock::new(
+        inventory::iter::<TenantTable>
+            .into_iter()
+            .filter_map(|tenant_table| Some(((tenant_table.table_name)(), (tenant_table.own_paths_column)()?.to_string())))
+            .collect(),
+    );
+    parsed_relations: RwLock<HashMap<String, Relations>>;
+}
+
+/// Tenant-scoped table submitted by the `TardisCreateEntity` derive
+#[doc(hidden)]
+pub struct TenantTable {
+    table_name: fn() -> String,
+    own_paths_column: fn() -> Option<&'static str>,
+}
+
+impl TenantTable {
+    pub const fn of<A: TardisActiveModel>() -> Self {
+        TenantTable {
+            table_name: entity_table_name::<A>,
+            own_paths_column: A::own_paths_column,
+        }
+    }
+}
+
+inventory::collect!(TenantTable);
+
+fn entity_table_name<A: TardisActiveModel>() -> String {
+    <A as ActiveModelTrait>::Entity::default().table_name().to_string()
+}
+
+impl TardisRelDBClient {
+    /// Register a tenant-scoped table / 注册租户隔离的表
+    ///
+    /// # Arguments
+    ///
+    ///  * `table_name` - name of the table / 表名
+    ///  * `own_paths_column` - column of the own paths / 所属路径列
+    pub fn register_tenant_table(table_name: &str, own_paths_column: &str) {
+        tenant_tables().write().expect("[Tardis.RelDBClient] Tenant table registry lock poisoned").insert(table_name.to_string(), own_paths_column.to_string());
+    }
+}
+
+pub(crate) fn own_paths_column(table_name: &str) -> Option<String> {
+    tenant_tables().read().expect("[Tardis.RelDBClient] Tenant table registry lock poisoned").get(table_name).cloned()
+}
+
+fn own_paths_like(own_paths: &str) -> LikeExpr {
+    LikeExpr::new(format!("{}%", escape_like(own_paths))).escape('\\')
+}
+
+fn table_name(relation: &TableFactor) -> Option<(String, String)> {
+    if let TableFactor::Table { name, alias, .. } = relation {
+        let table = name.0.last().and_then(ast::ObjectNamePart::as_ident)?.value.clone();
+        let qualifier = alias.as_ref().map(|alias| alias.name.value.clone()).unwrap_or_else(|| table.clone());
+        return Some((table, qualifier));
+    }
+    None
+}
+
+/// Get the top level tables of the statement, the parsed statements are cached by their SQL
+pub(crate) fn relations(sql: &str, backend: DbBackend) -> TardisResult<Relations> {
+    if let Some(relations) = parsed_relations().read().expect("[Tardis.RelDBClient] Parsed statement cache lock poisoned").get(sql) {
+        return Ok(relations.clone());
+    }
+    let relations = match parse_sql(backend, sql)? {
+        ast::Statement::Query(query) => match query.body.as_ref() {
+            SetExpr::Select(select) => select
+                .from
+                .iter()
+                .flat_map(|from| std::iter::once((&from.relation, false)).chain(from.joins.iter().map(|join| (&join.relation, true))))
+                .filter_map(|(relation, joined)| table_name(relation).map(|(table, qualifier)| (table, qualifier, joined)))
+                .collect(),
+            _ => Vec::new(),
+        },
+        ast::Statement::Update { table, .. } => table_name(&table.relation).map(|(table, qualifier)| vec![(table, qualifier, false)]).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    let relations = Arc::new(relations);
+    let mut parsed_relations = parsed_relations().write().expect("[Tardis.RelDBClient] Parsed statement cache lock poisoned");
+    if parsed_relations.len() >= PARSED_RELATIONS_CAPACITY {
+        parsed_relations.clear();
+    }
+    parsed_relations.insert(sql.to_string(), relations.clone());
+    Ok(relations)
+}
+
+/// Append the own paths predicates of the tenant-scoped tables in the `FROM` and `JOIN` clauses
+///
+/// The joined tables also match missing rows of the outer joins.
+pub(crate) fn scope_select<'a>(select_statement: &'a SelectStatement, own_paths: &str, backend: DbBackend) -> TardisResult<Cow<'a, SelectStatement>> {
+    if tenant_tables().read().expect("[Tardis.RelDBClient] Tenant table registry lock poisoned").is_empty() {
+        return Ok(Cow::Borrowed(select_statement));
+    }
+    let mut predicates: Vec<SimpleExpr> = Vec::new();
+    for (table, qualifier, joined) in relations(&backend.build(select_statement).sql, backend)?.iter() {
+        let Some(column) = own_paths_column(table) else {
+            continue;
+        };
+        let column = Expr::col((Alias::new(qualifier), Alias::new(column)));
+        predicates.push(if *joined {
+            column.clone().like(own_paths_like(own_paths)).or(column.is_null())
+        } else {
+            column.like(own_paths_like(own_paths))
+        });
+    }
+    if predicates.is_empty() {
+        return Ok(Cow::Borrowed(select_statement));
+    }
+    let mut select_statement = select_statement.clone();
+    for predicate in predicates {
+        select_statement.and_where(predicate);
+    }
+    Ok(Cow::Owned(select_statement))
+}
+
+/// Append the own paths predicate of the tenant-scoped table to be updated
+pub(crate) fn scope_update<'a>(update_statement: &'a UpdateStatement, own_paths: &str, backend: DbBackend) -> TardisResult<Cow<'a, UpdateStatement>> {
+    if tenant_tables().read().expect("[Tardis.RelDBClient] Tenant table registry lock poisoned").is_empty() {
+        return Ok(Cow::Borrowed(update_statement));
+    }
+    match relations(&backend.build(update_statement).sql, backend)?.first().and_then(|(table, _, _)| own_paths_column(table)) {
+        Some(column) => {
+            let mut update_statement = update_statement.clone();
+            update_statement.and_where(Expr::col(Alias::new(column)).like(own_paths_like(own_paths)));
+            Ok(Cow::Owned(update_statement))
+        }
+        None => Ok(Cow::Borrowed(update_statement)),
+    }
+}
+
+/// Append the own paths predicate of the soft deleted records
+///
+/// The own paths of a tenant-scoped record are read from its JSON content, the records of the other tables are kept.
+pub(crate) fn scope_del_records(select: &mut SelectStatement, own_paths: &str, backend: DbBackend) {
+    let tenant_tables = tenant_tables().read().expect("[Tardis.RelDBClient] Tenant table registry lock poisoned").clone();
+    if tenant_tables.is_empty() {
+        return;
+    }
+    let mut predicate = Expr::col(tardis_db_del_record::Column::EntityName).is_not_in(tenant_tables.keys().cloned());
+    for (table, column) in tenant_tables {
+        let record_own_paths = match backend {
+            DbBackend::MySql => Expr::cust_with_values("JSON_UNQUOTE(JSON_EXTRACT(content, ?))", [format!("$.\"{column}\"")]),
+            DbBackend::Postgres => Expr::cust_with_values("(content::json ->> $1)", [column]),
+            DbBackend::Sqlite => Expr::cust_with_values("json_extract(content, ?)", [format!("$.\"{column}\"")]),
+        };
+        predicate = predicate.or(Expr::col(tardis_db_del_record::Column::EntityName).eq(table).and(Expr::expr(record_own_paths).like(own_paths_like(own_paths))));
+    }
+    select.and_where(predicate);
+}
+
+/// Append the own paths predicate of the tenant-scoped entity / 追加租户隔离实体的所属路径条件
+///
+/// The column is taken from [`TardisActiveModel::own_paths_column`] of the entity, the select is unchanged if the entity is not tenant-scoped.
+///
+/// 所属路径列取自实体的 [`TardisActiveModel::own_paths_column`] ，如果实体不是租户隔离实体则不做修改.
+pub fn scope_entity_select<E>(select: Select<E>, own_paths: &str) -> Select<E>
+where
+    E: EntityTrait,
+    E::ActiveModel: TardisActiveModel,
+{
+    match <E::ActiveModel as TardisActiveModel>::own_paths_column() {
+        Some(column) => select.filter(Expr::col((E::default(), Alias::new(column))).like(own_paths_like(own_paths))),
+        None => select,
+    }
+}
+
+/// Predicate of the records within the own paths / 所属路径内记录的条件
+///
+/// The value of the column starts with `own_paths` , the wildcards of which are escaped.
+///
+/// 列的值以 `own_paths` 开头，其中的通配符会被转义.
+pub fn own_paths_predicate<C>(column: C, own_paths: &str) -> SimpleExpr
+where
+    C: ColumnTrait,
+{
+    column.like(own_paths_like(own_paths))
+}
+
+/// Append the own paths predicate of the tenant-scoped table of the entity to the condition
+pub(crate) fn scope_table_condition<E>(condition: Condition, own_paths: &str) -> Condition
+where
+    E: EntityTrait,
+{
+    match own_paths_column(E::default().table_name()) {
+        Some(column) => condition.add(Expr::col((E::default(), Alias::new(column))).like(own_paths_like(own_paths))),
+        None => condition,
+    }
+}
+
+/// Append the own paths predicate of the tenant-scoped table of the entity
+pub(crate) fn scope_table_select<E>(select: Select<E>, own_paths: &str) -> Select<E>
+where
+    E: EntityTrait,
+{
+    match own_paths_column(E::default().table_name()) {
+        Some(column) => select.filter(Expr::col((E::default(), Alias::new(column))).like(own_paths_like(own_paths))),
+        None => select,
+    }
+}
@@ -0,0 +1,150 @@
+//! Tracing of the relational database queries / 关系型数据库查询的追踪
+//!
+//! The spans of [`TardisRelDBlConnection`](crate::db::reldb_client::TardisRelDBlConnection) carry the attributes of the
+//! [OpenTelemetry semantic conventions](https://opentelemetry.io/docs/specs/semconv/database/) :
+//! `db.system` , `db.statement` (the SQL with the string and numeric literals redacted) and `db.response.returned_rows` .
+//!
+//! [`TardisRelDBlConnection`](crate::db::reldb_client::TardisRelDBlConnection) 的 span 携带 OpenTelemetry 语义约定的属性：
+//! `db.system` 、 `db.statement` （字符串及数字字面量脱敏后的SQL）及 `db.response.returned_rows` .
+//!
+//! The statements exceeding [`DBSlowQueryConfig::threshold_ms`] are logged at the warn level, with the query plan captured by `EXPLAIN`
+//! if [`DBSlowQueryConfig::explain`] is enabled. The bound values are never logged, only their number.
+//!
+//! 超过 [`DBSlowQueryConfig::threshold_ms`] 的语句以 warn 级别输出，如果启用了 [`DBSlowQueryConfig::explain`] 则同时输出 `EXPLAIN` 获取的查询计划.
+//! 绑定的参数值不会被输出，只输出其个数.
+use std::time::Duration;
+
+use sea_orm::{DatabaseConnection, DbBackend, FromQueryResult, JsonValue, Statement};
+use tracing::{warn, Span};
+
+use crate::config::config_dto::component::db::DBSlowQueryConfig;
+
+/// Value of the `db.system` attribute / `db.system` 属性的值
+pub fn db_system(backend: DbBackend) -> &'static str {
+    match backend {
+        DbBackend::MySql => "mysql",
+        DbBackend::Postgres => "postgresql",
+        DbBackend::Sqlite => "sqlite",
+    }
+}
+
+/// Redact the string and numeric literals of the SQL / 脱敏SQL中的字符串及数字字面量
+///
+/// The quoted identifiers, the digits of the identifiers and the `$1` placeholders are kept.
+///
+/// 带引号的标识符、标识符中的数字及 `$1` 占位符保持不变.
+///
+/// # Examples
+/// ```
+/// use tardis::db::reldb_trace::redact_sql;
+/// assert_eq!(
+///     redact_sql("SELECT \"name\" FROM doc2 WHERE name = 'it''s' AND size > 1.5 AND id = $1"),
+///     "SELECT \"name\" FROM doc2 WHERE name = '?' AND size > ? AND id = $1"
+/// );
+/// ```
+pub fn redact_sql(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                redacted.push_str("'?'");
+                while let Some(c) = chars.next() {
+                    match c {
+                        // escaped quote
+                        '\'' if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                        }
+                        '\\' => {
+                            chars.next();
+                        }
+                        '\'' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '"' | '`' => {
+                redacted.push(c);
+                for quoted in chars.by_ref() {
+                    redacted.push(quoted);
+                    if quoted == c {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_digit() && !redacted.ends_with(|prev: char| prev.is_alphanumeric() || prev == '_' || prev == '$') => {
+                redacted.push('?');
+                let mut last = c;
+                while let Some(&next) = chars.peek() {
+                    // decimals, exponents and hexadecimals, e.g. `1.5` , `1e-5` and `0x1f`
+                    if next.is_ascii_alphanumeric() || next == '.' || next == '_' || (matches!(next, '+' | '-') && matches!(last, 'e' | 'E')) {
+                        last = next;
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            _ => redacted.push(c),
+        }
+    }
+    redacted
+}
+
+/// Record the number of returned rows on the current span
+pub(crate) fn record_returned_rows(rows: usize) {
+    Span::current().record("db.response.returned_rows", rows);
+}
+
+/// Install the callback recording the span attributes and logging the slow queries
+pub(crate) fn trace(con: &mut DatabaseConnection, slow_query: &DBSlowQueryConfig) {
+    let threshold = slow_query.threshold_ms.map(Duration::from_millis);
+    // the explain statements are executed by a connection without the callback
+    let explain_con = if slow_query.explain { Some(con.clone()) } else { None };
+    con.set_metric_callback(move |info| {
+        let sql = redact_sql(&info.statement.sql);
+        let span = Span::current();
+        span.record("db.system", db_system(info.statement.db_backend));
+        span.record("db.statement", sql.as_str());
+        let Some(threshold) = threshold else {
+            return;
+        };
+        if info.elapsed < threshold {
+            return;
+        }
+        let params = info.statement.values.as_ref().map(|values| values.0.len()).unwrap_or(0);
+        warn!(
+            "[Tardis.RelDBClient] Slow query took {}ms (threshold {}ms), failed:{}, sql: {}, params: {} redacted",
+            info.elapsed.as_millis(),
+            threshold.as_millis(),
+            info.failed,
+            sql,
+            params
+        );
+        if let Some(explain_con) = &explain_con {
+            explain(explain_con.clone(), info.statement.clone(), sql);
+        }
+    });
+}
+
+/// Capture the query plan of the statement in the background
+fn explain(con: DatabaseConnection, statement: Statement, sql: String) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let keyword = statement.sql.split_whitespace().next().unwrap_or_default().to_uppercase();
+    if !["SELECT", "WITH", "INSERT", "UPDATE", "DELETE"].contains(&keyword.as_str()) {
+        return;
+    }
+    let explain_sql = match statement.db_backend {
+        DbBackend::Sqlite => format!("EXPLAIN QUERY PLAN {}", statement.sql),
+        DbBackend::MySql | DbBackend::Postgres => format!("EXPLAIN {}", statement.sql),
+    };
+    let explain_statement = Statement { sql: explain_sql, ..statement };
+    runtime.spawn(async move {
+        match JsonValue::find_by_statement(explain_statement).all(&con).await {
+            Ok(plan) => warn!("[Tardis.RelDBClient] Slow query plan of sql: {}, plan: {}", sql, JsonValue::Array(plan)),
+            Err(error) => warn!("[Tardis.RelDBClient] Slow query plan of sql: {} is not available: {}", sql, error),
+        }
+    });
+}
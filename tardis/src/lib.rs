@@ -9,6 +9,7 @@ extern crate core;
 extern crate lazy_static;
 
 use std::collections::HashMap;
+use std::future::Future;
 
 use std::{any::Any, sync::Arc};
 
@@ -25,8 +26,12 @@ pub use futures_util;
 pub use lru;
 #[cfg(feature = "web-server-grpc")]
 pub use poem_grpc;
+#[cfg(feature = "metrics")]
+pub use prometheus;
 pub use rand;
 pub use regex;
+#[cfg(feature = "conf-schema")]
+pub use schemars;
 pub use serde;
 use serde::Deserialize;
 pub use serde_json;
@@ -43,16 +48,20 @@ use basic::tracing::TardisTracing;
 pub use paste;
 #[cfg(feature = "tardis-macros")]
 #[cfg(any(feature = "reldb-postgres", feature = "reldb-mysql", feature = "reldb-sqlite"))]
-pub use tardis_macros::{TardisCreateEntity, TardisCreateIndex, TardisCreateTable, TardisEmptyBehavior, TardisEmptyRelation};
+pub use tardis_macros::{TardisCreateEntity, TardisCreateIndex, TardisCreateTable, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation};
 pub use tracing;
 pub use url;
 
 use crate::basic::field::TardisField;
 use crate::basic::json::TardisJson;
+use crate::basic::lifecycle::TardisLifecycle;
 use crate::basic::uri::TardisUri;
 #[cfg(feature = "cache")]
 use crate::cache::cache_client::TardisCacheClient;
+#[cfg(feature = "cluster")]
+use crate::cluster::cluster_processor::TardisCluster;
 use crate::config::config_dto::{FrameworkConfig, TardisConfig};
+use crate::config::config_notifier::TardisConfigNotifier;
 #[cfg(feature = "reldb-core")]
 use crate::db::domain::tardis_db_config::TardisDataDict;
 #[cfg(feature = "reldb-core")]
@@ -170,8 +179,10 @@ use crate::web::web_server::TardisWebServer;
 pub struct TardisFuns {
     custom_config: TardisComponentMap<CachedJsonValue>,
     framework_config: TardisComponent<FrameworkConfig>,
+    config_notifier: TardisConfigNotifier,
     components: basic::component::ComponentStore,
     pub(crate) tracing: TardisComponent<TardisTracing>,
+    lifecycle: TardisLifecycle,
     #[cfg(feature = "reldb-core")]
     reldb: TardisComponentMap<TardisRelDBClient>,
     #[cfg(feature = "web-server")]
@@ -188,6 +199,8 @@ pub struct TardisFuns {
     mail: TardisComponentMap<TardisMailClient>,
     #[cfg(feature = "os")]
     os: TardisComponentMap<TardisOSClient>,
+    #[cfg(feature = "cluster")]
+    cluster: TardisComponent<TardisCluster>,
 }
 
 tardis_static! {
@@ -243,6 +256,11 @@ impl TardisFuns {
     ///
     /// 本函数不需要配置文件，直接使用rust对象实例初始化.
     ///
+    /// The configuration is checked by [`TardisConfig::check`] before any component is initialized,
+    /// which fails only if `fw.adv.strict_validation` is enabled.
+    ///
+    /// 在初始化任何组件前通过 [`TardisConfig::check`] 检查配置，仅在启用 `fw.adv.strict_validation` 时失败.
+    ///
     /// # Arguments
     ///
     /// * `conf` - configuration object instance / 配置对象实例
@@ -269,11 +287,14 @@ impl TardisFuns {
     /// .await?;
     /// ```
     pub async fn init_conf(conf: TardisConfig) -> TardisResult<()> {
+        // report the misconfigured sections before any component is initialized
+        conf.check()?;
         let custom_config = conf.cs.iter().map(|(k, v)| (k.clone(), CachedJsonValue::new(v.clone()))).collect::<HashMap<_, _>>();
         tardis_instance().custom_config.replace_inner(custom_config);
         tardis_instance().framework_config.set(conf.fw);
         #[allow(unused_variables)]
         let fw_conf = TardisFuns::fw_config();
+        tardis_instance().lifecycle.mark_started();
         if let Some(log_config) = &fw_conf.log {
             tardis_instance().tracing.get().update_config(log_config)?;
         }
@@ -281,6 +302,8 @@ impl TardisFuns {
         {
             if let Some(db_config) = &fw_conf.db {
                 tardis_instance().reldb.init_by(db_config).await?;
+                db::reldb_migration::migrate_on_startup(db_config).await?;
+                db::reldb_del_record::start_purge_jobs(db_config)?;
             }
         }
         #[cfg(feature = "web-server")]
@@ -288,6 +311,10 @@ impl TardisFuns {
             if let Some(_web_server_config) = &fw_conf.web_server {
                 tracing::info!("initialize web server");
                 let web_server = TardisWebServer::init_by_conf(&fw_conf)?;
+                #[cfg(feature = "cluster")]
+                if fw_conf.cluster.is_some() {
+                    TardisCluster::mount_global(&web_server).await;
+                }
                 // take out previous webserver first, because TARDIS_INST is not send and can't live cross an `await` point
                 let inherit = tardis_instance().web_server.get();
                 if inherit.is_running().await {
@@ -313,6 +340,15 @@ impl TardisFuns {
                 tardis_instance().cache.init_by(cache_config).await?;
             }
         }
+        #[cfg(feature = "cluster")]
+        {
+            if fw_conf.cluster.is_some() {
+                tracing::info!("initialize cluster");
+                let cluster = TardisCluster::init_by_conf(&fw_conf).await?;
+                let old_cluster = tardis_instance().cluster.replace(cluster);
+                old_cluster.shutdown().await?;
+            }
+        }
         #[cfg(feature = "mq")]
         {
             if let Some(mq_config) = &fw_conf.mq {
@@ -337,7 +373,69 @@ impl TardisFuns {
                 tardis_instance().os.init_by(os_config).await?;
             }
         }
-        Ok(())
+        tardis_instance().config_notifier.notify();
+        if fw_conf.lifecycle.handle_signal {
+            tardis_instance().lifecycle.listen_signal();
+        }
+        tardis_instance().lifecycle.run_startup_hooks().await
+    }
+
+    /// Register a startup hook / 注册启动钩子
+    ///
+    /// Startup hooks run at the end of [init_conf](Self::init_conf), after all components are initialized,
+    /// a failed hook aborts the initialization.
+    ///
+    /// 启动钩子在 [init_conf](Self::init_conf) 完成所有组件初始化后执行，任一钩子失败将中止初始化.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - hook name, used in logs / 钩子名称，用于日志
+    /// * `priority` - hooks with a lower priority run first, hooks with the same priority run in registration order
+    ///   / 优先级值小的钩子先执行，相同优先级的钩子按注册顺序执行
+    /// * `hook` - the hook / 钩子
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// TardisFuns::on_startup("warm_up", 0, || async { Ok(()) });
+    /// ```
+    pub fn on_startup<F, T>(name: &str, priority: i32, hook: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        tardis_instance().lifecycle.add_startup_hook(name, priority, hook)
+    }
+
+    /// Register a shutdown hook / 注册关闭钩子
+    ///
+    /// Shutdown hooks run at the beginning of [shutdown](Self::shutdown), before the components are closed,
+    /// failed hooks are logged and don't stop the shutdown. All hooks and the draining of the web requests and mq deliveries
+    /// share the `fw.lifecycle.grace_timeout_sec`.
+    ///
+    /// 关闭钩子在 [shutdown](Self::shutdown) 开始时、关闭组件之前执行，失败的钩子仅记录日志而不会中止关闭.
+    /// 所有钩子与Web请求及MQ消息的等待共享 `fw.lifecycle.grace_timeout_sec` 的宽限时间.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - hook name, used in logs / 钩子名称，用于日志
+    /// * `priority` - hooks with a lower priority run first, hooks with the same priority run in registration order
+    ///   / 优先级值小的钩子先执行，相同优先级的钩子按注册顺序执行
+    /// * `hook` - the hook / 钩子
+    pub fn on_shutdown<F, T>(name: &str, priority: i32, hook: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: Future<Output = TardisResult<()>> + Send + 'static,
+    {
+        tardis_instance().lifecycle.add_shutdown_hook(name, priority, hook)
+    }
+
+    /// Wait until [shutdown](Self::shutdown) finished, E.g. the shutdown triggered by `SIGTERM` when `fw.lifecycle.handle_signal` is enabled
+    ///
+    /// 等待 [shutdown](Self::shutdown) 完成，例如在启用 `fw.lifecycle.handle_signal` 时由 `SIGTERM` 触发的关闭
+    pub async fn wait_for_shutdown() {
+        tardis_instance().lifecycle.wait_shutdown().await
     }
 
     /// Build single Module by the specified code / 通过指定的 code 构造单模块实例
@@ -402,6 +500,95 @@ impl TardisFuns {
         panic!("[Tardis.Config] Custom Config [{code}] or [] doesn't exist");
     }
 
+    /// Watch the custom configuration object / 监听自定义配置对象
+    ///
+    /// The receiver is notified after [init_conf](Self::init_conf) and [hot_reload](Self::hot_reload)
+    /// only when the configuration of `code` actually changed, values failed to be deserialized as `T` are skipped and logged.
+    ///
+    /// 仅当 `code` 对应的配置确实变更时，接收者才会在 [init_conf](Self::init_conf) 及 [hot_reload](Self::hot_reload) 后得到通知，
+    /// 无法反序列化为 `T` 的值会被跳过并记录日志.
+    ///
+    /// # Panic
+    /// Same as [cs_config](Self::cs_config).
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let mut receiver = TardisFuns::watch_cs_config::<ModuleConfig>("m1");
+    /// tokio::spawn(async move {
+    ///     while receiver.changed().await.is_ok() {
+    ///         let config = receiver.borrow_and_update().clone();
+    ///     }
+    /// });
+    /// ```
+    pub fn watch_cs_config<T: 'static + for<'a> Deserialize<'a> + Any + Send + Sync>(code: &str) -> tokio::sync::watch::Receiver<Arc<T>> {
+        let code = code.to_lowercase();
+        let (tx, rx) = tokio::sync::watch::channel(Self::cs_config::<T>(&code));
+        let last = std::sync::Mutex::new(Self::cs_config_value(&code).map(|value| value.raw().clone()));
+        tardis_instance().config_notifier.subscribe(move || {
+            if tx.is_closed() {
+                return false;
+            }
+            let Some(value) = Self::cs_config_value(&code) else {
+                return true;
+            };
+            let mut last = last.lock().expect("[Tardis.Config] Custom config watcher lock poisoned");
+            if last.as_ref() == Some(value.raw()) {
+                return true;
+            }
+            match value.get::<T>() {
+                Ok(config) => {
+                    *last = Some(value.raw().clone());
+                    tx.send_replace(config);
+                }
+                Err(e) => tracing::error!("[Tardis.Config] Custom Config [{code}] type conversion error {e}"),
+            }
+            true
+        });
+        rx
+    }
+
+    /// The custom configuration of `code`, fallback to the default one
+    fn cs_config_value(code: &str) -> Option<Arc<CachedJsonValue>> {
+        let conf = &tardis_instance().custom_config;
+        conf.get(code).or_else(|| conf.get(""))
+    }
+
+    /// Watch a section of the Tardis configuration object / 监听Tardis配置对象的某个部分
+    ///
+    /// The receiver is notified after [init_conf](Self::init_conf) and [hot_reload](Self::hot_reload)
+    /// only when the section selected by `select` actually changed.
+    ///
+    /// 仅当 `select` 选取的部分确实变更时，接收者才会在 [init_conf](Self::init_conf) 及 [hot_reload](Self::hot_reload) 后得到通知.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// let mut receiver = TardisFuns::watch_fw_config(|fw| fw.log.clone());
+    /// ```
+    pub fn watch_fw_config<T, F>(select: F) -> tokio::sync::watch::Receiver<Arc<T>>
+    where
+        T: PartialEq + Send + Sync + 'static,
+        F: Fn(&FrameworkConfig) -> T + Send + Sync + 'static,
+    {
+        let (tx, rx) = tokio::sync::watch::channel(Arc::new(select(&Self::fw_config())));
+        tardis_instance().config_notifier.subscribe(move || {
+            if tx.is_closed() {
+                return false;
+            }
+            let section = select(&Self::fw_config());
+            tx.send_if_modified(|current| {
+                if **current == section {
+                    return false;
+                }
+                *current = Arc::new(section);
+                true
+            });
+            true
+        });
+        rx
+    }
+
     /// Get default language in the custom configuration / 从自定义配置中获取默认语言
     pub fn default_lang() -> Option<String> {
         tardis_instance().framework_config.get().app.default_lang.clone()
@@ -571,6 +758,10 @@ impl TardisFuns {
 
     #[cfg(feature = "reldb-core")]
     pub fn reldb_by_module(code: &str) -> Arc<TardisRelDBClient> {
+        #[cfg(all(feature = "test", feature = "reldb-sqlite"))]
+        if let Some(client) = test::test_reldb::current() {
+            return client;
+        }
         let code = code.to_lowercase();
         let code = code.as_str();
         tardis_instance().reldb.get(code).unwrap_or_else(|| panic!("[Tardis.Config] RelDB {code} instance doesn't exist"))
@@ -578,6 +769,10 @@ impl TardisFuns {
 
     #[cfg(feature = "reldb-core")]
     pub fn reldb_by_module_or_default(code: &str) -> Arc<TardisRelDBClient> {
+        #[cfg(all(feature = "test", feature = "reldb-sqlite"))]
+        if let Some(client) = test::test_reldb::current() {
+            return client;
+        }
         let code = code.to_lowercase();
         let code = code.as_str();
         tardis_instance().reldb.get(code).unwrap_or_else(Self::reldb)
@@ -592,6 +787,28 @@ impl TardisFuns {
         tardis_instance().web_server.get().into()
     }
 
+    /// Use the cluster feature / 使用集群功能
+    ///
+    /// This feature needs to be enabled #[cfg(feature = "cluster")] .
+    ///
+    /// 本功能需要启用 #[cfg(feature = "cluster")] .
+    ///
+    /// # Steps to use / 使用步骤
+    ///
+    /// 1. Initialize the cluster and web server configuration / 初始化集群及Web服务配置 @see [init](Self::init)
+    /// 2. Call this function to publish or request events between nodes / 调用本函数完成节点间的事件发布及请求
+    ///
+    /// ```ignore
+    /// use tardis::TardisFuns;
+    /// use tardis::cluster::cluster_processor::ClusterEventTarget;
+    /// TardisFuns::cluster().subscribe(EchoSubscriber);
+    /// TardisFuns::cluster().publish_event("echo", json!("hi"), ClusterEventTarget::Broadcast).await?;
+    /// ```
+    #[cfg(feature = "cluster")]
+    pub fn cluster() -> Arc<TardisCluster> {
+        tardis_instance().cluster.get()
+    }
+
     /// Use the web  client feature / 使用web客户端功能
     ///
     /// This feature needs to be enabled #[cfg(feature = "web-client")] .
@@ -843,11 +1060,104 @@ impl TardisFuns {
         tardis_instance().os.get(code).unwrap_or_else(Self::os)
     }
 
+    /// Prometheus metrics of the framework components / 框架组件的Prometheus指标
+    ///
+    /// See [`basic::metrics`] for the recorded metrics. / 记录的指标见 [`basic::metrics`] .
+    #[cfg(feature = "metrics")]
+    pub fn metrics() -> &'static basic::metrics::TardisMetrics {
+        basic::metrics::tardis_metrics()
+    }
+
+    /// Check the health of all initialized components / 检查所有已初始化组件的健康状态
+    ///
+    /// Modules are checked concurrently, each check times out after [`HEALTH_CHECK_TIMEOUT`](crate::basic::health::HEALTH_CHECK_TIMEOUT).
+    ///
+    /// 各模块并发检查，单个检查超时时间为 [`HEALTH_CHECK_TIMEOUT`](crate::basic::health::HEALTH_CHECK_TIMEOUT) .
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let report = TardisFuns::health().await;
+    /// if !report.is_up() {
+    ///     println!("{}", TardisFuns::json.obj_to_string(&report).unwrap());
+    /// }
+    /// ```
+    pub async fn health() -> basic::health::TardisHealthReport {
+        #[allow(unused_mut)]
+        let mut report = basic::health::TardisHealthReport::default();
+        #[cfg(feature = "reldb-core")]
+        {
+            // release the read lock before awaiting
+            let modules = tardis_instance().reldb.read().clone();
+            report.check_component("reldb", modules).await;
+        }
+        #[cfg(feature = "cache")]
+        {
+            let modules = tardis_instance().cache.read().clone();
+            report.check_component("cache", modules).await;
+        }
+        #[cfg(feature = "mq")]
+        {
+            let modules = tardis_instance().mq.read().clone();
+            report.check_component("mq", modules).await;
+        }
+        #[cfg(feature = "web-client")]
+        {
+            let modules = tardis_instance().search.read().clone();
+            report.check_component("search", modules).await;
+        }
+        #[cfg(feature = "mail")]
+        {
+            let modules = tardis_instance().mail.read().clone();
+            report.check_component("mail", modules).await;
+        }
+        #[cfg(feature = "os")]
+        {
+            let modules = tardis_instance().os.read().clone();
+            report.check_component("os", modules).await;
+        }
+        report
+    }
+
     /// # Parameters
     /// - `clean: bool`: if use clean mode, it will cleanup all user setted configs like webserver modules
     async fn shutdown_internal(#[allow(unused_variables)] clean: bool) -> TardisResult<()> {
         tracing::info!("[Tardis] Shutdown...");
-        // using a join set to collect async task, because `&TARDIS_INST` is not `Send`
+        #[cfg(feature = "conf-watch")]
+        crate::config::config_watcher::unwatch();
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(TardisFuns::fw_config().lifecycle.grace_timeout_sec);
+        // 1. shutdown hooks, components are still available here
+        tardis_instance().lifecycle.run_shutdown_hooks(deadline).await;
+        // 2. stop accepting work and drain in-flight web requests and mq deliveries
+        #[cfg(feature = "cluster")]
+        {
+            let cluster = tardis_instance().cluster.replace(TardisCluster::default());
+            if let Err(e) = cluster.shutdown().await {
+                tracing::error!("[Tardis] Encounter an error while shutting down cluster: {}", e);
+            }
+        }
+        #[cfg(feature = "web-server")]
+        {
+            let web_server = tardis_instance().web_server.get();
+            if web_server.is_running().await {
+                // the hooks have taken part of the grace timeout
+                if let Err(e) = web_server.shutdown_within(deadline.saturating_duration_since(tokio::time::Instant::now())).await {
+                    tracing::error!("[Tardis] Encounter an error while shutting down webserver: {}", e);
+                }
+            }
+        }
+        #[cfg(feature = "mq")]
+        {
+            let mq = tardis_instance().mq.drain();
+            for (code, client) in mq {
+                if let Err(e) = client.drain(deadline.saturating_duration_since(tokio::time::Instant::now())).await {
+                    tracing::error!("[Tardis] Encounter an error while draining MQClient [{code}]: {}", e);
+                }
+                if let Err(e) = client.close().await {
+                    tracing::error!("[Tardis] Encounter an error while shutting down MQClient [{code}]: {}", e);
+                }
+            }
+        }
+        // 3. close connections
         #[cfg(feature = "web-client")]
         tardis_instance().web_client.clear();
         #[cfg(feature = "cache")]
@@ -860,30 +1170,20 @@ impl TardisFuns {
         // connection will be closed by drop calling
         // see: https://www.sea-ql.org/SeaORM/docs/install-and-config/connection/
         #[cfg(feature = "reldb-core")]
-        tardis_instance().reldb.clear();
-        #[cfg(feature = "mq")]
-        {
-            let mq = tardis_instance().mq.drain();
-            for (code, client) in mq {
-                if let Err(e) = client.close().await {
-                    tracing::error!("[Tardis] Encounter an error while shutting down MQClient [{code}]: {}", e);
-                }
-            }
-        }
-        #[cfg(feature = "web-server")]
         {
-            let web_server = tardis_instance().web_server.get();
-            if web_server.is_running().await {
-                if let Err(e) = web_server.shutdown().await {
-                    tracing::error!("[Tardis] Encounter an error while shutting down webserver: {}", e);
-                }
-            }
+            db::reldb_del_record::stop_purge_jobs();
+            tardis_instance().reldb.clear();
         }
+        tardis_instance().lifecycle.mark_shutdown();
         tracing::info!("[Tardis] Shutdown finished");
         Ok(())
     }
 
     /// shutdown totally
+    ///
+    /// Runs the shutdown hooks, drains in-flight web requests and mq deliveries within `fw.lifecycle.grace_timeout_sec`, then closes the components.
+    ///
+    /// 执行关闭钩子，在 `fw.lifecycle.grace_timeout_sec` 内等待处理中的Web请求及MQ消息完成，然后关闭各组件.
     pub async fn shutdown() -> TardisResult<()> {
         Self::shutdown_internal(true).await
     }
@@ -905,6 +1205,13 @@ impl TardisFuns {
         #[allow(unused_variables)]
         let old_framework_config = tardis_instance().framework_config.replace(new_framework_config);
 
+        let result = Self::reload_components(&old_framework_config).await;
+        tardis_instance().config_notifier.notify();
+        result
+    }
+
+    /// Reinitialize the components whose config changed
+    async fn reload_components(old_framework_config: &FrameworkConfig) -> TardisResult<()> {
         #[allow(unused_variables)]
         let fw_config = TardisFuns::fw_config();
 
@@ -919,6 +1226,8 @@ impl TardisFuns {
             if fw_config.db != old_framework_config.db {
                 if let Some(db_config) = &fw_config.db {
                     tardis_instance().reldb.init_by(db_config).await?;
+                    db::reldb_migration::migrate_on_startup(db_config).await?;
+                    db::reldb_del_record::start_purge_jobs(db_config)?;
                 }
             }
         }
@@ -926,6 +1235,10 @@ impl TardisFuns {
         {
             if fw_config.web_server.is_some() && old_framework_config.web_server != fw_config.web_server {
                 let web_server = TardisWebServer::init_by_conf(&fw_config)?;
+                #[cfg(feature = "cluster")]
+                if fw_config.cluster.is_some() {
+                    TardisCluster::mount_global(&web_server).await;
+                }
                 let old_server = tardis_instance().web_server.get();
                 // if there's some inherit webserver
                 if old_server.is_running().await {
@@ -951,6 +1264,19 @@ impl TardisFuns {
                 tardis_instance().cache.init_by(cache_config).await?;
             }
         }
+        #[cfg(feature = "cluster")]
+        {
+            // the access address is derived from the web server config
+            if fw_config.cluster != old_framework_config.cluster || fw_config.web_server != old_framework_config.web_server {
+                let old_cluster = if fw_config.cluster.is_some() {
+                    let cluster = TardisCluster::init_by_conf(&fw_config).await?;
+                    tardis_instance().cluster.replace(cluster)
+                } else {
+                    tardis_instance().cluster.replace(TardisCluster::default())
+                };
+                old_cluster.shutdown().await?;
+            }
+        }
         #[cfg(feature = "mq")]
         {
             if fw_config.mq != old_framework_config.mq {
@@ -1075,6 +1401,16 @@ impl TardisFunsInst {
         self.db.expect("db is not initialized").rollback().await
     }
 
+    /// run the function in a transaction, see [`TardisRelDBlConnection::transaction`](db::reldb_client::TardisRelDBlConnection::transaction)
+    #[cfg(feature = "reldb-core")]
+    pub async fn transaction<F, T>(&self, f: F) -> TardisResult<T>
+    where
+        F: for<'c> FnOnce(&'c db::reldb_client::TardisRelDBlConnection) -> futures::future::BoxFuture<'c, TardisResult<T>> + Send,
+        T: Send,
+    {
+        self.db().transaction(f).await
+    }
+
     /// Get current module's cache client.
     #[cfg(feature = "cache")]
     pub fn cache(&self) -> Arc<TardisCacheClient> {
@@ -1116,7 +1452,9 @@ pub mod basic;
 #[cfg(feature = "cache")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
 pub mod cache;
-
+#[cfg(feature = "cluster")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cluster")))]
+pub mod cluster;
 pub mod config;
 #[cfg(any(feature = "crypto", feature = "base64"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "crypto", feature = "base64"))))]
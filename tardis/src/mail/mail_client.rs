@@ -5,6 +5,7 @@ use tracing::{error, info, trace, warn};
 use typed_builder::TypedBuilder;
 
 use crate::basic::error::TardisError;
+use crate::basic::health::HealthCheck;
 use crate::config::config_dto::component::mail::MailModuleConfig;
 use crate::utils::initializer::InitBy;
 use crate::{TardisFuns, TardisResult};
@@ -28,6 +29,24 @@ impl InitBy<MailModuleConfig> for TardisMailClient {
     }
 }
 
+#[async_trait::async_trait]
+impl HealthCheck for TardisMailClient {
+    /// Connect to the SMTP server and send `NOOP` / 连接SMTP服务并发送 `NOOP`
+    async fn health_check(&self) -> TardisResult<()> {
+        match self.client.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(TardisError::io_error(
+                "[Tardis.MailClient] SMTP server didn't respond to NOOP",
+                "503-tardis-mail-health-check-error",
+            )),
+            Err(error) => Err(TardisError::io_error(
+                &format!("[Tardis.MailClient] SMTP connection error: {error}"),
+                "503-tardis-mail-health-check-error",
+            )),
+        }
+    }
+}
+
 impl TardisMailClient {
     /// init mail client
     pub fn init(
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use amq_protocol_types::{AMQPValue, LongString, ShortString};
 use futures_util::lock::Mutex;
@@ -9,12 +12,33 @@ use lapin::{options::*, types::FieldTable, BasicProperties, Channel, Connection,
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::mq::MQModuleConfig;
 
+use crate::basic::health::HealthCheck;
 use crate::{basic::error::TardisError, utils::initializer::InitBy};
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn};
 
 pub struct TardisMQClient {
     con: Connection,
     channels: Mutex<Vec<Channel>>,
+    /// channels and tags of the running consumers
+    consumers: Mutex<Vec<(Channel, String)>>,
+    /// number of deliveries being processed
+    processing: Arc<AtomicUsize>,
+}
+
+/// Decrease the processing counter when the delivery is handled
+struct ProcessingGuard(Arc<AtomicUsize>);
+
+impl ProcessingGuard {
+    fn new(processing: &Arc<AtomicUsize>) -> Self {
+        processing.fetch_add(1, Ordering::SeqCst);
+        ProcessingGuard(processing.clone())
+    }
+}
+
+impl Drop for ProcessingGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 #[async_trait::async_trait]
@@ -24,6 +48,25 @@ impl InitBy<MQModuleConfig> for TardisMQClient {
     }
 }
 
+#[async_trait::async_trait]
+impl HealthCheck for TardisMQClient {
+    /// Check the status of the connection and the consumer channels / 检查连接及消费通道的状态
+    async fn health_check(&self) -> TardisResult<()> {
+        if !self.con.status().connected() {
+            return Err(TardisError::io_error("[Tardis.MQClient] Connection is not connected", "503-tardis-mq-health-check-error"));
+        }
+        for (channel, consumer_tag) in self.consumers.lock().await.iter() {
+            if !channel.status().connected() {
+                return Err(TardisError::io_error(
+                    &format!("[Tardis.MQClient] Channel of consumer {consumer_tag} is not connected"),
+                    "503-tardis-mq-health-check-error",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl TardisMQClient {
     pub async fn init(MQModuleConfig { url }: &MQModuleConfig) -> TardisResult<TardisMQClient> {
         info!("[Tardis.MQClient] Initializing, host:{}, port:{}", url.host_str().unwrap_or(""), url.port().unwrap_or(0));
@@ -32,9 +75,37 @@ impl TardisMQClient {
         Ok(TardisMQClient {
             con,
             channels: Mutex::new(Vec::new()),
+            consumers: Mutex::new(Vec::new()),
+            processing: Arc::new(AtomicUsize::new(0)),
         })
     }
 
+    /// Stop all consumers and wait for the deliveries being processed / 停止所有消费者并等待处理中的消息完成
+    ///
+    /// Deliveries still being processed after the timeout are left unacknowledged and will be redelivered by the broker.
+    ///
+    /// 超时后仍在处理的消息不会被确认，由消息服务重新投递.
+    pub async fn drain(&self, timeout: Duration) -> TardisResult<()> {
+        info!("[Tardis.MQClient] Draining...");
+        let consumers = std::mem::take(&mut *self.consumers.lock().await);
+        for (channel, consumer_tag) in consumers {
+            channel.basic_cancel(&consumer_tag, BasicCancelOptions::default()).await?;
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let processing = self.processing.load(Ordering::SeqCst);
+            if processing == 0 {
+                break;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                warn!("[Tardis.MQClient] Drain timeout, {} deliveries are still being processed", processing);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+
     pub async fn close(&self) -> TardisResult<()> {
         info!("[Tardis.MQClient] Shutdown...");
         let channels = self.channels.lock().await;
@@ -65,6 +136,8 @@ impl TardisMQClient {
             .await?;
         if confirm.is_ack() {
             channel.close(200u16, "").await?;
+            #[cfg(feature = "metrics")]
+            crate::TardisFuns::metrics().mq_published.with_label_values(&[address]).inc();
             Ok(())
         } else {
             Err(TardisError::internal_error("MQ request confirmation error", "500-tardis-mq-confirm-error"))
@@ -105,6 +178,7 @@ impl TardisMQClient {
                 FieldTable::default(),
             )
             .await?;
+        self.consumers.lock().await.push((channel.clone(), consumer.tag().to_string()));
         self.channels.lock().await.push(channel);
         self.process(address.to_string(), consumer, fun).await
     }
@@ -129,6 +203,8 @@ impl TardisMQClient {
             .await?;
         if confirm.is_ack() {
             channel.close(200u16, "").await?;
+            #[cfg(feature = "metrics")]
+            crate::TardisFuns::metrics().mq_published.with_label_values(&[topic]).inc();
             Ok(())
         } else {
             Err(TardisError::internal_error("MQ request confirmation error", "500-tardis-mq-confirm-error"))
@@ -173,6 +249,7 @@ impl TardisMQClient {
                 FieldTable::default(),
             )
             .await?;
+        self.consumers.lock().await.push((channel.clone(), consumer.tag().to_string()));
         self.channels.lock().await.push(channel);
         self.process(topic.to_string(), consumer, fun).await
     }
@@ -200,12 +277,16 @@ impl TardisMQClient {
         F: Fn((HashMap<String, String>, String)) -> T + Send + Sync + 'static,
         T: Future<Output = TardisResult<()>> + Send + 'static,
     {
+        let processing = self.processing.clone();
         async_global_executor::spawn(async move {
             while let Some(delivery) = consumer.next().await {
+                let _processing = ProcessingGuard::new(&processing);
                 match delivery {
                     Ok(d) => match std::str::from_utf8(d.data.as_slice()) {
                         Ok(msg) => {
                             trace!("[Tardis.MQClient] Receive, queue:{}, message:{}", topic_or_address, msg);
+                            #[cfg(feature = "metrics")]
+                            crate::TardisFuns::metrics().mq_consumed.with_label_values(&[topic_or_address.as_str()]).inc();
                             let mut resp_header: HashMap<String, String> = HashMap::default();
                             let _ = d.properties.headers().as_ref().map(|header| {
                                 for (k, v) in header.into_iter() {
@@ -227,6 +308,8 @@ impl TardisMQClient {
                                 },
                                 Err(error) => {
                                     error!("[Tardis.MQClient] Receive process error, queue:{topic_or_address}, message:{msg} | {error}");
+                                    #[cfg(feature = "metrics")]
+                                    crate::TardisFuns::metrics().mq_handler_failures.with_label_values(&[topic_or_address.as_str()]).inc();
                                 }
                             }
                         }
@@ -9,6 +9,7 @@ use s3::{Bucket, BucketConfiguration, Region};
 use tracing::{error, info, trace};
 
 use crate::basic::error::{TardisError, ERROR_DEFAULT_CODE};
+use crate::basic::health::HealthCheck;
 use crate::config::config_dto::component::os::OSModuleConfig;
 use crate::utils::initializer::InitBy;
 use crate::TardisResult;
@@ -30,6 +31,14 @@ impl InitBy<OSModuleConfig> for TardisOSClient {
     }
 }
 
+#[async_trait::async_trait]
+impl HealthCheck for TardisOSClient {
+    /// Request the location of the default bucket / 请求默认桶的位置信息
+    async fn health_check(&self) -> TardisResult<()> {
+        self.get_client().health_check().await
+    }
+}
+
 impl TardisOSClient {
     pub fn init(
         OSModuleConfig {
@@ -206,6 +215,8 @@ trait TardisOSOperations {
     async fn put_lifecycle(&self, bucket_name: Option<&str>, config: BucketLifecycleConfiguration) -> TardisResult<()>;
 
     async fn delete_lifecycle(&self, bucket_name: Option<&str>) -> TardisResult<()>;
+
+    async fn health_check(&self) -> TardisResult<()>;
 }
 
 #[async_trait]
@@ -393,6 +404,14 @@ impl TardisOSOperations for TardisOSS3Client {
         bucket.delete_bucket_lifecycle().await?;
         Ok(())
     }
+
+    async fn health_check(&self) -> TardisResult<()> {
+        // without a default bucket there is nothing to check without side effects
+        if let Some(bucket) = &self.default_bucket {
+            bucket.location().await?;
+        }
+        Ok(())
+    }
 }
 
 impl TardisOSS3Client {
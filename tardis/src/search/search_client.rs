@@ -6,6 +6,7 @@ use tracing::{debug, info, trace};
 use url::Url;
 
 use crate::basic::error::TardisError;
+use crate::basic::health::HealthCheck;
 use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::search::SearchModuleConfig;
 use crate::config::config_dto::component::web_client::WebClientModuleConfig;
@@ -79,6 +80,31 @@ impl InitBy<SearchModuleConfig> for TardisSearchClient {
     }
 }
 
+#[derive(Deserialize)]
+struct TardisRawClusterHealth {
+    status: String,
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for TardisSearchClient {
+    /// Check the elasticsearch cluster health, `red` is considered down / 检查Elasticsearch集群健康状态， `red` 视为不可用
+    async fn health_check(&self) -> TardisResult<()> {
+        let url = self.get_url_with_path(["_cluster", "health"]);
+        let resp = self.client.get::<TardisRawClusterHealth>(url, None).await?;
+        match (resp.code, resp.body) {
+            (200, Some(health)) if health.status != "red" => Ok(()),
+            (code, health) => Err(TardisError::custom(
+                &code.to_string(),
+                &format!(
+                    "[Tardis.SearchClient] Cluster is unhealthy, status: {}",
+                    health.map(|health| health.status).unwrap_or_default()
+                ),
+                "-1-tardis-search-health-check-error",
+            )),
+        }
+    }
+}
+
 impl TardisSearchClient {
     /// Initialize configuration / 初始化配置
     pub fn init(SearchModuleConfig { url, timeout_sec }: &SearchModuleConfig) -> TardisResult<TardisSearchClient> {
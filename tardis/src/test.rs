@@ -1 +1,3 @@
 pub mod test_container;
+#[cfg(feature = "reldb-sqlite")]
+pub mod test_reldb;
@@ -0,0 +1,153 @@
+//! In-memory SQLite database for the tests of the relational database code / 用于关系型数据库代码测试的内存SQLite数据库
+//!
+//! [`TardisTestRelDB`] runs a test with a fresh `sqlite::memory:` database, which is returned by [`TardisFuns::reldb`](crate::TardisFuns::reldb) ,
+//! [`TardisFuns::reldb_by_module`](crate::TardisFuns::reldb_by_module) and
+//! [`TardisFuns::reldb_by_module_or_default`](crate::TardisFuns::reldb_by_module_or_default) of every module,
+//! and so shared by the [`TardisFunsInst`](crate::TardisFunsInst) of the test, without Docker.
+//! The tests running in parallel don't see each other's database.
+//!
+//! [`TardisTestRelDB`] 使用全新的 `sqlite::memory:` 数据库运行测试，所有模块的 `TardisFuns::reldb` 等方法均返回该数据库，
+//! 因此该测试的 `TardisFunsInst` 共享此数据库，无需Docker. 并行运行的测试之间互不可见.
+//!
+//! The database is bound to the task of the test, the tasks spawned by the test should be wrapped by [`TardisTestRelDB::scope`] to use it.
+//!
+//! 数据库绑定在测试的任务上，测试中创建的任务需要通过 [`TardisTestRelDB::scope`] 包装才能使用该数据库.
+//!
+//! The tests sharing a database among several clients or configurations, e.g. the migrations or the replicas,
+//! put their SQLite files in a [`TardisTestSqliteDir`] instead.
+//!
+//! 在多个客户端或配置间共享数据库的测试（如迁移或只读副本）改为将SQLite文件放在 [`TardisTestSqliteDir`] 中.
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sea_orm::sea_query::{IndexCreateStatement, TableCreateStatement};
+use sea_orm::DbBackend;
+
+use crate::basic::result::TardisResult;
+use crate::config::config_dto::component::db::{CompatibleType, DBModuleConfig};
+use crate::db::reldb_client::{TardisActiveModel, TardisRelDBClient};
+
+tokio::task_local! {
+    static TEST_RELDB: Arc<TardisRelDBClient>;
+}
+
+/// Database of the current test, if any
+pub(crate) fn current() -> Option<Arc<TardisRelDBClient>> {
+    TEST_RELDB.try_with(|client| client.clone()).ok()
+}
+
+/// In-memory SQLite database of a test / 测试的内存SQLite数据库
+///
+/// # Examples
+/// ```ignore
+/// use tardis::test::test_reldb::TardisTestRelDB;
+/// use tardis::TardisFuns;
+///
+/// #[tokio::test]
+/// async fn test_account() -> TardisResult<()> {
+///     TardisTestRelDB::sqlite()
+///         .entity::<account::ActiveModel>()
+///         .run(|_| async move {
+///             let funs = TardisFuns::inst_with_db_conn("iam", None);
+///             account_serv::add(&funs, &ctx).await?;
+///             Ok(())
+///         })
+///         .await
+/// }
+/// ```
+#[derive(Default)]
+pub struct TardisTestRelDB {
+    entities: Vec<(TableCreateStatement, Vec<IndexCreateStatement>, Vec<String>)>,
+}
+
+impl TardisTestRelDB {
+    /// A fresh `sqlite::memory:` database / 全新的 `sqlite::memory:` 数据库
+    pub fn sqlite() -> Self {
+        Self::default()
+    }
+
+    /// Create the table of the entity / 创建实体的表
+    pub fn entity<A>(mut self) -> Self
+    where
+        A: TardisActiveModel,
+    {
+        self.entities.push(A::init(DbBackend::Sqlite, None, CompatibleType::None));
+        self
+    }
+
+    /// Create the table by the statements returned by [`TardisActiveModel::init`] / 通过 [`TardisActiveModel::init`] 返回的语句创建表
+    pub fn init(mut self, params: (TableCreateStatement, Vec<IndexCreateStatement>, Vec<String>)) -> Self {
+        self.entities.push(params);
+        self
+    }
+
+    /// Initialize the database with the basic tables and the entities, then run the test with it / 使用基础表及实体初始化数据库，然后以此运行测试
+    pub async fn run<F, T>(self, fun: F) -> TardisResult<()>
+    where
+        F: FnOnce(Arc<TardisRelDBClient>) -> T,
+        T: Future<Output = TardisResult<()>>,
+    {
+        // every `sqlite::memory:` connection options names a new database, shared by the connections of the pool,
+        // which is dropped when the last connection is closed
+        let client = Arc::new(TardisRelDBClient::init(&DBModuleConfig::builder().url("sqlite::memory:").min_connections(1).build()).await?);
+        client.init_basic_tables().await?;
+        let conn = client.conn();
+        for params in self.entities {
+            conn.init(params).await?;
+        }
+        TEST_RELDB.scope(client.clone(), fun(client)).await
+    }
+
+    /// Make the database of the current test available to the future, e.g. a spawned task / 使当前测试的数据库在该 future 中可用，如创建的任务
+    ///
+    /// # Panics
+    ///
+    /// Panics if it's not called in a test run by [`TardisTestRelDB::run`] .
+    pub fn scope<F>(future: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        let client = current().expect("[Tardis.Test] Not in a test run by TardisTestRelDB::run");
+        TEST_RELDB.scope(client, future)
+    }
+}
+
+/// Temporary directory of the SQLite files of a test, removed when dropped even if the test fails / 测试的SQLite文件临时目录，即使测试失败也会在释放时删除
+///
+/// # Examples
+/// ```ignore
+/// use tardis::test::test_reldb::TardisTestSqliteDir;
+///
+/// let dir = TardisTestSqliteDir::new()?;
+/// let primary = TardisRelDBClient::init(&DBModuleConfig::builder().url(dir.url("primary")).build()).await?;
+/// let other = TardisRelDBClient::init(&DBModuleConfig::builder().url(dir.url("primary")).build()).await?;
+/// ```
+pub struct TardisTestSqliteDir {
+    dir: PathBuf,
+}
+
+impl TardisTestSqliteDir {
+    /// Create a new temporary directory / 创建新的临时目录
+    pub fn new() -> TardisResult<Self> {
+        let dir = std::env::temp_dir().join(format!("tardis-test-reldb-{:08x}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Path of the directory / 目录的路径
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// URL of the database file `<name>.db` , created on the first connection / 数据库文件 `<name>.db` 的URL，首次连接时创建
+    pub fn url(&self, name: &str) -> String {
+        format!("sqlite://{}?mode=rwc", self.dir.join(format!("{name}.db")).display())
+    }
+}
+
+impl Drop for TardisTestSqliteDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -17,6 +17,9 @@ pub use tokio_tungstenite;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod context_extractor;
+#[cfg(all(feature = "web-server", feature = "metrics"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "web-server", feature = "metrics"))))]
+pub mod metrics_mw;
 #[cfg(feature = "web-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "web-server")))]
 pub mod uniform_error_mw;
@@ -0,0 +1,47 @@
+use std::time::Instant;
+
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+
+use crate::TardisFuns;
+
+/// Record the count and the latency of requests of a web server module / 记录Web服务模块的请求数及耗时
+pub struct TardisMetricsMiddleware {
+    module: String,
+}
+
+impl TardisMetricsMiddleware {
+    pub fn new(module: impl Into<String>) -> Self {
+        TardisMetricsMiddleware { module: module.into() }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TardisMetricsMiddleware {
+    type Output = TardisMetricsMiddlewareImpl<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TardisMetricsMiddlewareImpl { ep, module: self.module.clone() }
+    }
+}
+
+pub struct TardisMetricsMiddlewareImpl<E> {
+    ep: E,
+    module: String,
+}
+
+impl<E: Endpoint> Endpoint for TardisMetricsMiddlewareImpl<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        let method = req.method().to_string();
+        let start = Instant::now();
+        let resp = self.ep.call(req).await.map(IntoResponse::into_response);
+        let status = match &resp {
+            Ok(resp) => resp.status(),
+            Err(error) => error.status(),
+        };
+        let metrics = TardisFuns::metrics();
+        metrics.web_request_duration.with_label_values(&[self.module.as_str(), method.as_str()]).observe(start.elapsed().as_secs_f64());
+        metrics.web_requests.with_label_values(&[self.module.as_str(), method.as_str(), status.as_str()]).inc();
+        resp
+    }
+}
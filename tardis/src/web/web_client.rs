@@ -303,7 +303,17 @@ impl TardisWebClient {
             global::get_text_map_propagator(|propagator| propagator.inject_context(&ctx, &mut crate::basic::tracing::HeaderInjector(request.headers_mut())));
         }
         trace!("start request");
-        let response = self.client.execute(request).await?;
+        #[cfg(feature = "metrics")]
+        let (metrics_method, metrics_host, start) = (request.method().to_string(), url.host_str().unwrap_or_default().to_string(), std::time::Instant::now());
+        let response = self.client.execute(request).await;
+        #[cfg(feature = "metrics")]
+        {
+            let metrics = TardisFuns::metrics();
+            let status = response.as_ref().map(|response| response.status().as_str().to_string()).unwrap_or_else(|_| "error".to_string());
+            metrics.web_client_request_duration.with_label_values(&[metrics_method.as_str(), metrics_host.as_str()]).observe(start.elapsed().as_secs_f64());
+            metrics.web_client_requests.with_label_values(&[metrics_method.as_str(), metrics_host.as_str(), status.as_str()]).inc();
+        }
+        let response = response?;
         let code = response.status().as_u16();
         let headers = response
             .headers()
@@ -20,10 +20,11 @@ use crate::basic::result::TardisResult;
 use crate::config::config_dto::component::web_server::WebServerCommonConfig;
 use crate::config::config_dto::{
     component::{web_server::WebServerModuleConfig, WebServerConfig},
-    FrameworkConfig,
+    FrameworkConfig, LifecycleConfig,
 };
 use crate::utils::initializer::InitBy;
 use crate::web::uniform_error_mw::UniformError;
+mod health;
 mod initializer;
 use initializer::*;
 mod module;
@@ -132,6 +133,8 @@ pub struct TardisWebServer {
     /// use `load_initializer` or `load_boxed_initializer` instead
     pub(self) initializers: Mutex<Vec<Box<dyn WebServerInitializer + Send + Sync>>>,
     state: Mutex<ServerState>,
+    /// How long the shutdown waits for in-flight requests, see [LifecycleConfig::grace_timeout_sec]
+    grace_timeout: Duration,
 }
 
 impl Default for TardisWebServer {
@@ -142,6 +145,7 @@ impl Default for TardisWebServer {
             config: WebServerConfig::default(),
             state: Mutex::new(ServerState::default()),
             initializers: Mutex::new(Vec::new()),
+            grace_timeout: Duration::from_secs(LifecycleConfig::default().grace_timeout_sec),
         }
     }
 }
@@ -156,6 +160,7 @@ impl InitBy<FrameworkConfig> for TardisWebServer {
             config: conf.web_server.clone().expect("missing web server config"),
             state: Mutex::new(ServerState::Halted(route)),
             initializers: Mutex::new(Vec::new()),
+            grace_timeout: Duration::from_secs(conf.lifecycle.grace_timeout_sec),
         })
     }
 }
@@ -169,6 +174,7 @@ impl TardisWebServer {
             config: conf.web_server.clone().expect("missing web server config"),
             state: Mutex::new(ServerState::Halted(route)),
             initializers: Mutex::new(Vec::new()),
+            grace_timeout: Duration::from_secs(conf.lifecycle.grace_timeout_sec),
         })
     }
 
@@ -181,6 +187,7 @@ impl TardisWebServer {
             config: WebServerConfig::builder().common(WebServerCommonConfig::builder().host(host).port(port).build()).default(WebServerModuleConfig::builder().build()).build(),
             state: Mutex::new(ServerState::Halted(route)),
             initializers: Mutex::new(Vec::new()),
+            grace_timeout: Duration::from_secs(LifecycleConfig::default().grace_timeout_sec),
         })
     }
 
@@ -318,6 +325,8 @@ impl TardisWebServer {
         };
         let route = route.boxed();
         let route = route.with(middleware).with(poem::middleware::Tracing).with(poem::middleware::CatchPanic::default());
+        #[cfg(feature = "metrics")]
+        let route = route.with(crate::web::metrics_mw::TardisMetricsMiddleware::new(code));
         #[cfg(feature = "tracing")]
         let route = {
             let tracer = opentelemetry::global::tracer(crate::basic::tracing::tracing_service_name());
@@ -387,11 +396,23 @@ impl TardisWebServer {
 
         // server_task will be locked until function return
         let mut state_locked = self.state.lock().await;
-        let Some(route) = state_locked.take_route() else {
+        let Some(mut route) = state_locked.take_route() else {
             // case of already running
             warn!("[Tardis.WebServer] Trying to start webserver while it is already running");
             return TardisResult::Ok(());
         };
+        if self.config.health_check {
+            route = route.nest("/health", health::route());
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_path) = &self.config.metrics_path {
+            let exporter = poem::IntoEndpoint::into_endpoint(poem::endpoint::PrometheusExporter::new(crate::TardisFuns::metrics().registry().clone())).before(|req| async move {
+                #[cfg(feature = "reldb-core")]
+                crate::TardisFuns::metrics().refresh_reldb_pool_connections();
+                Ok(req)
+            });
+            route = route.nest(metrics_path, exporter);
+        }
 
         let (tx, rx) = oneshot::channel::<()>();
         let graceful_shutdown_signal = async move {
@@ -412,7 +433,7 @@ impl TardisWebServer {
                         .cert(self.config.tls_cert.clone().expect("[Tardis.WebServer] TLS cert clone error")),
                 ),
             );
-            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(Duration::from_secs(5)));
+            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(self.grace_timeout));
             tokio::spawn(async {
                 server.await?;
                 info!("[Tardis.WebServer] Poem webserver shutdown finished");
@@ -420,7 +441,7 @@ impl TardisWebServer {
             })
         } else {
             let bind = TcpListener::bind(format!("{}:{}", self.config.host, self.config.port));
-            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(Duration::from_secs(5)));
+            let server = poem::Server::new(bind).run_with_graceful_shutdown(route, graceful_shutdown_signal, Some(self.grace_timeout));
             tokio::spawn(async {
                 server.await?;
                 info!("[Tardis.WebServer] Poem webserver shutdown finished");
@@ -440,6 +461,16 @@ impl TardisWebServer {
     /// # Shutdown
     /// shutdown this webserver, if it's not running it will return `Ok(())` instantly
     pub async fn shutdown(&self) -> TardisResult<()> {
+        // poem stops waiting for in-flight requests after the grace timeout, leave some time for it to exit
+        self.shutdown_within(self.grace_timeout + Duration::from_secs(1)).await
+    }
+
+    /// Shutdown this webserver, waiting for the in-flight requests at most `timeout` / 关闭Web服务，最多等待 `timeout` 时间以完成处理中的请求
+    ///
+    /// The server task is aborted if it's not finished within `timeout` , e.g. the remaining time of the shutdown deadline.
+    ///
+    /// 如果服务任务未在 `timeout` 内结束（如关闭截止时间的剩余时间）则将其中止.
+    pub async fn shutdown_within(&self, timeout: Duration) -> TardisResult<()> {
         let mut state_locked = self.state.lock().await;
         let mut swap_state = ServerState::default();
         std::mem::swap(&mut *state_locked, &mut swap_state);
@@ -450,12 +481,14 @@ impl TardisWebServer {
             if send_result.is_err() {
                 warn!("[Tardis.WebServer] Trying to shutdown webserver which seems already closed")
             };
-            match tokio::time::timeout(Duration::from_secs(5), task.inner).await {
+            let mut inner = task.inner;
+            match tokio::time::timeout(timeout, &mut inner).await {
                 Ok(Ok(result)) => return result,
                 Ok(Err(e)) => {
                     error!("[Tardis.WebServer] Fail to join webservert task: {e}")
                 }
                 Err(e) => {
+                    inner.abort();
                     error!("[Tardis.WebServer] Shutdown webserver timeout: {e}")
                 }
             }
@@ -0,0 +1,24 @@
+use poem::http::StatusCode;
+use poem::web::Json;
+use poem::{handler, IntoResponse, Route};
+
+use crate::basic::health::TardisHealthReport;
+use crate::TardisFuns;
+
+/// Routes of `/health/live` and `/health/ready`
+pub(crate) fn route() -> Route {
+    Route::new().at("/live", poem::get(live)).at("/ready", poem::get(ready))
+}
+
+/// The process is alive as long as it can respond
+#[handler]
+async fn live() -> Json<TardisHealthReport> {
+    Json(TardisHealthReport::default())
+}
+
+#[handler]
+async fn ready() -> impl IntoResponse {
+    let report = TardisFuns::health().await;
+    let status = if report.is_up() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    Json(report).with_status(status)
+}
@@ -0,0 +1,14 @@
+use tardis::db::sea_orm::{self, *};
+use tardis::{TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation)]
+#[sea_orm(table_name = "tests")]
+#[tardis_entity(dto = "Test")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+
+#[allow(dead_code)]
+fn main() {}
@@ -0,0 +1,20 @@
+use tardis::db::reldb_client::TardisActiveModel;
+use tardis::db::sea_orm;
+use tardis::db::sea_orm::*;
+use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+#[sea_orm(table_name = "tests")]
+#[tardis_entity(tenant_scoped)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(column_name = "paths")]
+    #[fill_ctx(fill = "own_paths")]
+    pub own_paths: String,
+}
+
+#[allow(dead_code)]
+fn main() {
+    assert_eq!(ActiveModel::own_paths_column(), Some("paths"));
+}
@@ -46,6 +46,9 @@ async fn test_cache_client() -> TardisResult<()> {
         assert_eq!(str_value, "测试2");
 
         client.expire("test_key_nx", 1).await?;
+        assert!(client.set_nx_ex("test_key_nx_ex", "测试4", 1).await?);
+        assert!(!client.set_nx_ex("test_key_nx_ex", "测试5", 1).await?);
+        assert_eq!(client.get("test_key_nx_ex").await?.unwrap(), "测试4");
         client.set_ex("test_key_ex", "测试3", 1).await?;
         str_value = client.get("test_key_ex").await?.unwrap();
         assert_eq!(str_value, "测试3");
@@ -58,6 +61,8 @@ async fn test_cache_client() -> TardisResult<()> {
         assert!(!bool_value);
         bool_value = client.exists("test_key_nx").await?;
         assert!(!bool_value);
+        bool_value = client.exists("test_key_nx_ex").await?;
+        assert!(!bool_value);
 
         opt_value = client.getset("test_key_none", "孤岛旭日").await?;
         assert_eq!(opt_value, None);
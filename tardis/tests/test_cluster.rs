@@ -0,0 +1,180 @@
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serial_test::serial;
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::cache::cache_client::TardisCacheClient;
+use tardis::cluster::cluster_processor::{ClusterEventTarget, TardisCluster, TardisClusterMembershipEvent, TardisClusterMessageReq, TardisClusterSubscriber};
+use tardis::config::config_dto::{CacheModuleConfig, ClusterConfig, ClusterWatchKind};
+use tardis::consts::IP_LOCALHOST;
+use tardis::serde_json::{json, Value};
+use tardis::test::test_container::TardisTestContainer;
+use tardis::web::web_server::TardisWebServer;
+use tardis::TardisFuns;
+use tokio::time::{sleep, timeout};
+use url::Url;
+
+struct EchoSubscriber {
+    node_id: String,
+}
+
+#[tardis::async_trait::async_trait]
+impl TardisClusterSubscriber for EchoSubscriber {
+    fn event_name(&self) -> String {
+        "echo".to_string()
+    }
+
+    async fn subscribe(&self, message_req: TardisClusterMessageReq) -> TardisResult<Option<Value>> {
+        Ok(Some(json!({"from": message_req.req_node_id, "by": self.node_id, "msg": message_req.msg})))
+    }
+}
+
+struct CountSubscriber {
+    counter: Arc<AtomicUsize>,
+}
+
+#[tardis::async_trait::async_trait]
+impl TardisClusterSubscriber for CountSubscriber {
+    fn event_name(&self) -> String {
+        "count".to_string()
+    }
+
+    async fn subscribe(&self, _: TardisClusterMessageReq) -> TardisResult<Option<Value>> {
+        self.counter.fetch_add(1, Ordering::SeqCst);
+        Ok(None)
+    }
+}
+
+struct FailSubscriber;
+
+#[tardis::async_trait::async_trait]
+impl TardisClusterSubscriber for FailSubscriber {
+    fn event_name(&self) -> String {
+        "fail".to_string()
+    }
+
+    async fn subscribe(&self, _: TardisClusterMessageReq) -> TardisResult<Option<Value>> {
+        Err(TardisError::bad_request("always fail", "400-test-cluster-fail"))
+    }
+}
+
+struct TestNode {
+    cluster: Arc<TardisCluster>,
+    web_server: TardisWebServer,
+    counter: Arc<AtomicUsize>,
+}
+
+async fn start_node(node_id: &str, port: u16, config: &ClusterConfig, cache: Option<Arc<TardisCacheClient>>) -> TardisResult<TestNode> {
+    let web_server = TardisWebServer::init_simple(IP_LOCALHOST, port)?;
+    let cluster = TardisCluster::init(node_id, "test-app", &format!("127.0.0.1:{port}"), config, cache).await?;
+    cluster.mount(&web_server).await;
+    web_server.start().await?;
+    let counter = Arc::new(AtomicUsize::new(0));
+    cluster.subscribe(EchoSubscriber { node_id: node_id.to_string() });
+    cluster.subscribe(CountSubscriber { counter: counter.clone() });
+    cluster.subscribe(FailSubscriber);
+    Ok(TestNode { cluster, web_server, counter })
+}
+
+async fn wait_for_nodes(node: &TestNode, count: usize) {
+    timeout(Duration::from_secs(15), async {
+        while node.cluster.nodes().len() != count {
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("node {} should see {} nodes, but got {:?}", node.cluster.node_id(), count, node.cluster.nodes()));
+}
+
+async fn test_cluster_nodes(nodes: Vec<TestNode>) -> TardisResult<()> {
+    for node in &nodes {
+        wait_for_nodes(node, nodes.len() - 1).await;
+    }
+    let (node1, node2, node3) = (&nodes[0], &nodes[1], &nodes[2]);
+    let mut node1_nodes = node1.cluster.nodes().into_iter().map(|node| node.id).collect::<Vec<_>>();
+    node1_nodes.sort();
+    assert_eq!(node1_nodes, vec!["node2".to_string(), "node3".to_string()]);
+
+    // request
+    let resp = node1.cluster.request_event("echo", json!("hi"), "node2").await?;
+    assert_eq!(resp, json!({"from": "node1", "by": "node2", "msg": "hi"}));
+    let resp = node3.cluster.request_event("count", json!({}), "node1").await?;
+    assert_eq!(resp, Value::Null);
+    assert_eq!(node1.counter.load(Ordering::SeqCst), 1);
+    let err = node1.cluster.request_event("fail", json!({}), "node2").await.unwrap_err();
+    assert_eq!(err.code, "400");
+    let err = node1.cluster.request_event("not_exist", json!({}), "node2").await.unwrap_err();
+    assert_eq!(err.code, "404");
+    assert!(err.message.contains("not_exist"));
+    let err = node1.cluster.request_event("echo", json!({}), "node_not_exist").await.unwrap_err();
+    assert_eq!(err.code, "404");
+    assert!(err.message.contains("node_not_exist"));
+
+    // publish
+    node1.cluster.publish_event("count", json!({}), ClusterEventTarget::Broadcast).await?;
+    node2.cluster.publish_event("count", json!({}), ClusterEventTarget::Single("node3".to_string())).await?;
+    node3.cluster.publish_event("count", json!({}), ClusterEventTarget::Multi(vec!["node1".to_string(), "node2".to_string()])).await?;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(node1.counter.load(Ordering::SeqCst), 2);
+    assert_eq!(node2.counter.load(Ordering::SeqCst), 2);
+    assert_eq!(node3.counter.load(Ordering::SeqCst), 2);
+
+    // membership
+    let mut membership = node1.cluster.watch_membership();
+    node3.cluster.shutdown().await?;
+    node3.web_server.shutdown().await?;
+    let event = timeout(Duration::from_secs(15), membership.recv()).await.expect("node3 should leave").expect("membership channel closed");
+    match event {
+        TardisClusterMembershipEvent::Left(node) => assert_eq!(node.id, "node3"),
+        event => panic!("unexpected membership event {event:?}"),
+    }
+    wait_for_nodes(node2, 1).await;
+    assert_eq!(node1.cluster.nodes().len(), 1);
+
+    node1.cluster.shutdown().await?;
+    node2.cluster.shutdown().await?;
+    node1.web_server.shutdown().await?;
+    node2.web_server.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(cluster_tests)]
+async fn test_cluster_static() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    let ports = (0..3).map(|_| portpicker::pick_unused_port().expect("no free port")).collect::<Vec<_>>();
+    let config = ClusterConfig::builder()
+        .watch_kind(ClusterWatchKind::Static)
+        .static_nodes(ports.iter().map(|port| format!("127.0.0.1:{port}")).collect::<Vec<_>>())
+        .heartbeat_interval_sec(1)
+        .heartbeat_timeout_sec(3)
+        .build();
+    let mut nodes = Vec::new();
+    for (idx, port) in ports.iter().enumerate() {
+        nodes.push(start_node(&format!("node{}", idx + 1), *port, &config, None).await?);
+    }
+    test_cluster_nodes(nodes).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(cluster_tests)]
+async fn test_cluster_cache() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    TardisTestContainer::redis(|url| async move {
+        let url = url.parse::<Url>().expect("invalid url");
+        let cache = Arc::new(TardisCacheClient::init(&CacheModuleConfig::builder().url(url).build()).await?);
+        let config = ClusterConfig::builder().watch_kind(ClusterWatchKind::Cache).heartbeat_interval_sec(1).heartbeat_timeout_sec(3).build();
+        let mut nodes = Vec::new();
+        for idx in 1..=3 {
+            let port = portpicker::pick_unused_port().expect("no free port");
+            nodes.push(start_node(&format!("node{idx}"), port, &config, Some(cache.clone())).await?);
+        }
+        test_cluster_nodes(nodes).await
+    })
+    .await
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serial_test::serial;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::TardisConfig;
+use tardis::config::config_processor::{ConfCenterContext, ConfCenterProcess};
+use tardis::config::config_rs;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::web::poem;
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TestConfig {
+    project_name: String,
+    level_num: u8,
+}
+
+fn set_conf_center_env(kind: &str, url: &str) {
+    env::set_var("PROFILE", "test");
+    env::set_var("TARDIS_FW.APP.ID", "test-app");
+    env::set_var("TARDIS_FW.CONF_CENTER.KIND", kind);
+    env::set_var("TARDIS_FW.CONF_CENTER.URL", url);
+    env::set_var("TARDIS_FW.CONF_CENTER.CONFIG_CHANGE_POLLING_INTERVAL", "100");
+}
+
+fn conf_content(project_name: &str) -> String {
+    format!("[cs]\nproject_name = \"{project_name}\"\n")
+}
+
+async fn wait_for_project_name(project_name: &str) {
+    for _ in 0..50 {
+        if TardisFuns::cs_config::<TestConfig>("").project_name == project_name {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("config was not reloaded to {project_name}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(conf_center_tests)]
+async fn test_conf_center_dir() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=debug");
+    let dir = env::temp_dir().join(format!("tardis-conf-center-{:08x}", rand::random::<u32>()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("test-app-default.toml"), "[cs]\nproject_name = \"default\"\nlevel_num = 1\n")?;
+    std::fs::write(dir.join("test-app-test.toml"), conf_content("v1"))?;
+    set_conf_center_env("dir", dir.to_str().unwrap());
+
+    TardisFuns::init(None).await?;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v1");
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").level_num, 1);
+
+    std::fs::write(dir.join("test-app-test.toml"), conf_content("v2"))?;
+    wait_for_project_name("v2").await;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").level_num, 1);
+
+    // kubernetes config maps are updated by swapping the `..data` symlink
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+        std::fs::create_dir_all(dir.join("..v3"))?;
+        std::fs::write(dir.join("..v3").join("test-app-test.toml"), conf_content("v3"))?;
+        symlink("..v3", dir.join("..data"))?;
+        std::fs::remove_file(dir.join("test-app-test.toml"))?;
+        symlink("..data/test-app-test.toml", dir.join("test-app-test.toml"))?;
+        wait_for_project_name("v3").await;
+        std::fs::create_dir_all(dir.join("..v4"))?;
+        std::fs::write(dir.join("..v4").join("test-app-test.toml"), conf_content("v4"))?;
+        symlink("..v4", dir.join("..data_tmp"))?;
+        std::fs::rename(dir.join("..data_tmp"), dir.join("..data"))?;
+        wait_for_project_name("v4").await;
+    }
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(conf_center_tests)]
+async fn test_conf_center_http() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=debug");
+    let documents = Arc::new(Mutex::new(HashMap::from([("test-app-default".to_string(), conf_content("v1"))])));
+    let port = portpicker::pick_unused_port().expect("no free port");
+    {
+        let documents = documents.clone();
+        let route = poem::Route::new().at(
+            "/configs/:data_id",
+            poem::get(poem::endpoint::make_sync(move |req: poem::Request| {
+                let data_id = req.path_params::<String>().unwrap();
+                match documents.lock().unwrap().get(&data_id) {
+                    Some(content) => poem::Response::builder().body(content.clone()),
+                    None => poem::Response::builder().status(poem::http::StatusCode::NOT_FOUND).finish(),
+                }
+            })),
+        );
+        tokio::spawn(poem::Server::new(poem::listener::TcpListener::bind(format!("127.0.0.1:{port}"))).run(route));
+    }
+    sleep(Duration::from_millis(200)).await;
+    set_conf_center_env("http", &format!("http://127.0.0.1:{port}/configs/"));
+
+    // the document of the profile doesn't exist
+    TardisFuns::init(None).await?;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v1");
+
+    documents.lock().unwrap().insert("test-app-test".to_string(), conf_content("v2"));
+    wait_for_project_name("v2").await;
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
+
+#[derive(Debug)]
+struct MemoryProcessor {
+    content: String,
+    reload_notifier: Arc<Mutex<Option<tokio::sync::mpsc::Sender<()>>>>,
+}
+
+impl ConfCenterProcess for MemoryProcessor {
+    fn listen_update(&self, reload_notifier: &tokio::sync::mpsc::Sender<()>) {
+        *self.reload_notifier.lock().unwrap() = Some(reload_notifier.clone());
+    }
+
+    fn register_to_config(&self, conf: config_rs::ConfigBuilder<config_rs::builder::AsyncState>) -> config_rs::ConfigBuilder<config_rs::builder::AsyncState> {
+        conf.add_source(config_rs::File::from_str(&self.content, config_rs::FileFormat::Toml))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(conf_center_tests)]
+async fn test_conf_center_custom() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=debug");
+    set_conf_center_env("unknown", "");
+    assert!(TardisConfig::init(None).await.is_err());
+
+    let content = Arc::new(Mutex::new(conf_content("v1")));
+    let reload_notifier = Arc::new(Mutex::new(None));
+    {
+        let content = content.clone();
+        let reload_notifier = reload_notifier.clone();
+        TardisConfig::register_conf_center("Memory", move |ctx: ConfCenterContext| {
+            assert_eq!(ctx.app_id, "test-app");
+            assert_eq!(ctx.profile, "test");
+            let processor = MemoryProcessor {
+                content: content.lock().unwrap().clone(),
+                reload_notifier: reload_notifier.clone(),
+            };
+            async move { Ok(Box::new(processor) as Box<dyn ConfCenterProcess>) }
+        });
+    }
+    set_conf_center_env("memory", "");
+    TardisFuns::init(None).await?;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v1");
+
+    *content.lock().unwrap() = conf_content("v2");
+    let notifier = reload_notifier.lock().unwrap().clone().expect("listen_update should be called");
+    notifier.send(()).await.unwrap();
+    wait_for_project_name("v2").await;
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
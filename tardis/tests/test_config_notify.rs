@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{AppConfig, FrameworkConfig, LogConfig, TardisConfig};
+use tardis::serde::{Deserialize, Serialize};
+use tardis::serde_json::{self, json};
+use tardis::TardisFuns;
+use tokio::time::timeout;
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+struct TestModuleConfig {
+    name: String,
+    level_num: u8,
+}
+
+fn config(m1: serde_json::Value, m2: serde_json::Value, app_name: &str, log_level: &str) -> TardisConfig {
+    TardisConfig::builder()
+        .cs(HashMap::from([("".to_string(), json!({})), ("m1".to_string(), m1), ("m2".to_string(), m2)]))
+        .fw(FrameworkConfig::builder()
+            .app(AppConfig::builder().name(app_name.to_string()).build())
+            .log(serde_json::from_value::<LogConfig>(json!({ "level": log_level })).unwrap())
+            .build())
+        .build()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_notify() -> TardisResult<()> {
+    TardisFuns::init_conf(config(json!({"name": "m1", "level_num": 1}), json!({"name": "m2"}), "app", "info")).await?;
+
+    let mut m1 = TardisFuns::watch_cs_config::<TestModuleConfig>("M1");
+    let mut m2 = TardisFuns::watch_cs_config::<TestModuleConfig>("m2");
+    let mut log = TardisFuns::watch_fw_config(|fw| fw.log.clone());
+    assert_eq!(m1.borrow_and_update().name, "m1");
+    assert_eq!(m2.borrow_and_update().name, "m2");
+    assert_eq!(log.borrow_and_update().as_ref().as_ref().unwrap().level.as_ref().unwrap().to_string(), "info");
+
+    // only the changed sections are notified
+    TardisFuns::hot_reload(config(json!({"name": "m1", "level_num": 2}), json!({"name": "m2"}), "app2", "info")).await?;
+    timeout(Duration::from_secs(1), m1.changed()).await.expect("m1 should be notified").unwrap();
+    assert_eq!(
+        *m1.borrow_and_update().as_ref(),
+        TestModuleConfig {
+            name: "m1".to_string(),
+            level_num: 2
+        }
+    );
+    assert!(Arc::ptr_eq(&m1.borrow(), &TardisFuns::cs_config::<TestModuleConfig>("m1")));
+    assert!(!m2.has_changed().unwrap());
+    assert!(!log.has_changed().unwrap());
+
+    // values that can't be deserialized are skipped
+    TardisFuns::hot_reload(config(json!({"name": "m1", "level_num": "x"}), json!({"name": "m2"}), "app2", "debug")).await?;
+    timeout(Duration::from_secs(1), log.changed()).await.expect("log should be notified").unwrap();
+    assert_eq!(log.borrow_and_update().as_ref().as_ref().unwrap().level.as_ref().unwrap().to_string(), "debug");
+    assert!(!m1.has_changed().unwrap());
+
+    // removed sections fallback to the default one
+    TardisFuns::hot_reload(TardisConfig::builder().cs(HashMap::from([("".to_string(), json!({"name": "default"}))])).build()).await?;
+    assert_eq!(m2.borrow_and_update().name, "default");
+
+    // dropped receivers are unsubscribed
+    drop(m1);
+    TardisFuns::hot_reload(config(json!({"name": "m1"}), json!({"name": "m2"}), "app", "info")).await?;
+    assert_eq!(m2.borrow_and_update().name, "m2");
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::TardisConfig;
+use tardis::config::config_secret::{decrypt_secret, encrypt_secret, generate_secret_key, load_secret_key, SECRET_KEY_ENV, SECRET_KEY_FILE_ENV};
+use tardis::serde::{Deserialize, Serialize};
+use tardis::TardisFuns;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TestConfig {
+    project_name: String,
+    level_num: u8,
+    tokens: Vec<String>,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_secret() -> TardisResult<()> {
+    env::set_var("PROFILE", "");
+    let dir = env::temp_dir().join(format!("tardis-conf-secret-{:08x}", rand::random::<u32>()));
+    std::fs::create_dir_all(&dir)?;
+
+    // key from env or key file
+    let key = generate_secret_key();
+    assert!(load_secret_key().is_err());
+    std::fs::write(dir.join("secret.key"), format!("{key}\n"))?;
+    env::set_var(SECRET_KEY_FILE_ENV, dir.join("secret.key"));
+    let key_bytes = load_secret_key()?;
+    assert_eq!(key_bytes.len(), 32);
+    env::remove_var(SECRET_KEY_FILE_ENV);
+    env::set_var(SECRET_KEY_ENV, &key);
+    assert_eq!(load_secret_key()?, key_bytes);
+
+    let password = encrypt_secret("p@ss:w}rd", &key_bytes)?;
+    assert!(password.starts_with("${secret:aead:"));
+    assert_ne!(password, encrypt_secret("p@ss:w}rd", &key_bytes)?);
+    assert_eq!(decrypt_secret(&password[14..password.len() - 1], &key_bytes)?, "p@ss:w}rd");
+    let aes128 = encrypt_secret("aes128", &key_bytes[..16])?;
+    assert_eq!(decrypt_secret(&aes128[14..aes128.len() - 1], &key_bytes[..16])?, "aes128");
+    assert!(decrypt_secret(&password[14..password.len() - 1], &key_bytes[..16]).is_err());
+    assert!(encrypt_secret("x", b"short").is_err());
+
+    // any field can be a secret
+    env::set_var("TEST_SECRET_DB_USER", "admin");
+    env::set_var("TEST_SECRET_APP_NAME", "secret app");
+    std::fs::write(dir.join("project_name"), "secret project\n")?;
+    std::fs::write(
+        dir.join("conf-default.toml"),
+        format!(
+            r#"
+[fw.app]
+name = "${{secret:env:TEST_SECRET_APP_NAME}}"
+
+[fw.db]
+url = "postgres://${{secret:env:TEST_SECRET_DB_USER}}:{password}@localhost/test"
+
+[cs]
+project_name = "${{secret:file:{}}}"
+level_num = 1
+tokens = ["plain", "${{secret:vault:token}}"]
+"#,
+            dir.join("project_name").display()
+        ),
+    )?;
+    TardisConfig::register_secret_resolver("Vault", |path| Ok(format!("vault-{path}")));
+    let config = TardisConfig::init(Some(dir.to_str().unwrap())).await?;
+    let db = config.fw.db.as_ref().unwrap();
+    assert_eq!(db.default.url, "postgres://admin:p@ss:w}rd@localhost/test");
+    assert_eq!(config.fw.app.name, "secret app");
+    TardisFuns::init_conf(config).await?;
+    let cs = TardisFuns::cs_config::<TestConfig>("");
+    assert_eq!(cs.project_name, "secret project");
+    assert_eq!(cs.level_num, 1);
+    assert_eq!(cs.tokens, vec!["plain".to_string(), "vault-token".to_string()]);
+
+    // unresolvable secrets fail the initialization
+    env::remove_var("TEST_SECRET_DB_USER");
+    assert_eq!(TardisConfig::init(Some(dir.to_str().unwrap())).await.unwrap_err().code, "404");
+    env::set_var("TEST_SECRET_DB_USER", "admin");
+    env::set_var(SECRET_KEY_ENV, generate_secret_key());
+    assert_eq!(TardisConfig::init(Some(dir.to_str().unwrap())).await.unwrap_err().code, "406");
+    env::set_var(SECRET_KEY_ENV, &key);
+    std::fs::write(dir.join("conf-default.toml"), "[cs]\nproject_name = \"${secret:unknown:x}\"\n")?;
+    assert_eq!(TardisConfig::init(Some(dir.to_str().unwrap())).await.unwrap_err().code, "406");
+
+    TardisFuns::shutdown().await?;
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{
+    AdvConfig, CacheModuleConfig, ClusterConfig, DBConfig, DBModuleConfig, FrameworkConfig, TardisConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig,
+};
+use tardis::schemars::JsonSchema;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::serde_json::json;
+use tardis::TardisFuns;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct TestModuleConfig {
+    name: String,
+    level_num: u8,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_validate() -> TardisResult<()> {
+    // the registered schemas are global, so run in order
+    test_validate_fw().await?;
+    test_schema()
+}
+
+async fn test_validate_fw() -> TardisResult<()> {
+    TardisConfig::builder().build().validate()?;
+    TardisConfig::builder()
+        .fw(FrameworkConfig::builder()
+            .db(DBConfig::builder()
+                .default(DBModuleConfig::builder().url("postgres://postgres@localhost/test").build())
+                .modules([("m1".to_string(), DBModuleConfig::builder().url("sqlite::memory:").build())])
+                .build())
+            .build())
+        .build()
+        .validate()?;
+
+    let config = TardisConfig::builder()
+        .fw(FrameworkConfig::builder()
+            .adv(AdvConfig::builder().strict_validation(true).build())
+            .db(DBConfig::builder()
+                .default(DBModuleConfig::builder().url("redis://localhost").build())
+                .modules([
+                    ("m 1".to_string(), DBModuleConfig::builder().url("mysql://localhost/test").build()),
+                    (
+                        "m2".to_string(),
+                        DBModuleConfig::builder().url("mysql://localhost/test").max_connections(2).min_connections(3).build(),
+                    ),
+                ])
+                .build())
+            .web_server(WebServerConfig::builder().common(WebServerCommonConfig::builder().tls_key("key").build()).default(WebServerModuleConfig::default()).build())
+            .cluster(ClusterConfig::builder().heartbeat_interval_sec(10).heartbeat_timeout_sec(5).build())
+            .build())
+        .build();
+    let error = config.validate().unwrap_err();
+    assert_eq!(error.code, "406");
+    // reported before any component is initialized if strict, and only logged otherwise
+    assert_eq!(TardisFuns::init_conf(config.clone()).await.unwrap_err().message, error.message);
+    let mut lenient_config = config.clone();
+    lenient_config.fw.adv.strict_validation = false;
+    lenient_config.check()?;
+    assert!(lenient_config.validate().is_err());
+    for problem in [
+        "fw.db.url has an unsupported scheme [redis]",
+        "fw.db.modules has an invalid module code [m 1]",
+        "fw.db.modules.m2.min_connections (3) must not be greater than max_connections (2)",
+        "fw.web_server.tls_key and fw.web_server.tls_cert must be set together",
+        "fw.cache must be set when fw.cluster.watch_kind is cache",
+        "fw.cluster.heartbeat_timeout_sec (5) must be greater than heartbeat_interval_sec (10)",
+    ] {
+        assert!(error.message.contains(problem), "{problem} not found in {}", error.message);
+    }
+    assert!(!error.message.contains("fw.db.modules.m1"));
+
+    let config = TardisConfig::builder().fw(FrameworkConfig::builder().cache(CacheModuleConfig::builder().url("http://localhost".parse().unwrap()).build()).build()).build();
+    assert!(config.validate().unwrap_err().message.contains("fw.cache.url has an unsupported scheme [http]"));
+
+    // the web modules are configured in the other components or in csm
+    let config = TardisConfig::builder()
+        .cs(HashMap::from([("m2".to_string(), json!({}))]))
+        .fw(FrameworkConfig::builder()
+            .db(DBConfig::builder()
+                .default(DBModuleConfig::builder().url("sqlite::memory:").build())
+                .modules([("m1".to_string(), DBModuleConfig::builder().url("sqlite::memory:").build())])
+                .build())
+            .web_server(
+                WebServerConfig::builder()
+                    .default(WebServerModuleConfig::default())
+                    .modules([
+                        ("m1".to_string(), WebServerModuleConfig::default()),
+                        ("M2".to_string(), WebServerModuleConfig::default()),
+                        ("m3".to_string(), WebServerModuleConfig::default()),
+                    ])
+                    .build(),
+            )
+            .build())
+        .build();
+    let error = config.validate().unwrap_err();
+    assert!(error.message.contains("fw.web_server.modules.m3 is not configured in the modules of the other components or in csm"));
+    assert!(!error.message.contains("fw.web_server.modules.m1"));
+    assert!(!error.message.contains("fw.web_server.modules.M2"));
+    Ok(())
+}
+
+fn test_schema() -> TardisResult<()> {
+    let fw_schema = TardisConfig::fw_schema();
+    assert_eq!(fw_schema["title"], "FrameworkConfig");
+    assert!(fw_schema["properties"]["db"].is_object());
+    assert!(fw_schema["definitions"]["DBModuleConfig"]["properties"]["max_connections"].is_object());
+    assert_eq!(fw_schema["definitions"]["LogConfig"]["properties"]["directives"]["type"], "array");
+
+    TardisConfig::register_cs_schema::<TestModuleConfig>("M1");
+    let schema = TardisConfig::schema();
+    assert_eq!(schema["properties"]["fw"]["$ref"], "#/definitions/FrameworkConfig");
+    assert_eq!(schema["properties"]["csm"]["properties"]["m1"]["$ref"], "#/definitions/TestModuleConfig");
+    assert!(schema["definitions"]["TestModuleConfig"]["properties"]["level_num"].is_object());
+
+    // registered custom configs are validated
+    let config = |m1: serde_json::Value| TardisConfig::builder().cs(HashMap::from([("m1".to_string(), m1)])).build();
+    config(json!({"name": "m1", "level_num": 1})).validate()?;
+    assert!(config(json!({"name": "m1", "level_num": "x"})).validate().unwrap_err().message.contains("csm.m1 is invalid"));
+    Ok(())
+}
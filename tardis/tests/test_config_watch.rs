@@ -0,0 +1,82 @@
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+use tardis::basic::locale::TardisLocale;
+use tardis::basic::result::TardisResult;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::TardisFuns;
+use tokio::time::sleep;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TestConfig {
+    project_name: String,
+    level_num: u8,
+}
+
+fn write_conf(dir: &Path, file: &str, content: &str) {
+    std::fs::write(dir.join(file), content).unwrap();
+}
+
+async fn wait_for_project_name(project_name: &str) {
+    for _ in 0..50 {
+        if TardisFuns::cs_config::<TestConfig>("").project_name == project_name {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("config was not reloaded to {project_name}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_watch() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=debug");
+    env::set_var("PROFILE", "test");
+    let dir = env::temp_dir().join(format!("tardis-conf-watch-{:08x}", rand::random::<u32>()));
+    std::fs::create_dir_all(dir.join("locale"))?;
+    write_conf(
+        &dir,
+        "conf-default.toml",
+        "[fw.conf_watch]\nenabled = true\ndebounce_ms = 200\n\n[cs]\nproject_name = \"default\"\nlevel_num = 1\n",
+    );
+    write_conf(&dir, "conf-test.toml", "[cs]\nproject_name = \"v1\"\n");
+    write_conf(&dir, "locale/zh-cn.tardis", "404-test-watch\t旧的\n");
+    TardisFuns::init(Some(dir.to_str().unwrap())).await?;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v1");
+
+    // files written several times in a row are reloaded once they are quiet
+    for i in 0..5 {
+        write_conf(&dir, "conf-test.toml", &format!("[cs]\nproject_name = \"v2-{i}\"\n"));
+        sleep(Duration::from_millis(20)).await;
+    }
+    wait_for_project_name("v2-4").await;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").level_num, 1);
+
+    // invalid config is rejected, the current one is kept
+    write_conf(&dir, "conf-test.toml", "[cs\nproject_name = \"v3\"\n");
+    sleep(Duration::from_millis(1000)).await;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v2-4");
+    write_conf(&dir, "conf-test.toml", "[cs]\nproject_name = \"v3\"\n");
+    wait_for_project_name("v3").await;
+
+    // locale changes are reloaded too
+    write_conf(&dir, "locale/zh-cn.tardis", "404-test-watch\t新的\n");
+    let mut translated = String::new();
+    for _ in 0..50 {
+        translated = TardisLocale::get_message("404-test-watch", "", "zh-cn")?;
+        if translated == "新的" {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(translated, "新的");
+
+    // stop watching after shutdown
+    TardisFuns::shutdown().await?;
+    write_conf(&dir, "conf-test.toml", "[cs]\nproject_name = \"v4\"\n");
+    sleep(Duration::from_millis(1000)).await;
+    assert_eq!(TardisFuns::cs_config::<TestConfig>("").project_name, "v3");
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
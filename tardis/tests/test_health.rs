@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+
+use serial_test::serial;
+use tardis::basic::error::TardisError;
+use tardis::basic::health::{HealthCheck, HealthStatus, TardisHealthReport};
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{
+    DBConfig, DBModuleConfig, FrameworkConfig, SearchConfig, SearchModuleConfig, TardisConfig, WebClientConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig,
+};
+use tardis::TardisFuns;
+use tokio::time::{sleep, Duration};
+
+struct MockBackend {
+    healthy: bool,
+}
+
+#[tardis::async_trait::async_trait]
+impl HealthCheck for MockBackend {
+    async fn health_check(&self) -> TardisResult<()> {
+        if self.healthy {
+            Ok(())
+        } else {
+            Err(TardisError::io_error("backend is down", ""))
+        }
+    }
+}
+
+struct SlowBackend;
+
+#[tardis::async_trait::async_trait]
+impl HealthCheck for SlowBackend {
+    async fn health_check(&self) -> TardisResult<()> {
+        sleep(Duration::from_secs(10)).await;
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_health_report() -> TardisResult<()> {
+    let mut report = TardisHealthReport::default();
+    report.check_component("mock", HashMap::from([("".to_string(), Arc::new(MockBackend { healthy: true }))])).await;
+    report.check_component::<MockBackend>("empty", HashMap::new()).await;
+    assert!(report.is_up());
+    assert_eq!(report.components.len(), 1);
+    assert_eq!(report.components["mock"][""].status, HealthStatus::Up);
+
+    report
+        .check_component(
+            "mock",
+            HashMap::from([
+                ("m1".to_string(), Arc::new(MockBackend { healthy: false })),
+                ("m2".to_string(), Arc::new(MockBackend { healthy: true })),
+            ]),
+        )
+        .await;
+    assert!(!report.is_up());
+    assert_eq!(report.components["mock"].len(), 3);
+    assert_eq!(report.components["mock"]["m1"].status, HealthStatus::Down);
+    assert!(report.components["mock"]["m1"].error.as_ref().unwrap().contains("backend is down"));
+    assert_eq!(report.components["mock"]["m2"].status, HealthStatus::Up);
+
+    let json = TardisFuns::json.obj_to_json(&report)?;
+    assert_eq!(json["status"], "DOWN");
+    assert_eq!(json["components"]["mock"][""]["status"], "UP");
+    assert!(json["components"]["mock"][""].get("error").is_none());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_health_timeout() -> TardisResult<()> {
+    let mut report = TardisHealthReport::default();
+    report.check_component("slow", HashMap::from([("".to_string(), Arc::new(SlowBackend))])).await;
+    assert!(!report.is_up());
+    assert!(report.components["slow"][""].elapsed_ms < 10000);
+    assert!(report.components["slow"][""].error.as_ref().unwrap().contains("timeout"));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(health_tests)]
+async fn test_health_routes() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    let port = portpicker::pick_unused_port().expect("no free port");
+    let unused_port = portpicker::pick_unused_port().expect("no free port");
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .web_server(
+                    WebServerConfig::builder()
+                        .common(WebServerCommonConfig::builder().port(port).health_check(true).build())
+                        .default(WebServerModuleConfig::builder().build())
+                        .build(),
+                )
+                .web_client(WebClientConfig::default())
+                .db(DBConfig::builder().default(DBModuleConfig::builder().url("sqlite::memory:").min_connections(1).build()).build())
+                .build())
+            .build(),
+    )
+    .await?;
+    TardisFuns::web_server().start().await?;
+    sleep(Duration::from_millis(200)).await;
+
+    let live = TardisFuns::web_client().get::<TardisHealthReport>(format!("http://127.0.0.1:{port}/health/live"), []).await?;
+    assert_eq!(live.code, 200);
+    assert!(live.body.unwrap().is_up());
+
+    let ready = TardisFuns::web_client().get::<TardisHealthReport>(format!("http://127.0.0.1:{port}/health/ready"), []).await?;
+    assert_eq!(ready.code, 200);
+    let ready = ready.body.unwrap();
+    assert!(ready.is_up());
+    assert_eq!(ready.components["reldb"][""].status, HealthStatus::Up);
+    TardisFuns::shutdown().await?;
+
+    // an unreachable search server makes the service unready
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .web_server(
+                    WebServerConfig::builder()
+                        .common(WebServerCommonConfig::builder().port(port).health_check(true).build())
+                        .default(WebServerModuleConfig::builder().build())
+                        .build(),
+                )
+                .web_client(WebClientConfig::default())
+                .search(SearchConfig::builder().default(SearchModuleConfig::builder().url(format!("http://127.0.0.1:{unused_port}").parse().expect("invalid url")).build()).build())
+                .build())
+            .build(),
+    )
+    .await?;
+    TardisFuns::web_server().start().await?;
+    sleep(Duration::from_millis(200)).await;
+    let ready = TardisFuns::web_client().get::<TardisHealthReport>(format!("http://127.0.0.1:{port}/health/ready"), []).await?;
+    assert_eq!(ready.code, 503);
+    let ready = ready.body.unwrap();
+    assert!(!ready.is_up());
+    assert_eq!(ready.components["search"][""].status, HealthStatus::Down);
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
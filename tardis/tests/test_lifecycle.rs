@@ -0,0 +1,119 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serial_test::serial;
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{FrameworkConfig, LifecycleConfig, TardisConfig, WebClientConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig};
+use tardis::web::poem;
+use tardis::TardisFuns;
+use tokio::time::{sleep, Instant};
+
+fn record(records: &Arc<Mutex<Vec<String>>>, name: &str) {
+    records.lock().unwrap().push(name.to_string());
+}
+
+fn lifecycle_config(grace_timeout_sec: u64) -> TardisConfig {
+    TardisConfig::builder().fw(FrameworkConfig::builder().lifecycle(LifecycleConfig::builder().grace_timeout_sec(grace_timeout_sec).build()).build()).build()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(lifecycle_tests)]
+async fn test_lifecycle_hooks() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    let records = Arc::new(Mutex::new(Vec::new()));
+
+    for (name, priority) in [("startup_10", 10), ("startup_0_a", 0), ("startup_-1", -1), ("startup_0_b", 0)] {
+        let records = records.clone();
+        TardisFuns::on_startup(name, priority, move || {
+            record(&records, name);
+            async { Ok(()) }
+        });
+    }
+    for (name, priority) in [("shutdown_2", 2), ("shutdown_1", 1)] {
+        let records = records.clone();
+        TardisFuns::on_shutdown(name, priority, move || {
+            let records = records.clone();
+            async move {
+                sleep(Duration::from_millis(100)).await;
+                record(&records, name);
+                Ok(())
+            }
+        });
+    }
+    {
+        let records = records.clone();
+        // failed shutdown hooks don't stop the shutdown
+        TardisFuns::on_shutdown("shutdown_fail", 0, move || {
+            record(&records, "shutdown_fail");
+            async { Err(TardisError::internal_error("shutdown failed", "")) }
+        });
+    }
+
+    TardisFuns::init_conf(lifecycle_config(5)).await?;
+    assert_eq!(*records.lock().unwrap(), vec!["startup_-1", "startup_0_a", "startup_0_b", "startup_10"]);
+    records.lock().unwrap().clear();
+
+    let wait_for_shutdown = tokio::spawn(TardisFuns::wait_for_shutdown());
+    sleep(Duration::from_millis(100)).await;
+    assert!(!wait_for_shutdown.is_finished());
+    TardisFuns::shutdown().await?;
+    tokio::time::timeout(Duration::from_secs(1), wait_for_shutdown).await.expect("shutdown should be notified").unwrap();
+    assert_eq!(*records.lock().unwrap(), vec!["shutdown_fail", "shutdown_1", "shutdown_2"]);
+    records.lock().unwrap().clear();
+
+    // shutdown hooks share the grace timeout
+    TardisFuns::on_shutdown("shutdown_slow", 3, || async {
+        sleep(Duration::from_secs(10)).await;
+        Ok(())
+    });
+    TardisFuns::init_conf(lifecycle_config(1)).await?;
+    records.lock().unwrap().clear();
+    let start = Instant::now();
+    TardisFuns::shutdown().await?;
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(*records.lock().unwrap(), vec!["shutdown_fail", "shutdown_1", "shutdown_2"]);
+
+    // failed startup hooks abort the initialization
+    TardisFuns::on_startup("startup_fail", 100, || async { Err(TardisError::internal_error("startup failed", "")) });
+    assert!(TardisFuns::init_conf(lifecycle_config(1)).await.is_err());
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
+
+#[poem::handler]
+async fn slow() -> &'static str {
+    sleep(Duration::from_secs(1)).await;
+    "done"
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial(lifecycle_tests)]
+async fn test_lifecycle_drain_web_requests() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    let port = portpicker::pick_unused_port().expect("no free port");
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .lifecycle(LifecycleConfig::builder().grace_timeout_sec(5).build())
+                .web_server(WebServerConfig::builder().common(WebServerCommonConfig::builder().port(port).build()).default(WebServerModuleConfig::builder().build()).build())
+                .web_client(WebClientConfig::default())
+                .build())
+            .build(),
+    )
+    .await?;
+    TardisFuns::web_server().add_module_raw("slow", poem::Route::new().at("/", poem::get(slow))).await;
+    TardisFuns::web_server().start().await?;
+    sleep(Duration::from_millis(200)).await;
+
+    let request = tokio::spawn(async move { TardisFuns::web_client().get_to_str(format!("http://127.0.0.1:{port}/slow"), []).await });
+    sleep(Duration::from_millis(200)).await;
+    TardisFuns::shutdown().await?;
+    let response = request.await.unwrap()?;
+    assert_eq!(response.code, 200);
+    assert_eq!(response.body.unwrap(), "done");
+    Ok(())
+}
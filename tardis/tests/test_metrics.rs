@@ -0,0 +1,90 @@
+use std::env;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBConfig, DBModuleConfig, FrameworkConfig, TardisConfig, WebClientConfig, WebServerCommonConfig, WebServerConfig, WebServerModuleConfig};
+use tardis::web::poem_openapi::{param::Path, OpenApi};
+use tardis::web::web_resp::{TardisApiResult, TardisResp};
+use tardis::TardisFuns;
+use tokio::time::{sleep, Duration};
+
+#[derive(Clone)]
+struct EchoApi;
+
+#[OpenApi]
+impl EchoApi {
+    #[oai(path = "/:msg", method = "get")]
+    async fn echo(&self, msg: Path<String>) -> TardisApiResult<String> {
+        TardisResp::ok(msg.0)
+    }
+}
+
+/// Find the value of a sample whose labels contain all the given labels
+fn sample_value(metrics: &str, name: &str, labels: &[&str]) -> Option<f64> {
+    metrics
+        .lines()
+        .filter(|line| line.starts_with(&format!("{name}{{")) || line.starts_with(&format!("{name} ")))
+        .find(|line| labels.iter().all(|label| line.contains(label)))
+        .and_then(|line| line.rsplit(' ').next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_metrics() -> TardisResult<()> {
+    env::set_var("RUST_LOG", "info,tardis=trace");
+    TardisFuns::init_log();
+    let port = portpicker::pick_unused_port().expect("no free port");
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .web_server(
+                    WebServerConfig::builder()
+                        .common(WebServerCommonConfig::builder().port(port).metrics_path("/metrics").build())
+                        .default(WebServerModuleConfig::builder().build())
+                        .build(),
+                )
+                .web_client(WebClientConfig::default())
+                .db(DBConfig::builder().default(DBModuleConfig::builder().url("sqlite::memory:").min_connections(1).build()).build())
+                .build())
+            .build(),
+    )
+    .await?;
+    TardisFuns::web_server().add_module("echo", EchoApi).await.start().await?;
+    sleep(Duration::from_millis(200)).await;
+
+    for _ in 0..3 {
+        let resp = TardisFuns::web_client().get_to_str(format!("http://127.0.0.1:{port}/echo/hi"), []).await?;
+        assert_eq!(resp.code, 200);
+    }
+    // uniform error responds 200, the metrics record the original status
+    TardisFuns::web_client().get_to_str(format!("http://127.0.0.1:{port}/echo"), []).await?;
+    TardisFuns::reldb().conn().query_all("SELECT 1", vec![]).await?;
+    assert!(TardisFuns::reldb().conn().query_all("SELECT * FROM not_exist", vec![]).await.is_err());
+
+    let metrics = TardisFuns::web_client().get_to_str(format!("http://127.0.0.1:{port}/metrics"), []).await?;
+    assert_eq!(metrics.code, 200);
+    let metrics = metrics.body.unwrap();
+    assert_eq!(
+        sample_value(&metrics, "tardis_web_requests_total", &[r#"module="echo""#, r#"method="GET""#, r#"status="200""#]),
+        Some(3.0)
+    );
+    assert_eq!(
+        sample_value(&metrics, "tardis_web_requests_total", &[r#"module="echo""#, r#"method="GET""#, r#"status="404""#]),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample_value(&metrics, "tardis_web_request_duration_seconds_count", &[r#"module="echo""#, r#"method="GET""#]),
+        Some(4.0)
+    );
+    assert_eq!(
+        sample_value(&metrics, "tardis_web_client_requests_total", &[r#"host="127.0.0.1""#, r#"method="GET""#, r#"status="200""#]),
+        Some(4.0)
+    );
+    assert_eq!(sample_value(&metrics, "tardis_reldb_query_duration_seconds_count", &[r#"op="query_all""#]), Some(2.0));
+    assert_eq!(sample_value(&metrics, "tardis_reldb_query_errors_total", &[r#"op="query_all""#]), Some(1.0));
+    assert!(sample_value(&metrics, "tardis_reldb_pool_connections", &[r#"module="""#, r#"state="idle""#]).is_some());
+
+    // metrics are rendered for other exporters as well
+    assert!(TardisFuns::metrics().render().contains("tardis_web_requests_total"));
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::serde_json::{json, Value as JsonValue};
+use tardis::test::test_reldb::TardisTestRelDB;
+use tardis::TardisFuns;
+
+fn ctx(owner: &str, ak: &str) -> TardisContext {
+    TardisContext {
+        owner: owner.to_string(),
+        ak: ak.to_string(),
+        ..Default::default()
+    }
+}
+
+fn tenant_ctx(own_paths: &str) -> TardisContext {
+    TardisContext {
+        own_paths: own_paths.to_string(),
+        owner: "a".to_string(),
+        ..Default::default()
+    }
+}
+
+fn parse(content: &Option<String>) -> Option<JsonValue> {
+    content.as_ref().map(|content| TardisFuns::json.str_to_obj(content).unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_audit() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<note::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+            // the audited tables are registered by the derive, however they are created
+            conn.create_table_from_entity(doc::Entity).await?;
+
+            // insert
+            conn.insert_one(
+                doc::ActiveModel {
+                    id: Set("d1".to_string()),
+                    name: Set("n1".to_string()),
+                    size: Set(1),
+                },
+                &ctx("a", "ak1"),
+            )
+            .await?;
+            let records = conn.find_audit_records("test_audit_doc", "d1").await?;
+            assert_eq!(records.len(), 1);
+            assert_eq!((records[0].operation.as_str(), records[0].owner.as_str(), records[0].ak.as_str()), ("insert", "a", "ak1"));
+            assert_eq!(parse(&records[0].before), None);
+            assert_eq!(parse(&records[0].after), Some(json!({"id": "d1", "name": "n1", "size": 1})));
+
+            // update one, only the changed fields are recorded
+            conn.update_one(
+                doc::ActiveModel {
+                    id: Set("d1".to_string()),
+                    name: Set("n2".to_string()),
+                    ..Default::default()
+                },
+                &ctx("b", "ak2"),
+            )
+            .await?;
+            let records = conn.find_audit_records("test_audit_doc", "d1").await?;
+            assert_eq!(records.len(), 2);
+            assert_eq!((records[1].operation.as_str(), records[1].owner.as_str()), ("update", "b"));
+            assert_eq!(parse(&records[1].before), Some(json!({"name": "n1"})));
+            assert_eq!(parse(&records[1].after), Some(json!({"name": "n2"})));
+
+            // the unchanged updates are not recorded
+            conn.update_one(
+                doc::ActiveModel {
+                    id: Set("d1".to_string()),
+                    name: Set("n2".to_string()),
+                    ..Default::default()
+                },
+                &ctx("b", "ak2"),
+            )
+            .await?;
+            assert_eq!(conn.find_audit_records("test_audit_doc", "d1").await?.len(), 2);
+
+            // update many, the actor is taken from the context of the connection
+            conn.insert_one(
+                doc::ActiveModel {
+                    id: Set("d2".to_string()),
+                    name: Set("n'2".to_string()),
+                    size: Set(2),
+                },
+                &ctx("a", "ak1"),
+            )
+            .await?;
+            client
+                .conn()
+                .with_ctx(&ctx("c", "ak3"))
+                .update_many(Query::update().table(doc::Entity).value(doc::Column::Size, 10).and_where(Expr::col(doc::Column::Name).is_in(["n2", "n'2"])))
+                .await?;
+            for (id, size) in [("d1", 1), ("d2", 2)] {
+                let records = conn.find_audit_records("test_audit_doc", id).await?;
+                let record = records.last().unwrap();
+                assert_eq!((record.operation.as_str(), record.owner.as_str(), record.ak.as_str()), ("update", "c", "ak3"));
+                assert_eq!(parse(&record.before), Some(json!({ "size": size })));
+                assert_eq!(parse(&record.after), Some(json!({"size": 10})));
+            }
+
+            // soft delete, the owner defaults to the delete user
+            conn.soft_delete(doc::Entity::find().filter(doc::Column::Id.eq("d1")), "admin").await?;
+            let records = conn.find_audit_records("test_audit_doc", "d1").await?;
+            assert_eq!(
+                records.iter().map(|record| record.operation.as_str()).collect::<Vec<_>>(),
+                vec!["insert", "update", "update", "delete"]
+            );
+            assert_eq!((records[3].owner.as_str(), records[3].ak.as_str()), ("admin", ""));
+            assert_eq!(parse(&records[3].before), Some(json!({"id": "d1", "name": "n2", "size": 10})));
+            assert_eq!(parse(&records[3].after), None);
+
+            // in the current transaction
+            let mut tx_conn = client.conn();
+            tx_conn.begin().await?;
+            tx_conn
+                .insert_one(
+                    doc::ActiveModel {
+                        id: Set("d3".to_string()),
+                        name: Set("n3".to_string()),
+                        size: Set(3),
+                    },
+                    &ctx("a", "ak1"),
+                )
+                .await?;
+            assert_eq!(tx_conn.find_audit_records("test_audit_doc", "d3").await?.len(), 1);
+            tx_conn.rollback().await?;
+            assert!(conn.find_audit_records("test_audit_doc", "d3").await?.is_empty());
+
+            // the changes within the same second are kept in order
+            for size in 0..20 {
+                conn.update_one(
+                    doc::ActiveModel {
+                        id: Set("d2".to_string()),
+                        size: Set(100 + size),
+                        ..Default::default()
+                    },
+                    &ctx("a", "ak1"),
+                )
+                .await?;
+            }
+            let records = conn.find_audit_records("test_audit_doc", "d2").await?;
+            assert_eq!(
+                records.iter().skip(2).map(|record| parse(&record.after).unwrap()).collect::<Vec<_>>(),
+                (0..20).map(|size| json!({ "size": 100 + size })).collect::<Vec<_>>()
+            );
+
+            // the records of the tenant-scoped tables are limited to the own paths of the context
+            conn.create_table_from_entity(memo::Entity).await?;
+            conn.insert_one(
+                memo::ActiveModel {
+                    id: Set("m1".to_string()),
+                    content: Set("c1".to_string()),
+                    ..Default::default()
+                },
+                &tenant_ctx("t1"),
+            )
+            .await?;
+            assert_eq!(conn.find_audit_records("test_audit_memo", "m1").await?.len(), 1);
+            assert_eq!(
+                client.conn().with_ctx(&tenant_ctx("t1")).find_audit_records("test_audit_memo", "m1").await?[0].own_paths,
+                "t1"
+            );
+            assert!(client.conn().with_ctx(&tenant_ctx("t2")).find_audit_records("test_audit_memo", "m1").await?.is_empty());
+            assert_eq!(client.conn().with_ctx(&tenant_ctx("t2")).find_audit_records("test_audit_doc", "d1").await?.len(), 4);
+
+            // the tables not audited
+            conn.insert_one(
+                note::ActiveModel {
+                    id: Set("n1".to_string()),
+                    content: Set("c1".to_string()),
+                },
+                &ctx("a", "ak1"),
+            )
+            .await?;
+            conn.update_many(Query::update().table(note::Entity).value(note::Column::Content, "c2").and_where(Expr::col(note::Column::Id).eq("n1"))).await?;
+            assert!(conn.find_audit_records("test_audit_note", "n1").await?.is_empty());
+
+            Ok(())
+        })
+        .await
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_audit_doc")]
+    #[tardis_entity(audited)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub name: String,
+        pub size: i32,
+    }
+}
+
+pub mod note {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_audit_note")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub content: String,
+    }
+}
+
+pub mod memo {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_audit_memo")]
+    #[tardis_entity(tenant_scoped, audited)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub content: String,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
@@ -0,0 +1,213 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::test::test_reldb::TardisTestRelDB;
+use tardis::web::poem_openapi::types::MaybeUndefined;
+use tardis::web::poem_openapi::OpenApiService;
+
+use crate::note::{NoteAddReq, NoteServ};
+use crate::tag::{TagAddReq, TagServ};
+use crate::todo::{TodoAddReq, TodoFilterReq, TodoModifyReq, TodoServ};
+
+fn ctx(own_paths: &str) -> TardisContext {
+    TardisContext {
+        own_paths: own_paths.to_string(),
+        owner: "acc1".to_string(),
+        ..Default::default()
+    }
+}
+
+fn add_req(code: &str, done: bool) -> TodoAddReq {
+    TodoAddReq {
+        code: code.to_string(),
+        done,
+        memo: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_crud() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<todo::ActiveModel>()
+        .entity::<note::ActiveModel>()
+        .entity::<tag::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+            let (t1, t2) = (ctx("t1"), ctx("t2"));
+
+            // add, the own paths are filled by the context
+            let id1 = TodoServ::add(add_req("code1", false), &conn, &t1).await?;
+            let id2 = TodoServ::add(add_req("code2", true), &conn, &t1).await?;
+            let id3 = TodoServ::add(add_req("code3", false), &conn, &t2).await?;
+            let detail = TodoServ::get_by_id(id1, &conn, &t1).await?.unwrap();
+            assert_eq!((detail.code.as_str(), detail.done, detail.own_paths.as_str()), ("code1", false, "t1"));
+
+            // the records of the other tenants are invisible
+            assert!(TodoServ::get_by_id(id3, &conn, &t1).await?.is_none());
+            assert!(TodoServ::modify(
+                id3,
+                TodoModifyReq {
+                    done: Some(true),
+                    ..Default::default()
+                },
+                &conn,
+                &t1
+            )
+            .await
+            .is_err());
+            assert_eq!(TodoServ::delete(id3, &conn, &t1).await?, 0);
+
+            // modify the present fields
+            TodoServ::modify(
+                id1,
+                TodoModifyReq {
+                    memo: MaybeUndefined::Value("m1".to_string()),
+                    ..Default::default()
+                },
+                &conn,
+                &t1,
+            )
+            .await?;
+            let detail = TodoServ::get_by_id(id1, &conn, &t1).await?.unwrap();
+            assert_eq!((detail.code.as_str(), detail.memo.as_deref()), ("code1", Some("m1")));
+            assert_eq!(TodoServ::modify(id1, TodoModifyReq::default(), &conn, &t1).await.unwrap_err().code, "400");
+
+            // paginate with the filters
+            let page = TodoServ::paginate(&TodoFilterReq::default(), 1, 10, &conn, &t1).await?;
+            assert_eq!(page.total_size, 2);
+            assert_eq!(page.records.iter().map(|record| record.id).collect::<Vec<_>>(), vec![id1, id2]);
+            let page = TodoServ::paginate(
+                &TodoFilterReq {
+                    done: Some(true),
+                    ..Default::default()
+                },
+                1,
+                10,
+                &conn,
+                &t1,
+            )
+            .await?;
+            assert_eq!(page.records.iter().map(|record| record.id).collect::<Vec<_>>(), vec![id2]);
+            let page = TodoServ::paginate(
+                &TodoFilterReq {
+                    code: Some("de3".to_string()),
+                    ..Default::default()
+                },
+                1,
+                10,
+                &conn,
+                &t2,
+            )
+            .await?;
+            assert_eq!(page.records.iter().map(|record| record.id).collect::<Vec<_>>(), vec![id3]);
+            let page = TodoServ::paginate(
+                &TodoFilterReq {
+                    code: Some("%".to_string()),
+                    ..Default::default()
+                },
+                1,
+                10,
+                &conn,
+                &t1,
+            )
+            .await?;
+            assert_eq!(page.total_size, 0);
+            let page = TodoServ::paginate(&TodoFilterReq::default(), 2, 1, &conn, &t1).await?;
+            assert_eq!((page.total_size, page.records.len()), (2, 1));
+
+            // soft delete
+            assert_eq!(TodoServ::delete(id1, &conn, &t1).await?, 1);
+            assert!(TodoServ::get_by_id(id1, &conn, &t1).await?.is_none());
+            assert_eq!(conn.count_by_sql("SELECT * FROM tardis_del_record WHERE entity_name = 'test_crud_todo'", vec![]).await?, 1);
+
+            // hard delete with the custom primary key, within the own paths and recorded in the audit
+            let code = NoteServ::add(
+                NoteAddReq {
+                    code: "n1".to_string(),
+                    content: "c1".to_string(),
+                },
+                &conn,
+                &t1,
+            )
+            .await?;
+            assert_eq!(code, "n1");
+            assert_eq!(NoteServ::delete("n1".to_string(), &conn, &t2).await?, 0);
+            assert!(NoteServ::get_by_id("n1".to_string(), &conn, &t1).await?.is_some());
+            assert_eq!(NoteServ::delete("n1".to_string(), &conn, &t1).await?, 1);
+            assert!(NoteServ::get_by_id("n1".to_string(), &conn, &t1).await?.is_none());
+            assert_eq!(conn.count_by_sql("SELECT * FROM tardis_del_record WHERE entity_name = 'test_crud_note'", vec![]).await?, 0);
+            let records = conn.find_audit_records("test_crud_note", "n1").await?;
+            assert_eq!(records.iter().map(|record| record.operation.as_str()).collect::<Vec<_>>(), vec!["insert", "delete"]);
+
+            // the unscoped records are shared among all the contexts
+            let tag_id = TagServ::add(TagAddReq { name: "tag1".to_string() }, &conn, &t1).await?;
+            assert!(TagServ::get_by_id(tag_id, &conn, &t2).await?.is_some());
+            assert_eq!(TagServ::paginate(&Default::default(), 1, 10, &conn, &t2).await?.total_size, 1);
+            assert_eq!(TagServ::delete(tag_id, &conn, &t2).await?, 1);
+            Ok(())
+        })
+        .await
+}
+
+#[test]
+fn test_reldb_crud_api() {
+    let spec = OpenApiService::new(todo::TodoApi, "todo", "1.0").spec();
+    assert!(spec.contains("/todo/{id}"));
+    assert!(spec.contains("page_number"));
+    assert!(spec.contains("done"));
+}
+
+pub mod todo {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_crud_todo")]
+    #[tardis_entity(tenant_scoped, dto = "Todo")]
+    #[tardis_crud(api = "/todo")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        #[tardis_crud(filter = "like")]
+        pub code: String,
+        #[tardis_crud(filter)]
+        pub done: bool,
+        pub memo: Option<String>,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
+
+pub mod note {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_crud_note")]
+    #[tardis_entity(audited, dto = "Note")]
+    #[tardis_crud(delete = "hard")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub code: String,
+        pub content: String,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
+
+pub mod tag {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisCrud, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_crud_tag")]
+    #[tardis_entity(dto = "Tag")]
+    #[tardis_crud(unscoped, delete = "hard")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub name: String,
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBConfig, DBDelRecordConfig, DBModuleConfig, FrameworkConfig, TardisConfig};
+use tardis::db::reldb_client::{TardisActiveModel, TardisRelDBClient, TardisRelDBlConnection};
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestSqliteDir;
+use tardis::TardisFuns;
+
+async fn docs(conn: &TardisRelDBlConnection) -> TardisResult<Vec<doc::Model>> {
+    conn.find_dtos(Query::select().columns(doc::Column::iter()).from(doc::Entity).order_by(doc::Column::Id, Order::Asc)).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_del_record() -> TardisResult<()> {
+    let dir = TardisTestSqliteDir::new()?;
+    let url = dir.url("test");
+    let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(url.clone()).build()).await?;
+    client.init_basic_tables().await?;
+    let conn = client.conn();
+    conn.init(doc::ActiveModel::init(DbBackend::Sqlite, None, client.compatible_type())).await?;
+    for (id, own_paths, size, note) in [("d1", "t1", 1, Some("note")), ("d2", "t1", 2, None), ("d3", "t2", 3, None)] {
+        conn.insert_one(
+            doc::ActiveModel {
+                id: Set(id.to_string()),
+                size: Set(size),
+                note: Set(note.map(|note| note.to_string())),
+                ..Default::default()
+            },
+            &TardisContext {
+                own_paths: own_paths.to_string(),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    let origin = docs(&conn).await?;
+    conn.soft_delete(doc::Entity::find().filter(doc::Column::Id.is_in(["d1", "d2"])), "admin").await?;
+    conn.soft_delete(doc::Entity::find().filter(doc::Column::Id.eq("d3")), "bob").await?;
+    assert!(docs(&conn).await?.is_empty());
+
+    // query
+    let (records, total_size) = conn.paginate_soft_deleted(Some("test_del_record_doc"), Some("admin"), 1, 1).await?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(total_size, 2);
+    assert_eq!(conn.paginate_soft_deleted(None, None, 1, 10).await?.1, 3);
+    assert_eq!(conn.paginate_soft_deleted(Some("test_del_record_doc"), Some("bob"), 1, 10).await?.0[0].creator, "bob");
+    assert_eq!(conn.paginate_soft_deleted(Some("tardis_config"), None, 1, 10).await?.1, 0);
+
+    // query in the tenant
+    let tenant = |own_paths: &str| {
+        client.conn().with_ctx(&TardisContext {
+            own_paths: own_paths.to_string(),
+            ..Default::default()
+        })
+    };
+    let (records, total_size) = tenant("t1").paginate_soft_deleted(None, None, 1, 10).await?;
+    assert_eq!(total_size, 2);
+    assert!(records.iter().all(|record| record.record_id != "\"d3\""));
+    assert_eq!(tenant("t2").paginate_soft_deleted(Some("test_del_record_doc"), None, 1, 10).await?.0[0].record_id, "\"d3\"");
+    assert_eq!(tenant("t3").paginate_soft_deleted(None, None, 1, 10).await?.1, 0);
+
+    // restore
+    conn.restore_soft_deleted("test_del_record_doc", "d1").await?;
+    assert_eq!(docs(&conn).await?, vec![origin[0].clone()]);
+    assert_eq!(conn.paginate_soft_deleted(None, None, 1, 10).await?.1, 2);
+    assert_eq!(conn.restore_soft_deleted("test_del_record_doc", "d1").await.unwrap_err().code, "404");
+
+    // restore in the current transaction
+    let mut tx_conn = client.conn();
+    tx_conn.begin().await?;
+    tx_conn.restore_soft_deleted("test_del_record_doc", "d2").await?;
+    tx_conn.rollback().await?;
+    assert_eq!(docs(&conn).await?.len(), 1);
+
+    // restore in the tenant
+    let t1 = tenant("t1");
+    assert_eq!(t1.restore_soft_deleted("test_del_record_doc", "d3").await.unwrap_err().code, "404");
+    t1.restore_soft_deleted("test_del_record_doc", "d2").await?;
+    assert_eq!(docs(&conn).await?, origin[..2].to_vec());
+
+    // purge
+    assert_eq!(conn.purge_soft_deleted(Duration::from_secs(3600)).await?, 0);
+    assert_eq!(conn.purge_soft_deleted(Duration::MAX).await?, 0);
+    conn.execute_one("UPDATE tardis_del_record SET create_time = '2000-01-01 00:00:00'", vec![]).await?;
+    assert_eq!(conn.purge_soft_deleted(Duration::from_secs(3600)).await?, 1);
+    assert_eq!(conn.paginate_soft_deleted(None, None, 1, 10).await?.1, 0);
+
+    // purge periodically
+    conn.soft_delete(doc::Entity::find().filter(doc::Column::Id.eq("d1")), "admin").await?;
+    conn.execute_one("UPDATE tardis_del_record SET create_time = '2000-01-01 00:00:00'", vec![]).await?;
+    conn.soft_delete(doc::Entity::find().filter(doc::Column::Id.eq("d2")), "admin").await?;
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .db(DBConfig::builder()
+                    .default(DBModuleConfig::builder().url(url).del_record(DBDelRecordConfig::builder().retention_sec(3600).purge_interval_sec(1).build()).build())
+                    .build())
+                .build())
+            .build(),
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let records = conn.paginate_soft_deleted(None, None, 1, 10).await?.0;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].record_id, "\"d2\"");
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_del_record_doc")]
+    #[tardis_entity(tenant_scoped)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub size: i32,
+        pub note: Option<String>,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
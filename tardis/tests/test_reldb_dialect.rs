@@ -0,0 +1,187 @@
+use tardis::config::config_dto::CompatibleType;
+use tardis::db::reldb_client::TardisActiveModel;
+use tardis::db::reldb_dialect::TardisRelDBDialect;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+
+fn doc(id: &str, name: &str) -> doc::ActiveModel {
+    doc::ActiveModel {
+        id: Set(id.to_string()),
+        name: Set(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn select(backend: DbBackend) -> Statement {
+    Statement::from_sql_and_values(backend, "SELECT id FROM doc WHERE name = $1", ["n1".into()])
+}
+
+#[test]
+fn test_reldb_dialect_paginate() {
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::None);
+    let statement = dialect.paginate(select(DbBackend::Postgres), 3, 10);
+    assert_eq!(statement.sql, "SELECT id FROM doc WHERE name = $1 LIMIT 10 OFFSET 20");
+    assert_eq!(statement.values, Some(Values(vec!["n1".into()])));
+    assert_eq!(dialect.limit(select(DbBackend::Postgres), 5).sql, "SELECT id FROM doc WHERE name = $1 LIMIT 5");
+
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::Oracle);
+    let statement = dialect.paginate(select(DbBackend::Postgres), 3, 10);
+    assert_eq!(statement.sql, "SELECT id FROM doc WHERE name = $1 OFFSET 20 ROWS FETCH NEXT 10 ROWS ONLY");
+    assert_eq!(statement.values, Some(Values(vec!["n1".into()])));
+    assert_eq!(
+        dialect.limit(select(DbBackend::Postgres), 5).sql,
+        "SELECT id FROM doc WHERE name = $1 FETCH FIRST 5 ROWS ONLY"
+    );
+}
+
+#[test]
+fn test_reldb_dialect_count() {
+    let dialect = TardisRelDBDialect::new(DbBackend::MySql, CompatibleType::None);
+    assert_eq!(
+        dialect.count(select(DbBackend::MySql)).sql,
+        "SELECT COUNT(1) AS count FROM ( SELECT id FROM doc WHERE name = $1 ) tardis_count"
+    );
+
+    let dialect = TardisRelDBDialect::new(DbBackend::MySql, CompatibleType::Oracle);
+    assert_eq!(
+        dialect.count(select(DbBackend::MySql)).sql,
+        "SELECT COUNT(1) AS \"count\" FROM ( SELECT id FROM doc WHERE name = $1 ) tardis_count"
+    );
+}
+
+#[test]
+fn test_reldb_dialect_upsert() {
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::None);
+    assert!(dialect.support_returning());
+    let statement = dialect.upsert(vec![doc("d1", "n1"), doc("d2", "n2")], &[doc::Column::Id], &[doc::Column::Name], true).unwrap();
+    assert_eq!(
+        statement.sql,
+        r#"INSERT INTO "test_dialect_doc" ("id", "name") VALUES ($1, $2), ($3, $4) ON CONFLICT ("id") DO UPDATE SET "name" = "excluded"."name" RETURNING "id""#
+    );
+
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::Oracle);
+    assert!(!dialect.support_returning());
+    let statement = dialect.upsert(vec![doc("d1", "n1"), doc("d2", "n2")], &[doc::Column::Id], &[doc::Column::Id, doc::Column::Name], true).unwrap();
+    assert_eq!(
+        statement.sql,
+        concat!(
+            r#"MERGE INTO "test_dialect_doc" "target" USING (SELECT $1 AS "id", $2 AS "name" FROM DUAL UNION ALL SELECT $3 AS "id", $4 AS "name" FROM DUAL) "source""#,
+            r#" ON ("target"."id" = "source"."id") WHEN MATCHED THEN UPDATE SET "target"."name" = "source"."name""#,
+            r#" WHEN NOT MATCHED THEN INSERT ("id", "name") VALUES ("source"."id", "source"."name")"#
+        )
+    );
+    assert_eq!(statement.values, Some(Values(vec!["d1".into(), "n1".into(), "d2".into(), "n2".into()])));
+
+    let dialect = TardisRelDBDialect::new(DbBackend::MySql, CompatibleType::Oracle);
+    let statement = dialect.upsert(vec![doc("d1", "n1")], &[doc::Column::Id], &[doc::Column::Id], false).unwrap();
+    assert_eq!(
+        statement.sql,
+        r#"MERGE INTO "test_dialect_doc" "target" USING (SELECT ? AS "id", ? AS "name" FROM DUAL) "source" ON ("target"."id" = "source"."id") WHEN NOT MATCHED THEN INSERT ("id", "name") VALUES ("source"."id", "source"."name")"#
+    );
+
+    // the models must set the same columns
+    let error = dialect
+        .upsert(
+            vec![
+                doc("d1", "n1"),
+                doc::ActiveModel {
+                    id: Set("d2".to_string()),
+                    ..Default::default()
+                },
+            ],
+            &[doc::Column::Id],
+            &[doc::Column::Name],
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(error.code, "400");
+}
+
+#[test]
+fn test_reldb_dialect_delete_by_ids() {
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::None);
+    let statement = dialect.delete_by_ids("doc", "id", vec!["d1".into(), "d2".into()]);
+    assert_eq!(statement.sql, "DELETE FROM doc WHERE id IN ($1,$2)");
+    assert_eq!(statement.values, Some(Values(vec!["d1".into(), "d2".into()])));
+
+    let dialect = TardisRelDBDialect::new(DbBackend::MySql, CompatibleType::None);
+    let statement = dialect.delete_by_ids("doc", "id", (0..1001).map(|i| i.into()).collect());
+    assert_eq!(statement.sql, format!("DELETE FROM doc WHERE id IN ({})", ["?"; 1001].join(",")));
+
+    let dialect = TardisRelDBDialect::new(DbBackend::MySql, CompatibleType::Oracle);
+    let statement = dialect.delete_by_ids("doc", "id", vec!["d1".into(), "d2".into()]);
+    assert_eq!(statement.sql, "DELETE FROM doc WHERE id IN (?,?)");
+    let statement = dialect.delete_by_ids("doc", "id", (0..1001).map(|i| i.into()).collect());
+    assert_eq!(statement.sql, format!("DELETE FROM doc WHERE (id IN ({}) OR id IN (?))", ["?"; 1000].join(",")));
+    assert_eq!(statement.values.unwrap().0.len(), 1001);
+}
+
+#[test]
+fn test_reldb_dialect_column_type() {
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::None);
+    assert_eq!(dialect.column_type(ColumnType::Text), ColumnType::Text);
+    assert_eq!(dialect.column_type(ColumnType::Boolean), ColumnType::Boolean);
+
+    let dialect = TardisRelDBDialect::new(DbBackend::Postgres, CompatibleType::Oracle);
+    assert_eq!(dialect.column_type(ColumnType::String(StringLen::N(255))), ColumnType::custom("VARCHAR2(255)"));
+    assert_eq!(dialect.column_type(ColumnType::String(StringLen::None)), ColumnType::custom("VARCHAR2(4000)"));
+    assert_eq!(dialect.column_type(ColumnType::Text), ColumnType::custom("CLOB"));
+    assert_eq!(dialect.column_type(ColumnType::Json), ColumnType::custom("CLOB"));
+    assert_eq!(dialect.column_type(ColumnType::Integer), ColumnType::custom("NUMBER(10)"));
+    assert_eq!(dialect.column_type(ColumnType::BigInteger), ColumnType::custom("NUMBER(19)"));
+    assert_eq!(dialect.column_type(ColumnType::Boolean), ColumnType::custom("NUMBER(1)"));
+    assert_eq!(dialect.column_type(ColumnType::Decimal(Some((10, 2)))), ColumnType::custom("NUMBER(10, 2)"));
+    assert_eq!(dialect.column_type(ColumnType::TimestampWithTimeZone), ColumnType::custom("TIMESTAMP WITH TIME ZONE"));
+    assert_eq!(dialect.column_type(ColumnType::Uuid), ColumnType::custom("VARCHAR2(36)"));
+    // no equivalent
+    assert_eq!(dialect.column_type(ColumnType::Cidr), ColumnType::Cidr);
+}
+
+#[test]
+fn test_reldb_dialect_create_table() {
+    let column_types = |create_table_statement: &TableCreateStatement| {
+        create_table_statement.get_columns().iter().map(|column| (column.get_column_name(), column.get_column_type().cloned())).collect::<Vec<_>>()
+    };
+
+    let (create_table_statement, _, _) = doc::ActiveModel::init(DbBackend::Postgres, None, CompatibleType::None);
+    assert_eq!(
+        column_types(&create_table_statement),
+        vec![
+            ("id".to_string(), Some(ColumnType::String(StringLen::None))),
+            ("name".to_string(), Some(ColumnType::String(StringLen::N(255)))),
+            ("size".to_string(), Some(ColumnType::Integer)),
+            ("enabled".to_string(), Some(ColumnType::Boolean)),
+        ]
+    );
+
+    let (create_table_statement, _, _) = doc::ActiveModel::init(DbBackend::Postgres, None, CompatibleType::Oracle);
+    assert_eq!(
+        column_types(&create_table_statement),
+        vec![
+            ("id".to_string(), Some(ColumnType::custom("VARCHAR2(4000)"))),
+            ("name".to_string(), Some(ColumnType::custom("VARCHAR2(255)"))),
+            ("size".to_string(), Some(ColumnType::custom("NUMBER(10)"))),
+            ("enabled".to_string(), Some(ColumnType::custom("NUMBER(1)"))),
+        ]
+    );
+    let sql = DbBackend::Postgres.build(&create_table_statement).to_string();
+    assert!(sql.contains(r#""name" VARCHAR2(255) NOT NULL"#));
+    assert!(sql.contains(r#""enabled" NUMBER(1) NOT NULL"#));
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_dialect_doc")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        #[tardis_entity(custom_len = "255")]
+        pub name: String,
+        pub size: i32,
+        pub enabled: bool,
+    }
+}
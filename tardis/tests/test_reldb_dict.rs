@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::db::domain::tardis_db_config::{self, TardisDictChangeEvent, TardisDictChangeOperation};
+use tardis::db::sea_orm::*;
+use tardis::serde::{Deserialize, Serialize};
+use tardis::test::test_reldb::TardisTestRelDB;
+use tardis::TardisFuns;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Toggle {
+    enabled: bool,
+    percent: u8,
+}
+
+fn event(own_paths: &str, key: &str, operation: TardisDictChangeOperation) -> TardisDictChangeEvent {
+    TardisDictChangeEvent {
+        own_paths: own_paths.to_string(),
+        key: key.to_string(),
+        operation,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_dict() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .run(|client| async move {
+            let conn = client.conn();
+            let mut events = TardisFuns::dict.subscribe();
+
+            // typed values
+            TardisFuns::dict.add_as("toggle", &Toggle { enabled: true, percent: 10 }, "admin", &conn).await?;
+            assert_eq!(TardisFuns::dict.get_as::<Toggle>("toggle", &conn).await?, Some(Toggle { enabled: true, percent: 10 }));
+            assert_eq!(TardisFuns::dict.get("toggle", &conn).await?.unwrap().v, r#"{"enabled":true,"percent":10}"#);
+            TardisFuns::dict.update_as("toggle", &Toggle { enabled: false, percent: 0 }, "admin", &conn).await?;
+            assert_eq!(TardisFuns::dict.get_as::<Toggle>("toggle", &conn).await?, Some(Toggle { enabled: false, percent: 0 }));
+            assert_eq!(TardisFuns::dict.get_as::<Toggle>("missing", &conn).await?, None);
+            TardisFuns::dict.add("plain", "not json", "admin", &conn).await?;
+            assert!(TardisFuns::dict.get_as::<Toggle>("plain", &conn).await.is_err());
+
+            // namespaced by own_paths
+            let t1 = TardisFuns::dict.scope("t1");
+            let t2 = TardisFuns::dict.scope("t2");
+            t1.add_as("limit", &100, "admin", &conn).await?;
+            t1.add_as("limit_x", &1, "admin", &conn).await?;
+            t2.add_as("limit", &200, "admin", &conn).await?;
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(100));
+            assert_eq!(t2.get_as::<u32>("limit", &conn).await?, Some(200));
+            assert_eq!(TardisFuns::dict.get_as::<u32>("limit", &conn).await?, None);
+            assert_eq!(t1.get("limit", &conn).await?.unwrap().k, "limit");
+            let mut keys = t1.find_all(&conn).await?.into_iter().map(|resp| resp.k).collect::<Vec<_>>();
+            keys.sort();
+            assert_eq!(keys, vec!["limit", "limit_x"]);
+            // the keys starting with the key
+            assert_eq!(t1.find_like("limit", &conn).await?.len(), 2);
+            // the root scope sees neither the other scopes nor the internal keys
+            assert_eq!(TardisFuns::dict.find_all(&conn).await?.len(), 2);
+            assert!(TardisFuns::dict.add("t1::limit", "1", "admin", &conn).await.is_err());
+            assert!(TardisFuns::dict.scope("t1::x").add("limit", "1", "admin", &conn).await.is_err());
+            assert!(TardisFuns::dict.get("t1::limit", &conn).await.is_err());
+            assert!(TardisFuns::dict.get("__tardis_migration_lock__", &conn).await.is_err());
+            assert!(TardisFuns::dict.find_like("__tardis_", &conn).await.is_err());
+
+            // change events
+            let mut received = Vec::new();
+            while let Ok(event) = events.try_recv() {
+                received.push(event);
+            }
+            assert_eq!(
+                received,
+                vec![
+                    event("", "toggle", TardisDictChangeOperation::Add),
+                    event("", "toggle", TardisDictChangeOperation::Update),
+                    event("", "plain", TardisDictChangeOperation::Add),
+                    event("t1", "limit", TardisDictChangeOperation::Add),
+                    event("t1", "limit_x", TardisDictChangeOperation::Add),
+                    event("t2", "limit", TardisDictChangeOperation::Add),
+                ]
+            );
+
+            // cached
+            TardisFuns::dict.enable_cache(None);
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(100));
+            assert_eq!(t1.get_as::<u32>("missing", &conn).await?, None);
+            let update_behind = |k: &'static str, v: &'static str| {
+                let conn = client.conn();
+                async move {
+                    tardis_db_config::Entity::update_many()
+                        .col_expr(tardis_db_config::Column::V, sea_query::Expr::value(v))
+                        .filter(tardis_db_config::Column::K.eq(k))
+                        .exec(conn.raw_conn())
+                        .await
+                }
+            };
+            update_behind("t1::limit", "101").await?;
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(100));
+            // the reads in a transaction are not cached
+            let mut tx_conn = client.conn();
+            tx_conn.begin().await?;
+            assert_eq!(t1.get_as::<u32>("limit", &tx_conn).await?, Some(101));
+            tx_conn.rollback().await?;
+            // invalidated explicitly or by the changes
+            t1.invalidate("limit").await;
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(101));
+            t1.update_as("limit", &102, "admin", &conn).await?;
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(102));
+            t1.add_as("missing", &1, "admin", &conn).await?;
+            assert_eq!(t1.get_as::<u32>("missing", &conn).await?, Some(1));
+            t1.delete("missing", &conn).await?;
+            assert_eq!(t1.get_as::<u32>("missing", &conn).await?, None);
+
+            // cached per client
+            TardisTestRelDB::sqlite()
+                .run(|other_client| async move {
+                    assert_eq!(t1.get_as::<u32>("limit", &other_client.conn()).await?, None);
+                    Ok(())
+                })
+                .await?;
+            assert_eq!(t1.get_as::<u32>("limit", &conn).await?, Some(102));
+
+            // expired
+            TardisFuns::dict.enable_cache(Some(Duration::from_millis(100)));
+            assert_eq!(t2.get_as::<u32>("limit", &conn).await?, Some(200));
+            update_behind("t2::limit", "201").await?;
+            assert_eq!(t2.get_as::<u32>("limit", &conn).await?, Some(200));
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            assert_eq!(t2.get_as::<u32>("limit", &conn).await?, Some(201));
+
+            TardisFuns::dict.disable_cache();
+            update_behind("t2::limit", "202").await?;
+            assert_eq!(t2.get_as::<u32>("limit", &conn).await?, Some(202));
+
+            Ok(())
+        })
+        .await
+}
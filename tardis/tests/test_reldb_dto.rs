@@ -0,0 +1,142 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::sea_orm::sea_query::Query;
+use tardis::db::sea_orm::*;
+use tardis::serde_json::json;
+use tardis::test::test_reldb::TardisTestRelDB;
+use tardis::web::poem_openapi::types::{MaybeUndefined, ParseFromJSON, ToJSON};
+use tardis::TardisFuns;
+
+use crate::todo::{TodoAddReq, TodoDetailResp, TodoModifyReq};
+
+fn ctx() -> TardisContext {
+    TardisContext {
+        own_paths: "t1".to_string(),
+        owner: "acc1".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_reldb_dto_json() {
+    // renamed
+    let add_req = TodoAddReq::parse_from_json(Some(json!({"code": "c1", "desc": "d1", "done": false, "memo": null}))).unwrap();
+    assert_eq!((add_req.code.as_str(), add_req.description.as_str(), add_req.done, add_req.memo), ("c1", "d1", false, None));
+    assert_eq!(TardisFuns::json.obj_to_json(&add_req).unwrap()["desc"], "d1");
+    // validators carried over
+    assert!(TodoAddReq::parse_from_json(Some(json!({"code": "c", "desc": "d1", "done": false}))).is_err());
+    assert!(TodoModifyReq::parse_from_json(Some(json!({"code": "c"}))).is_err());
+    // all optional
+    let modify_req = TodoModifyReq::parse_from_json(Some(json!({"done": true}))).unwrap();
+    assert_eq!(
+        (modify_req.code, modify_req.description, modify_req.done, modify_req.memo),
+        (None, None, Some(true), MaybeUndefined::Undefined)
+    );
+    // the nullable fields are cleared by null
+    assert_eq!(TodoModifyReq::parse_from_json(Some(json!({"memo": null}))).unwrap().memo, MaybeUndefined::Null);
+    assert_eq!(TardisFuns::json.str_to_obj::<TodoModifyReq>(r#"{"memo":null}"#).unwrap().memo, MaybeUndefined::Null);
+    assert_eq!(TardisFuns::json.str_to_obj::<TodoModifyReq>("{}").unwrap().memo, MaybeUndefined::Undefined);
+    assert_eq!(
+        TardisFuns::json.obj_to_string(&TodoModifyReq::default()).unwrap(),
+        r#"{"code":null,"desc":null,"done":null}"#
+    );
+    let detail_resp = TodoDetailResp {
+        id: 1,
+        code: "c1".to_string(),
+        description: "d1".to_string(),
+        done: true,
+        memo: None,
+        own_paths: "t1".to_string(),
+    };
+    assert_eq!(
+        detail_resp.to_json(),
+        Some(json!({"id": 1, "code": "c1", "desc": "d1", "done": true, "memo": null, "own_paths": "t1"}))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_dto_conversion() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<todo::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+
+            let add_req = TodoAddReq {
+                code: "c1".to_string(),
+                description: "d1".to_string(),
+                done: false,
+                memo: Some("m1".to_string()),
+            };
+            let id = conn.insert_one(todo::ActiveModel::from(add_req), &ctx()).await?.last_insert_id;
+
+            // only the present fields are updated
+            let mut active_model = todo::ActiveModel::from(TodoModifyReq {
+                done: Some(true),
+                ..Default::default()
+            });
+            active_model.id = Set(id);
+            conn.update_one(active_model, &ctx()).await?;
+
+            let select = Query::select()
+                .columns([
+                    todo::Column::Id,
+                    todo::Column::Code,
+                    todo::Column::Description,
+                    todo::Column::Done,
+                    todo::Column::Memo,
+                    todo::Column::OwnPaths,
+                ])
+                .from(todo::Entity)
+                .and_where(todo::Column::Id.eq(id))
+                .to_owned();
+            let detail = conn.get_dto::<TodoDetailResp>(&select).await?.unwrap();
+            assert_eq!(
+                (
+                    detail.code.as_str(),
+                    detail.description.as_str(),
+                    detail.done,
+                    detail.memo.as_deref(),
+                    detail.own_paths.as_str()
+                ),
+                ("c1", "d1", true, Some("m1"), "t1")
+            );
+
+            // the nullable fields are cleared by null
+            let mut active_model = todo::ActiveModel::from(TodoModifyReq {
+                memo: MaybeUndefined::Null,
+                ..Default::default()
+            });
+            active_model.id = Set(id);
+            conn.update_one(active_model, &ctx()).await?;
+            let detail = conn.get_dto::<TodoDetailResp>(&select).await?.unwrap();
+            assert_eq!((detail.done, detail.memo), (true, None));
+
+            Ok(())
+        })
+        .await
+}
+
+pub mod todo {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_dto_todo")]
+    #[tardis_entity(dto = "Todo")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        /// Code of the todo
+        #[dto(validator(min_length = "2", max_length = "255"))]
+        pub code: String,
+        #[dto(rename = "desc")]
+        pub description: String,
+        pub done: bool,
+        pub memo: Option<String>,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+        #[sea_orm(ignore)]
+        pub tags: Vec<String>,
+    }
+}
@@ -0,0 +1,94 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::sea_orm::sea_query::Query;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestRelDB;
+use tardis::TardisFuns;
+
+async fn add_doc(id: &str) -> TardisResult<()> {
+    let funs = TardisFuns::inst_with_db_conn("m1", None);
+    funs.db()
+        .insert_one(
+            doc::ActiveModel {
+                id: Set(id.to_string()),
+                name: Set(format!("name of {id}")),
+            },
+            &TardisContext::default(),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn doc_ids() -> TardisResult<Vec<String>> {
+    #[derive(FromQueryResult)]
+    struct IdResp {
+        id: String,
+    }
+    let ids = TardisFuns::reldb().conn().find_dtos::<IdResp>(Query::select().column(doc::Column::Id).from(doc::Entity).order_by(doc::Column::Id, Order::Asc)).await?;
+    Ok(ids.into_iter().map(|resp| resp.id).collect())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_memory_isolated_1() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .run(|client| async move {
+            add_doc("a1").await?;
+            add_doc("a2").await?;
+            // shared by the modules and the instances of the test
+            assert_eq!(doc_ids().await?, vec!["a1", "a2"]);
+            assert_eq!(
+                TardisFuns::reldb_by_module("m2").conn().count(Query::select().column(doc::Column::Id).from(doc::Entity)).await?,
+                2
+            );
+            assert_eq!(client.conn().count(Query::select().column(doc::Column::Id).from(doc::Entity)).await?, 2);
+            // the basic tables are initialized
+            TardisFuns::dict.add("k1", "v1", "admin", &client.conn()).await?;
+            assert_eq!(TardisFuns::dict.get("k1", &client.conn()).await?.unwrap().v, "v1");
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_memory_isolated_2() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .run(|_| async move {
+            add_doc("b1").await?;
+            assert_eq!(doc_ids().await?, vec!["b1"]);
+            // the spawned tasks use the database of the test in the scope
+            let ids = tokio::spawn(TardisTestRelDB::scope(async {
+                add_doc("b2").await?;
+                doc_ids().await
+            }))
+            .await
+            .unwrap()?;
+            assert_eq!(ids, vec!["b1", "b2"]);
+            Ok(())
+        })
+        .await?;
+
+    // a fresh database for each run
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .run(|_| async move {
+            assert!(doc_ids().await?.is_empty());
+            Ok(())
+        })
+        .await
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_memory_doc")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub name: String,
+    }
+}
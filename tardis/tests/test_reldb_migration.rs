@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBConfig, DBMigrationConfig, DBModuleConfig, FrameworkConfig, TardisConfig};
+use tardis::db::domain::tardis_db_config;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::db::reldb_migration::{TardisMigration, TardisMigrator};
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestSqliteDir;
+use tardis::TardisFuns;
+
+#[derive(Debug, FromQueryResult)]
+struct TodoResp {
+    title: String,
+    owner: String,
+}
+
+async fn columns(client: &TardisRelDBClient) -> TardisResult<Vec<String>> {
+    let rows = client.conn().query_all("SELECT name FROM pragma_table_info('todo') ORDER BY cid", vec![]).await?;
+    rows.iter().map(|row| Ok(row.try_get::<String>("", "name")?)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_migration() -> TardisResult<()> {
+    let dir = TardisTestSqliteDir::new()?;
+    let migration_dir = dir.path().join("migrations");
+    std::fs::create_dir_all(&migration_dir)?;
+    std::fs::write(
+        migration_dir.join("V1__create_todo.sql"),
+        "CREATE TABLE todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL);\nCREATE INDEX idx_todo_title ON todo (title);",
+    )?;
+    std::fs::write(migration_dir.join("U1__create_todo.sql"), "DROP TABLE todo;")?;
+    std::fs::write(
+        migration_dir.join("V2__add_todo_owner.sql"),
+        "ALTER TABLE todo ADD COLUMN owner VARCHAR(255) NOT NULL DEFAULT ''",
+    )?;
+    std::fs::write(
+        migration_dir.join("V2__add_todo_owner.mysql.sql"),
+        "ALTER TABLE todo ADD COLUMN owner VARCHAR(255) NOT NULL DEFAULT '' AFTER title",
+    )?;
+    std::fs::write(migration_dir.join("U2__add_todo_owner.sql"), "ALTER TABLE todo DROP COLUMN owner CASCADE")?;
+    std::fs::write(migration_dir.join("U2__add_todo_owner.sqlite.sql"), "ALTER TABLE todo DROP COLUMN owner")?;
+    std::fs::write(migration_dir.join("README.md"), "not a migration")?;
+    let url = dir.url("test");
+    let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+
+    let migrations = TardisMigration::load_dir(&migration_dir)?;
+    assert_eq!(migrations.len(), 2);
+    assert_eq!(migrations[1].description, "add todo owner");
+    assert_ne!(migrations[1].checksum(DbBackend::Sqlite), migrations[1].checksum(DbBackend::MySql));
+    assert_eq!(migrations[1].checksum(DbBackend::Sqlite), migrations[1].checksum(DbBackend::Postgres));
+    let seed = TardisMigration::func(3, "seed todo", |conn| {
+        Box::pin(async move {
+            conn.execute_one("INSERT INTO todo (title, owner) VALUES (?, ?)", vec!["first".into(), "admin".into()]).await?;
+            Ok(())
+        })
+    })
+    .rollback_func(|conn| {
+        Box::pin(async move {
+            conn.execute_one("DELETE FROM todo WHERE title = ?", vec!["first".into()]).await?;
+            Ok(())
+        })
+    });
+    let migrator = TardisMigrator::new([migrations.clone(), vec![seed.clone()]].concat());
+
+    // dry run
+    let plan = migrator.dry_run(&client).await?;
+    assert!(plan.starts_with("-- 3 pending migration(s) on Sqlite"));
+    assert!(plan.contains("-- V2 add todo owner"));
+    assert!(plan.contains("ALTER TABLE todo ADD COLUMN owner VARCHAR(255) NOT NULL DEFAULT ''\n"));
+    assert!(plan.contains("-- <rust closure>"));
+    assert!(migrator.applied(&client).await?.is_empty());
+
+    // concurrent instances migrate only once
+    let other = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+    let (versions, other_versions) = tokio::join!(migrator.migrate(&client), migrator.migrate(&other));
+    let mut versions = [versions?, other_versions?].concat();
+    versions.sort();
+    assert_eq!(versions, vec![1, 2, 3]);
+    let applied = migrator.applied(&client).await?;
+    assert_eq!(applied.iter().map(|applied| applied.version).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(applied[0].checksum, migrations[0].checksum(DbBackend::Sqlite));
+    assert_eq!(columns(&client).await?, vec!["id", "title", "owner"]);
+    let todos = client.conn().find_dtos_by_sql::<TodoResp>("SELECT title, owner FROM todo", vec![]).await?;
+    assert_eq!((todos[0].title.as_str(), todos[0].owner.as_str()), ("first", "admin"));
+    assert!(migrator.migrate(&client).await?.is_empty());
+    assert!(migrator.dry_run(&client).await?.starts_with("-- 0 pending migration(s)"));
+
+    // changed or out of order migrations
+    let changed = TardisMigrator::new(vec![
+        TardisMigration::sql(1, "create todo", "CREATE TABLE todo (id INTEGER PRIMARY KEY)"),
+        migrations[1].clone(),
+        seed.clone(),
+    ]);
+    assert_eq!(changed.migrate(&client).await.unwrap_err().code, "409");
+    let out_of_order = TardisMigrator::new([migrations.clone(), vec![seed.clone(), TardisMigration::sql(0, "too old", "SELECT 1")]].concat());
+    assert_eq!(out_of_order.migrate(&client).await.unwrap_err().code, "406");
+    let duplicate = TardisMigrator::new([migrations.clone(), vec![seed.clone(), TardisMigration::sql(3, "duplicate", "SELECT 1")]].concat());
+    assert_eq!(duplicate.migrate(&client).await.unwrap_err().code, "406");
+    assert!(tardis_db_config::Entity::find_by_id("__tardis_migration_lock__").one(client.conn().raw_conn()).await?.is_none());
+
+    // a failed migration is rolled back and can be retried
+    let failed = TardisMigrator::new(
+        [
+            migrations.clone(),
+            vec![
+                seed.clone(),
+                TardisMigration::sql(4, "failed", "CREATE TABLE tag (id INTEGER); INSERT INTO no_table VALUES (1)"),
+            ],
+        ]
+        .concat(),
+    );
+    assert!(failed.migrate(&client).await.is_err());
+    assert_eq!(migrator.applied(&client).await?.len(), 3);
+    assert!(client.conn().query_all("SELECT * FROM tag", vec![]).await.is_err());
+
+    // rollback
+    assert_eq!(migrator.rollback(&client, 1).await?, vec![3, 2]);
+    assert_eq!(columns(&client).await?, vec!["id", "title"]);
+    assert_eq!(client.conn().count_by_sql("SELECT * FROM todo", vec![]).await?, 0);
+    let no_rollback = TardisMigrator::new(vec![TardisMigration::sql(1, "create todo", "")]);
+    assert_eq!(no_rollback.rollback(&client, 0).await.unwrap_err().code, "404");
+    assert_eq!(migrator.rollback(&client, 0).await?, vec![1]);
+    assert!(columns(&client).await?.is_empty());
+
+    // a stale lock is taken over
+    let crashed_at = chrono::Utc::now() - chrono::Duration::seconds(10);
+    tardis_db_config::ActiveModel {
+        k: Set("__tardis_migration_lock__".to_string()),
+        v: Set("crashed".to_string()),
+        creator: Set("test".to_string()),
+        updater: Set("test".to_string()),
+        create_time: Set(crashed_at),
+        update_time: Set(crashed_at),
+    }
+    .insert(client.conn().raw_conn())
+    .await?;
+    assert_eq!(migrator.clone().lock_timeout(Duration::from_secs(1)).migrate(&client).await?, vec![1, 2, 3]);
+
+    // migrate on startup
+    TardisMigrator::register("m1", vec![seed]);
+    let m1_url = dir.url("m1");
+    let migration = DBMigrationConfig::builder().enabled(true).dir(migration_dir.to_str().unwrap()).build();
+    TardisFuns::init_conf(
+        TardisConfig::builder()
+            .fw(FrameworkConfig::builder()
+                .db(DBConfig::builder()
+                    .default(DBModuleConfig::builder().url(dir.url("default")).migration(DBMigrationConfig::builder().build()).build())
+                    .modules([("m1".to_string(), DBModuleConfig::builder().url(m1_url).migration(migration).build())])
+                    .build())
+                .build())
+            .build(),
+    )
+    .await?;
+    assert_eq!(columns(&TardisFuns::reldb_by_module("m1")).await?, vec!["id", "title", "owner"]);
+    assert_eq!(migrator.applied(&TardisFuns::reldb_by_module("m1")).await?.len(), 3);
+    assert!(TardisFuns::reldb().conn().query_all("SELECT * FROM tardis_migration", vec![]).await.is_err());
+
+    TardisFuns::shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_migration_lock_heartbeat() -> TardisResult<()> {
+    let dir = TardisTestSqliteDir::new()?;
+    let url = dir.url("test");
+    let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+    let other = TardisRelDBClient::init(&DBModuleConfig::builder().url(&url).build()).await?;
+    // the migration outlasts the lock timeout, the lock is refreshed and not taken over
+    let slow = TardisMigration::func(1, "slow", |_| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(2500)).await;
+            Ok(())
+        })
+    });
+    let migrator = TardisMigrator::new(vec![slow]).lock_timeout(Duration::from_secs(1));
+    let (versions, other_versions) = tokio::join!(migrator.migrate(&client), async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        migrator.migrate(&other).await
+    });
+    assert_eq!(versions?, vec![1]);
+    assert!(other_versions?.is_empty());
+    assert_eq!(migrator.applied(&client).await?.len(), 1);
+    Ok(())
+}
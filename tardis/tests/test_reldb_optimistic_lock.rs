@@ -0,0 +1,103 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::reldb_client::TardisRelDBlConnection;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestRelDB;
+
+async fn get_doc(conn: &TardisRelDBlConnection, id: &str) -> TardisResult<doc::Model> {
+    Ok(conn.get_dto(Query::select().columns(doc::Column::iter()).from(doc::Entity).and_where(Expr::col(doc::Column::Id).eq(id))).await?.unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_optimistic_lock() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+            let ctx = TardisContext::default();
+            conn.insert_one(
+                doc::ActiveModel {
+                    id: Set("d1".to_string()),
+                    title: Set("v0".to_string()),
+                    version: Set(0),
+                },
+                &ctx,
+            )
+            .await?;
+
+            // two editors read the same version
+            let mut editor1: doc::ActiveModel = get_doc(&conn, "d1").await?.into();
+            let mut editor2: doc::ActiveModel = get_doc(&conn, "d1").await?.into();
+            editor1.title = Set("v1".to_string());
+            conn.update_one(editor1, &ctx).await?;
+            let doc = get_doc(&conn, "d1").await?;
+            assert_eq!(doc.title, "v1");
+            assert_eq!(doc.version, 1);
+
+            // the stale version is rejected
+            editor2.title = Set("v1-stale".to_string());
+            let error = conn.update_one(editor2, &ctx).await.unwrap_err();
+            assert_eq!(error.code, "409");
+            assert_eq!(get_doc(&conn, "d1").await?.title, "v1");
+
+            // the explicitly set version is used as well
+            conn.update_one(
+                doc::ActiveModel {
+                    id: Set("d1".to_string()),
+                    title: Set("v2".to_string()),
+                    version: Set(1),
+                },
+                &ctx,
+            )
+            .await?;
+            assert_eq!(get_doc(&conn, "d1").await?.version, 2);
+
+            // the version is required
+            let error = conn
+                .update_one(
+                    doc::ActiveModel {
+                        id: Set("d1".to_string()),
+                        title: Set("v3".to_string()),
+                        ..Default::default()
+                    },
+                    &ctx,
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, "400");
+
+            // the missing record
+            let error = conn
+                .update_one(
+                    doc::ActiveModel {
+                        id: Set("d2".to_string()),
+                        title: Set("v1".to_string()),
+                        version: Set(0),
+                    },
+                    &ctx,
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, "409");
+
+            Ok(())
+        })
+        .await
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_optimistic_lock_doc")]
+    #[tardis_entity(version = "version")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub title: String,
+        pub version: i32,
+    }
+}
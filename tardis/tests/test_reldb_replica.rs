@@ -0,0 +1,89 @@
+use tardis::basic::health::HealthCheck;
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBModuleConfig, DBReplicaPolicy};
+use tardis::db::reldb_client::{TardisRelDBClient, TardisRelDBlConnection};
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestSqliteDir;
+
+#[derive(Debug, FromQueryResult)]
+struct NameResp {
+    name: String,
+}
+
+async fn init_db(url: &str, name: &str) -> TardisResult<()> {
+    let conn = TardisRelDBClient::init(&DBModuleConfig::builder().url(url).build()).await?.conn();
+    conn.execute_one("CREATE TABLE test_replica (name TEXT NOT NULL)", vec![]).await?;
+    conn.execute_one("INSERT INTO test_replica (name) VALUES (?)", vec![name.into()]).await?;
+    Ok(())
+}
+
+async fn names(conn: &TardisRelDBlConnection) -> TardisResult<Vec<String>> {
+    Ok(conn.find_dtos_by_sql::<NameResp>("SELECT name FROM test_replica ORDER BY name", vec![]).await?.into_iter().map(|resp| resp.name).collect())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_replica() -> TardisResult<()> {
+    let dir = TardisTestSqliteDir::new()?;
+    let url = |name: &str| dir.url(name);
+    init_db(&url("primary"), "primary").await?;
+    init_db(&url("replica1"), "replica1").await?;
+    init_db(&url("replica2"), "replica2").await?;
+
+    let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(url("primary")).replica_urls(vec![url("replica1"), url("replica2")]).build()).await?;
+    client.health_check().await?;
+
+    // queries outside a transaction go to the replicas in turn
+    let conn = client.conn();
+    assert!(conn.is_replica());
+    assert_eq!(names(&conn).await?, vec!["replica1"]);
+    assert_eq!(names(&client.conn()).await?, vec!["replica2"]);
+    assert_eq!(names(&client.conn()).await?, vec!["replica1"]);
+    let select = Query::select().column(Alias::new("name")).from(Alias::new("test_replica")).to_owned();
+    assert_eq!(conn.find_dtos::<NameResp>(&select).await?[0].name, "replica1");
+    assert_eq!(conn.paginate_dtos::<NameResp>(&select, 1, 10).await?.0[0].name, "replica1");
+    assert_eq!(conn.query_all("SELECT name FROM test_replica", vec![]).await?[0].try_get::<String>("", "name")?, "replica1");
+
+    // writes go to the primary
+    conn.execute_one("INSERT INTO test_replica (name) VALUES (?)", vec!["primary2".into()]).await?;
+    assert_eq!(conn.count(&select).await?, 1);
+    assert_eq!(conn.count_by_sql("SELECT name FROM test_replica", vec![]).await?, 1);
+    assert_eq!(
+        conn.get_dto_by_sql::<NameResp>("SELECT name FROM test_replica ORDER BY name DESC", vec![]).await?.unwrap().name,
+        "primary2"
+    );
+
+    // the bookkeeping records are read from the primary, the basic tables are not in the replicas
+    client.init_basic_tables().await?;
+    let bookkeeping_conn = client.conn();
+    assert!(bookkeeping_conn.is_replica());
+    assert_eq!(bookkeeping_conn.paginate_soft_deleted(None, None, 1, 10).await?.1, 0);
+    assert!(bookkeeping_conn.find_audit_records("test_replica", "primary2").await?.is_empty());
+
+    // read your writes from the primary
+    let conn = conn.primary();
+    assert!(!conn.is_replica());
+    assert_eq!(names(&conn).await?, vec!["primary", "primary2"]);
+
+    // queries inside a transaction go to the primary
+    let mut conn = client.conn();
+    conn.begin().await?;
+    conn.execute_one("INSERT INTO test_replica (name) VALUES (?)", vec!["primary3".into()]).await?;
+    assert_eq!(names(&conn).await?, vec!["primary", "primary2", "primary3"]);
+    conn.commit().await?;
+
+    // without replicas, all operations go to the primary
+    let client = TardisRelDBClient::init(&DBModuleConfig::builder().url(url("primary")).build()).await?;
+    assert!(!client.conn().is_replica());
+    assert_eq!(names(&client.conn()).await?.len(), 3);
+
+    // random policy
+    let client = TardisRelDBClient::init(
+        &DBModuleConfig::builder().url(url("primary")).replica_urls(vec![url("replica1"), url("replica2")]).replica_policy(DBReplicaPolicy::Random).build(),
+    )
+    .await?;
+    for _ in 0..10 {
+        assert!(names(&client.conn()).await?[0].starts_with("replica"));
+    }
+    Ok(())
+}
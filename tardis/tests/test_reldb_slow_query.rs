@@ -0,0 +1,55 @@
+use tardis::basic::result::TardisResult;
+use tardis::config::config_dto::{DBModuleConfig, DBSlowQueryConfig};
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::db::reldb_trace::{db_system, redact_sql};
+use tardis::db::sea_orm::*;
+
+#[test]
+fn test_reldb_redact_sql() {
+    assert_eq!(redact_sql("SELECT * FROM doc WHERE id = $1"), "SELECT * FROM doc WHERE id = $1");
+    assert_eq!(
+        redact_sql("SELECT * FROM doc WHERE name = 'n1' AND id = 1"),
+        "SELECT * FROM doc WHERE name = '?' AND id = ?"
+    );
+    // the numeric literals are redacted, the digits of the identifiers and the placeholders are kept
+    assert_eq!(
+        redact_sql("SELECT col_2 FROM t1 WHERE size > -1.5e-3 AND id IN (0x1f, 42) AND age = ? AND code = $12 LIMIT 10"),
+        "SELECT col_2 FROM t1 WHERE size > -? AND id IN (?, ?) AND age = ? AND code = $12 LIMIT ?"
+    );
+    assert_eq!(redact_sql(r#"SELECT "t1"."c2" FROM "t1""#), r#"SELECT "t1"."c2" FROM "t1""#);
+    assert_eq!(
+        redact_sql("UPDATE doc SET name = 'it''s', memo = 'a\\'b' WHERE id = 'd1'"),
+        "UPDATE doc SET name = '?', memo = '?' WHERE id = '?'"
+    );
+    // the quoted identifiers are kept
+    assert_eq!(
+        redact_sql(r#"SELECT "it's" FROM `doc's` WHERE name = ''"#),
+        r#"SELECT "it's" FROM `doc's` WHERE name = '?'"#
+    );
+    // unterminated literal
+    assert_eq!(redact_sql("SELECT 'abc"), "SELECT '?'");
+
+    assert_eq!(db_system(DbBackend::MySql), "mysql");
+    assert_eq!(db_system(DbBackend::Postgres), "postgresql");
+    assert_eq!(db_system(DbBackend::Sqlite), "sqlite");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_slow_query() -> TardisResult<()> {
+    // every statement is slow, the explain statements must not disturb the queries
+    let client = TardisRelDBClient::init(
+        &DBModuleConfig::builder().url("sqlite::memory:").min_connections(1).slow_query(DBSlowQueryConfig::builder().threshold_ms(0).explain(true).build()).build(),
+    )
+    .await?;
+    let conn = client.conn();
+    conn.execute_one("CREATE TABLE doc (id TEXT PRIMARY KEY, name TEXT NOT NULL)", vec![]).await?;
+    conn.execute_one("INSERT INTO doc (id, name) VALUES ($1, $2)", vec!["d1".into(), "n1".into()]).await?;
+    conn.execute_one("INSERT INTO doc (id, name) VALUES ('d2', 'n2')", vec![]).await?;
+    let rows = conn.query_all("SELECT * FROM doc WHERE name <> $1 ORDER BY id", vec!["n0".into()]).await?;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].try_get::<String>("", "name")?, "n1");
+    assert!(conn.query_one("SELECT * FROM doc WHERE id = 'd3'", vec![]).await?.is_none());
+    // a failed statement is also reported
+    assert!(conn.query_all("SELECT * FROM not_exists", vec![]).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::futures::TryStreamExt;
+use tardis::test::test_reldb::TardisTestRelDB;
+
+#[derive(Debug, FromQueryResult)]
+struct RowResp {
+    id: i64,
+    own_paths: String,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_stream() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .run(|client| async move {
+            let conn = client.conn();
+            conn.execute_one("CREATE TABLE test_stream (id INTEGER PRIMARY KEY, own_paths TEXT NOT NULL)", vec![]).await?;
+            for id in 1..=250 {
+                conn.execute_one(
+                    "INSERT INTO test_stream (id, own_paths) VALUES (?, ?)",
+                    vec![id.into(), if id % 2 == 0 { "t1" } else { "t2" }.into()],
+                )
+                .await?;
+            }
+            let select = Query::select().columns([Alias::new("id"), Alias::new("own_paths")]).from(Alias::new("test_stream")).order_by(Alias::new("id"), Order::Asc).to_owned();
+
+            // stream
+            let rows: Vec<RowResp> = conn.stream_dtos(&select).await?.try_collect().await?;
+            assert_eq!(rows.len(), 250);
+            assert_eq!(rows.iter().map(|row| row.id).collect::<Vec<_>>(), (1..=250).collect::<Vec<_>>());
+            let mut rows = conn.stream_by_sql::<RowResp>("SELECT id, own_paths FROM test_stream WHERE id > ? ORDER BY id", vec![240.into()]).await?;
+            let mut count = 0;
+            while let Some(row) = rows.try_next().await? {
+                assert!(row.id > 240);
+                count += 1;
+            }
+            assert_eq!(count, 10);
+            let rows = conn.stream_by_sql::<RowResp>("SELECT * FROM test_stream_not_exist", vec![]).await;
+            assert!(rows.is_err() || rows?.try_collect::<Vec<_>>().await.is_err());
+
+            // stream inside a transaction
+            let mut tx_conn = client.conn();
+            tx_conn.begin().await?;
+            tx_conn.execute_one("INSERT INTO test_stream (id, own_paths) VALUES (?, ?)", vec![251.into(), "t1".into()]).await?;
+            {
+                let rows: Vec<RowResp> = tx_conn.stream_dtos(&select).await?.try_collect().await?;
+                assert_eq!(rows.len(), 251);
+            }
+            tx_conn.rollback().await?;
+            assert_eq!(conn.count(&select).await?, 250);
+
+            // stream in the tenant
+            TardisRelDBClient::register_tenant_table("test_stream", "own_paths");
+            let t1 = client.conn().with_ctx(&TardisContext {
+                own_paths: "t1".to_string(),
+                ..Default::default()
+            });
+            let rows: Vec<RowResp> = t1.stream_dtos(&select).await?.try_collect().await?;
+            assert_eq!(rows.len(), 125);
+            assert!(rows.iter().all(|row| row.own_paths == "t1"));
+
+            // keyset pagination
+            let mut after = None;
+            let mut ids = Vec::new();
+            loop {
+                let rows = conn.paginate_dtos_by_keyset::<RowResp, _>(&select, Alias::new("id"), Order::Asc, after, 100).await?;
+                ids.extend(rows.iter().map(|row| row.id));
+                match rows.last() {
+                    Some(row) if rows.len() == 100 => after = Some(row.id),
+                    _ => break,
+                }
+            }
+            assert_eq!(ids, (1..=250).collect::<Vec<_>>());
+            let rows = conn.paginate_dtos_by_keyset::<RowResp, _>(&select, Alias::new("id"), Order::Desc, Some(101), 3).await?;
+            assert_eq!(rows.iter().map(|row| row.id).collect::<Vec<_>>(), vec![100, 99, 98]);
+            let rows = t1.paginate_dtos_by_keyset::<RowResp, _>(&select, Alias::new("id"), Order::Asc, Some(10), 3).await?;
+            assert_eq!(rows.iter().map(|row| row.id).collect::<Vec<_>>(), vec![12, 14, 16]);
+
+            Ok(())
+        })
+        .await
+}
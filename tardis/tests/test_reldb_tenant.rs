@@ -0,0 +1,187 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::reldb_client::TardisRelDBClient;
+use tardis::db::reldb_tenant::scope_entity_select;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestRelDB;
+
+#[derive(Debug, FromQueryResult)]
+struct NameResp {
+    name: String,
+}
+
+fn ctx(own_paths: &str) -> TardisContext {
+    TardisContext {
+        own_paths: own_paths.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_tenant() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .entity::<tag::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+            for (name, own_paths) in [("t1-doc", "t1"), ("t1-app1-doc", "t1/app1"), ("t2-doc", "t2"), ("tx1-doc", "tx1")] {
+                conn.insert_one(
+                    doc::ActiveModel {
+                        id: Set(name.to_string()),
+                        name: Set(name.to_string()),
+                        ..Default::default()
+                    },
+                    &ctx(own_paths),
+                )
+                .await?;
+                conn.insert_one(
+                    tag::ActiveModel {
+                        doc_name: Set(name.to_string()),
+                        tag: Set("tag".to_string()),
+                        ..Default::default()
+                    },
+                    &ctx(own_paths),
+                )
+                .await?;
+            }
+            let names = |docs: Vec<NameResp>| docs.into_iter().map(|doc| doc.name).collect::<Vec<_>>();
+            let select = Query::select().column(doc::Column::Name).from(doc::Entity).order_by(doc::Column::Name, Order::Asc).to_owned();
+
+            // select
+            let t1 = client.conn().with_ctx(&ctx("t1"));
+            assert_eq!(t1.tenant_own_paths(), Some("t1"));
+            assert_eq!(names(t1.find_dtos(&select).await?), vec!["t1-app1-doc", "t1-doc"]);
+            assert_eq!(t1.count(&select).await?, 2);
+            assert_eq!(t1.paginate_dtos::<NameResp>(&select, 1, 1).await?.1, 2);
+            assert!(t1.get_dto::<NameResp>(&select.clone().and_where(Expr::col(doc::Column::Name).eq("t2-doc")).to_owned()).await?.is_none());
+            assert_eq!(names(client.conn().with_ctx(&ctx("t1/app1")).find_dtos(&select).await?), vec!["t1-app1-doc"]);
+            assert_eq!(names(client.conn().with_ctx(&ctx("t1_")).find_dtos(&select).await?), Vec::<String>::new());
+            assert_eq!(client.conn().with_ctx(&ctx("")).count(&select).await?, 4);
+            assert_eq!(t1.count_by_sql("SELECT name FROM test_tenant_doc", vec![]).await?, 4);
+
+            // aliases and joins
+            let joined = Query::select()
+                .column((Alias::new("d"), doc::Column::Name))
+                .from_as(doc::Entity, Alias::new("d"))
+                .left_join(tag::Entity, Expr::col((Alias::new("d"), doc::Column::Name)).equals((tag::Entity, tag::Column::DocName)))
+                .to_owned();
+            assert_eq!(t1.count(&joined).await?, 2);
+            let tags = Query::select()
+                .column(tag::Column::Tag)
+                .from(tag::Entity)
+                .inner_join(doc::Entity, Expr::col((doc::Entity, doc::Column::Name)).equals((tag::Entity, tag::Column::DocName)))
+                .to_owned();
+            assert_eq!(t1.count(&tags).await?, 2);
+
+            // update
+            let update = Query::update().table(doc::Entity).value(doc::Column::Name, Expr::cust("name || '-updated'")).to_owned();
+            t1.update_many(&update).await?;
+            assert_eq!(
+                client.conn().count(&select.clone().and_where(Expr::col(doc::Column::Name).like("%-updated")).to_owned()).await?,
+                2
+            );
+
+            // update one, the record of the other tenant is not updated
+            let rename = |id: &str, name: &str| doc::ActiveModel {
+                id: Set(id.to_string()),
+                name: Set(name.to_string()),
+                ..Default::default()
+            };
+            t1.update_one(rename("t2-doc", "t2-doc-by-t1"), &ctx("t1")).await?;
+            assert_eq!(t1.update_one_with_condition(rename("t2-doc", "t2-doc-by-t1"), Condition::all(), &ctx("t1")).await?, 0);
+            assert_eq!(
+                client.conn().count(&select.clone().and_where(Expr::col(doc::Column::Name).eq("t2-doc")).to_owned()).await?,
+                1
+            );
+            assert_eq!(t1.update_one_with_condition(rename("t1-doc", "t1-doc-renamed"), Condition::all(), &ctx("t1")).await?, 1);
+
+            // soft delete
+            let t2 = client.conn().with_ctx(&ctx("t2"));
+            assert_eq!(t2.soft_delete(doc::Entity::find(), "admin").await?, 1);
+            assert_eq!(t2.soft_delete_custom(tag::Entity::find(), "doc_name").await?.len(), 1);
+            assert_eq!(client.conn().count(&select).await?, 3);
+
+            // bypass
+            let admin = t1.bypass_tenant();
+            assert_eq!(admin.tenant_own_paths(), None);
+            assert_eq!(admin.count(&select).await?, 3);
+
+            // tables of the tenant-scoped entities are scoped however they are created
+            conn.create_table_from_entity(note::Entity).await?;
+            for (name, own_paths) in [("t1-note", "t1"), ("t2-note", "t2")] {
+                conn.insert_one(
+                    note::ActiveModel {
+                        name: Set(name.to_string()),
+                        ..Default::default()
+                    },
+                    &ctx(own_paths),
+                )
+                .await?;
+            }
+            let notes = Query::select().column(note::Column::Name).from(note::Entity).to_owned();
+            assert_eq!(names(client.conn().with_ctx(&ctx("t2")).find_dtos(&notes).await?), vec!["t2-note"]);
+            let notes = scope_entity_select(note::Entity::find(), "t1").all(conn.raw_conn()).await?;
+            assert_eq!(notes.into_iter().map(|note| note.name).collect::<Vec<_>>(), vec!["t1-note"]);
+
+            // tables registered manually
+            TardisRelDBClient::register_tenant_table("test_tenant_manual", "own_paths");
+            conn.execute_one("CREATE TABLE test_tenant_manual (name TEXT NOT NULL, own_paths TEXT NOT NULL)", vec![]).await?;
+            conn.execute_one("INSERT INTO test_tenant_manual VALUES ('t1-manual', 't1'), ('t2-manual', 't2')", vec![]).await?;
+            let manual = Query::select().column(Alias::new("name")).from(Alias::new("test_tenant_manual")).to_owned();
+            assert_eq!(names(client.conn().with_ctx(&ctx("t2")).find_dtos(&manual).await?), vec!["t2-manual"]);
+
+            Ok(())
+        })
+        .await
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_tenant_doc")]
+    #[tardis_entity(tenant_scoped)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub name: String,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
+
+pub mod tag {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_tenant_tag")]
+    #[tardis_entity(tenant_scoped)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub doc_name: String,
+        pub tag: String,
+        #[fill_ctx(fill = "own_paths")]
+        pub tag_own_paths: String,
+    }
+}
+
+pub mod note {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_tenant_note")]
+    #[tardis_entity(tenant_scoped)]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub name: String,
+        #[fill_ctx(fill = "own_paths")]
+        pub own_paths: String,
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use tardis::basic::error::TardisError;
+use tardis::basic::result::TardisResult;
+use tardis::db::reldb_client::{TardisRelDBlConnection, TardisTxRetryPolicy};
+use tardis::test::test_reldb::TardisTestRelDB;
+
+async fn insert(conn: &TardisRelDBlConnection, name: &str) -> TardisResult<()> {
+    conn.execute_one("INSERT INTO test_tx (name) VALUES (?)", vec![name.into()]).await?;
+    Ok(())
+}
+
+async fn count(conn: &TardisRelDBlConnection) -> TardisResult<u64> {
+    conn.count_by_sql("SELECT name FROM test_tx", vec![]).await
+}
+
+fn deadlock() -> TardisError {
+    TardisError::conflict(
+        r#"[Tardis.RelDBClient] Exec(SqlxError(Database(PgDatabaseError { severity: Error, code: "40P01", message: "deadlock detected" })))"#,
+        TardisTxRetryPolicy::RETRYABLE_CODE,
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_transaction() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .run(|client| async move {
+            let conn = client.conn();
+            conn.execute_one("CREATE TABLE test_tx (name TEXT NOT NULL)", vec![]).await?;
+
+            // commit on ok
+            let result = conn
+                .transaction(|tx| {
+                    Box::pin(async move {
+                        insert(tx, "a").await?;
+                        count(tx).await
+                    })
+                })
+                .await?;
+            assert_eq!(result, 1);
+            assert_eq!(count(&conn).await?, 1);
+
+            // rollback on error
+            let error = conn
+                .transaction(|tx| {
+                    Box::pin(async move {
+                        insert(tx, "b").await?;
+                        Err::<(), _>(TardisError::bad_request("failed", ""))
+                    })
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, "400");
+            assert_eq!(count(&conn).await?, 1);
+
+            // rollback on panic
+            let panic_client = client.clone();
+            let join = tokio::spawn(async move {
+                panic_client
+                    .conn()
+                    .transaction(|tx| {
+                        Box::pin(async move {
+                            insert(tx, "c").await?;
+                            panic!("failed");
+                            #[allow(unreachable_code)]
+                            Ok(())
+                        })
+                    })
+                    .await
+            })
+            .await;
+            assert!(join.unwrap_err().is_panic());
+            assert_eq!(count(&conn).await?, 1);
+
+            // nested transactions by savepoints
+            conn.transaction(|tx| {
+                Box::pin(async move {
+                    insert(tx, "d").await?;
+                    let nested = tx
+                        .transaction(|nested| {
+                            Box::pin(async move {
+                                insert(nested, "e").await?;
+                                Err::<(), _>(TardisError::bad_request("failed", ""))
+                            })
+                        })
+                        .await;
+                    assert!(nested.is_err());
+                    tx.transaction(|nested| Box::pin(async move { insert(nested, "f").await })).await?;
+                    Ok(())
+                })
+            })
+            .await?;
+            assert_eq!(conn.count_by_sql("SELECT name FROM test_tx WHERE name IN ('d', 'f')", vec![]).await?, 2);
+            assert_eq!(count(&conn).await?, 3);
+
+            // manual transaction with a nested one
+            let mut tx_conn = client.conn();
+            tx_conn.begin().await?;
+            insert(&tx_conn, "g").await?;
+            tx_conn.transaction(|nested| Box::pin(async move { insert(nested, "h").await })).await?;
+            tx_conn.rollback().await?;
+            assert_eq!(count(&conn).await?, 3);
+
+            // retry on deadlocks
+            let policy = TardisTxRetryPolicy::builder().initial_backoff(Duration::from_millis(1)).build();
+            let attempts = AtomicU32::new(0);
+            conn.transaction_with_retry(&policy, |tx| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    insert(tx, "i").await?;
+                    if attempt < 2 {
+                        return Err(deadlock());
+                    }
+                    Ok(())
+                })
+            })
+            .await?;
+            assert_eq!(attempts.load(Ordering::SeqCst), 3);
+            assert_eq!(conn.count_by_sql("SELECT name FROM test_tx WHERE name = 'i'", vec![]).await?, 1);
+
+            // give up after the max retries
+            let attempts = AtomicU32::new(0);
+            let result = conn
+                .transaction_with_retry(&policy, |_| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async move { Err::<(), _>(deadlock()) })
+                })
+                .await;
+            let error = result.unwrap_err();
+            assert!(TardisTxRetryPolicy::is_retryable(&error));
+            assert_eq!(error.code, "409");
+            assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+            // the other errors are not retried
+            let attempts = AtomicU32::new(0);
+            let result = conn
+                .transaction_with_retry(&policy, |_| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async move { Err::<(), _>(TardisError::conflict("failed", "")) })
+                })
+                .await;
+            assert!(!TardisTxRetryPolicy::is_retryable(&result.unwrap_err()));
+            // the errors are classified by the locale code instead of the code or the message
+            assert!(!TardisTxRetryPolicy::is_retryable(&TardisError::custom(TardisTxRetryPolicy::RETRYABLE_CODE, "failed", "")));
+            assert!(!TardisTxRetryPolicy::is_retryable(&TardisError::wrap(
+                r#"[Tardis.RelDBClient] Custom("code: \"40001\"")"#,
+                "-1-tardis-reldb-error"
+            )));
+            assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        })
+        .await
+}
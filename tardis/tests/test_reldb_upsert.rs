@@ -0,0 +1,106 @@
+use tardis::basic::dto::TardisContext;
+use tardis::basic::result::TardisResult;
+use tardis::db::reldb_client::TardisRelDBlConnection;
+use tardis::db::sea_orm::sea_query::*;
+use tardis::db::sea_orm::*;
+use tardis::test::test_reldb::TardisTestRelDB;
+
+fn ctx(owner: &str) -> TardisContext {
+    TardisContext {
+        owner: owner.to_string(),
+        ..Default::default()
+    }
+}
+
+fn doc(id: &str, name: &str) -> doc::ActiveModel {
+    doc::ActiveModel {
+        id: Set(id.to_string()),
+        name: Set(name.to_string()),
+        ..Default::default()
+    }
+}
+
+async fn get_doc(conn: &TardisRelDBlConnection, id: &str) -> TardisResult<Option<doc::Model>> {
+    conn.get_dto(Query::select().columns(doc::Column::iter()).from(doc::Entity).and_where(Expr::col(doc::Column::Id).eq(id))).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reldb_upsert() -> TardisResult<()> {
+    TardisTestRelDB::sqlite()
+        .entity::<doc::ActiveModel>()
+        .run(|client| async move {
+            let conn = client.conn();
+            let select = Query::select().column(doc::Column::Id).from(doc::Entity).to_owned();
+
+            // insert
+            let result = conn.upsert_one(doc("d1", "n1"), &ctx("a")).await?;
+            assert_eq!(result.rows_affected, 1);
+            assert_eq!(result.ids.is_some(), conn.raw_conn().support_returning());
+            let d1 = get_doc(&conn, "d1").await?.unwrap();
+            assert_eq!((d1.name.as_str(), d1.owner.as_str(), d1.updater.as_str()), ("n1", "a", "a"));
+
+            // update, the insert only columns are kept
+            conn.upsert_one(doc("d1", "n1-updated"), &ctx("b")).await?;
+            let d1 = get_doc(&conn, "d1").await?.unwrap();
+            assert_eq!((d1.name.as_str(), d1.owner.as_str(), d1.updater.as_str()), ("n1-updated", "a", "b"));
+
+            // many
+            let result = conn.upsert_many(vec![doc("d1", "n1-many"), doc("d2", "n2"), doc("d3", "n3")], &ctx("c")).await?;
+            assert_eq!(result.rows_affected, 3);
+            assert_eq!(conn.count(&select).await?, 3);
+            assert_eq!(get_doc(&conn, "d1").await?.unwrap().name, "n1-many");
+            assert_eq!(get_doc(&conn, "d3").await?.unwrap().owner, "c");
+            assert_eq!(conn.upsert_many(Vec::<doc::ActiveModel>::new(), &ctx("c")).await?.rows_affected, 0);
+
+            // the models must set the same columns
+            let error = conn
+                .upsert_many(
+                    vec![
+                        doc("d4", "n4"),
+                        doc::ActiveModel {
+                            id: Set("d5".to_string()),
+                            ..Default::default()
+                        },
+                    ],
+                    &ctx("c"),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, "400");
+            assert!(get_doc(&conn, "d4").await?.is_none());
+
+            // chunks
+            let models = (0..10000).map(|i| doc(&format!("batch{i}"), "batch")).collect::<Vec<_>>();
+            assert_eq!(conn.upsert_many(models, &ctx("d")).await?.rows_affected, 10000);
+            assert_eq!(conn.count(&select).await?, 10003);
+
+            // in the current transaction
+            let mut tx_conn = client.conn();
+            tx_conn.begin().await?;
+            tx_conn.upsert_one(doc("d6", "n6"), &ctx("e")).await?;
+            assert!(get_doc(&tx_conn, "d6").await?.is_some());
+            tx_conn.rollback().await?;
+            assert!(get_doc(&conn, "d6").await?.is_none());
+
+            Ok(())
+        })
+        .await
+}
+
+pub mod doc {
+    use tardis::db::sea_orm;
+    use tardis::db::sea_orm::*;
+    use tardis::{TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation};
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, TardisCreateEntity, TardisEmptyBehavior, TardisEmptyRelation)]
+    #[sea_orm(table_name = "test_upsert_doc")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub name: String,
+        #[fill_ctx]
+        pub owner: String,
+        #[fill_ctx(insert_only = false)]
+        pub updater: String,
+    }
+}